@@ -0,0 +1,23 @@
+//! 构建脚本：注入构建时的 git commit 和目标平台，供 --version 输出
+
+use std::process::Command;
+
+fn main() {
+    // 没有 git 或不在 git 仓库中构建时降级为 unknown
+    let commit = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|commit| commit.trim().to_string())
+        .filter(|commit| !commit.is_empty())
+        .unwrap_or_else(|| "unknown".to_string());
+
+    let target = std::env::var("TARGET").unwrap_or_else(|_| "unknown".to_string());
+
+    println!("cargo:rustc-env=WISH115_GIT_COMMIT={}", commit);
+    println!("cargo:rustc-env=WISH115_BUILD_TARGET={}", target);
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/refs/heads");
+}
@@ -0,0 +1,202 @@
+//! 许愿账号配置
+//!
+//! `wish_cookies` 中的每一项既可以直接写 cookie 字符串，也可以写成带账号级选项的对象：
+//!
+//! ```yaml
+//! wish_cookies:
+//!   - "UID=...; CID=...; SEID=..."
+//!   - cookie: "UID=...; CID=...; SEID=..."
+//!     adopt_to_cid: "2345678901234567890"
+//!     tags: [vip]
+//!     overrides:
+//!       aid_cooldown: 120
+//!       steps: { adopt: false }
+//! ```
+//!
+//! 账号较多时也可以维护在 CSV 文件中（`accounts_csv`），列为 cookie, content, reward_space, name, tags，
+//! 除 cookie 外都可以省略或留空
+
+use crate::secret::Secret;
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fs;
+use tracing::warn;
+
+/// 单个许愿账号的配置
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AccountConfig {
+    pub cookie: Secret,
+    /// 采纳助力时奖励存入的目录 cid，优先于全局配置
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub adopt_to_cid: Option<String>,
+    /// 账号备注名，用于日志和报告
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// 该账号固定使用的许愿内容，优先于 wish_contents
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    /// 该账号固定使用的奖励空间，优先于 reward_space 策略
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reward_space: Option<u32>,
+    /// 账号标签，用于 --group 只处理某一组账号
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// 只对该账号生效的配置覆盖，结构与全局配置相同，只需写出要覆盖的字段
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub overrides: Option<serde_yaml::Value>,
+}
+
+impl AccountConfig {
+    pub fn new(cookie: impl Into<Secret>) -> Self {
+        Self {
+            cookie: cookie.into(),
+            ..Default::default()
+        }
+    }
+
+    /// 是否带有给定标签中的任意一个，不区分大小写
+    pub fn has_any_tag(&self, tags: &[String]) -> bool {
+        self.tags
+            .iter()
+            .any(|tag| tags.iter().any(|wanted| wanted.trim().eq_ignore_ascii_case(tag.trim())))
+    }
+
+    /// 是否只配置了 cookie，没有任何账号级选项
+    fn is_cookie_only(&self) -> bool {
+        *self == Self::new(self.cookie.clone())
+    }
+}
+
+/// 配置文件中的一项账号配置
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum AccountEntry {
+    Cookie(Secret),
+    Detailed(AccountConfig),
+}
+
+/// 反序列化账号列表，兼容纯字符串和对象两种写法
+pub fn deserialize_accounts<'de, D>(deserializer: D) -> Result<Vec<AccountConfig>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let entries = Vec::<AccountEntry>::deserialize(deserializer)?;
+    Ok(entries
+        .into_iter()
+        .map(|entry| match entry {
+            AccountEntry::Cookie(cookie) => AccountConfig::new(cookie),
+            AccountEntry::Detailed(account) => account,
+        })
+        .collect())
+}
+
+/// 序列化账号列表，没有账号级选项的账号写成纯字符串
+pub fn serialize_accounts<S>(accounts: &[AccountConfig], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    let entries: Vec<AccountEntry> = accounts
+        .iter()
+        .map(|account| {
+            if account.is_cookie_only() {
+                AccountEntry::Cookie(account.cookie.clone())
+            } else {
+                AccountEntry::Detailed(account.clone())
+            }
+        })
+        .collect();
+    entries.serialize(serializer)
+}
+
+/// 从 CSV 文件读取账号列表
+///
+/// 第一行为表头，按列名识别 cookie, content, reward_space, name, tags（不区分大小写、顺序任意），
+/// 只有 cookie 列是必需的；cookie 为空的行跳过，其余列为空时使用全局配置。多个标签用分号分隔
+pub fn load_csv(path: &str) -> Result<Vec<AccountConfig>> {
+    let text = fs::read_to_string(path).with_context(|| format!("读取账号 CSV 文件 {} 失败", path))?;
+    let mut rows = parse_csv(text.trim_start_matches('\u{feff}')).into_iter();
+
+    let Some(header) = rows.next() else {
+        return Ok(Vec::new());
+    };
+    let header: Vec<String> = header.iter().map(|name| name.trim().to_lowercase()).collect();
+    let column = |name: &str| header.iter().position(|h| h == name);
+    let Some(cookie_column) = column("cookie") else {
+        bail!("账号 CSV 文件 {} 缺少 cookie 列", path);
+    };
+    let (content_column, reward_column, name_column) = (column("content"), column("reward_space"), column("name"));
+    let tags_column = column("tags");
+    for unknown in header.iter().filter(|h| !["cookie", "content", "reward_space", "name", "tags"].contains(&h.as_str())) {
+        warn!("账号 CSV 文件 {} 中的列 {} 无法识别，将被忽略", path, unknown);
+    }
+
+    let mut accounts = Vec::new();
+    for (line, row) in rows.enumerate() {
+        let cell = |column: Option<usize>| {
+            column
+                .and_then(|index| row.get(index))
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+        let Some(cookie) = cell(Some(cookie_column)) else {
+            continue;
+        };
+        let reward_space = match cell(reward_column) {
+            Some(value) => Some(value.parse().with_context(|| {
+                format!("账号 CSV 文件 {} 第 {} 行的 reward_space \"{}\" 不是正整数", path, line + 2, value)
+            })?),
+            None => None,
+        };
+
+        accounts.push(AccountConfig {
+            cookie: Secret::new(cookie),
+            name: cell(name_column),
+            content: cell(content_column),
+            reward_space,
+            tags: cell(tags_column)
+                .map(|tags| tags.split(';').map(str::trim).filter(|tag| !tag.is_empty()).map(str::to_string).collect())
+                .unwrap_or_default(),
+            ..Default::default()
+        });
+    }
+    Ok(accounts)
+}
+
+/// 按 RFC 4180 解析 CSV：字段可用引号包裹，引号内可以有逗号和换行，两个引号表示一个引号
+fn parse_csv(text: &str) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' if quoted => quoted = false,
+            '"' if field.is_empty() => quoted = true,
+            ',' if !quoted => row.push(std::mem::take(&mut field)),
+            '\r' if !quoted => {}
+            '\n' if !quoted => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            _ => field.push(c),
+        }
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+
+    rows.retain(|row| row.iter().any(|field| !field.trim().is_empty()));
+    rows
+}
+
+/// 校验目录 cid 格式，115 的目录 cid 为纯数字，根目录为 "0"
+pub fn is_valid_cid(cid: &str) -> bool {
+    !cid.is_empty() && cid.chars().all(|c| c.is_ascii_digit())
+}
@@ -0,0 +1,69 @@
+//! 以愿望为中心的助力关联视图
+//!
+//! 处理结果按许愿、助力、采纳分别记录，不便看出某个愿望最终被谁助力、采纳了哪个助力。
+//! 一轮结束后查询各愿望的助力列表，整理为“愿望 X → 助力者 [A, B] → 已采纳 A”的形式输出
+
+use std::fmt;
+
+/// 愿望的一条助力记录
+#[derive(Debug, Clone)]
+pub struct Aider {
+    /// 助力记录 ID，采纳时使用
+    pub aid_id: String,
+    /// 助力者的用户名，接口没有返回时为空
+    pub name: String,
+}
+
+impl fmt::Display for Aider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.name.is_empty() {
+            write!(f, "助力 {}", self.aid_id)
+        } else {
+            write!(f, "{}", self.name)
+        }
+    }
+}
+
+/// 一个愿望及其助力者和采纳情况
+#[derive(Debug, Clone)]
+pub struct WishAids {
+    pub code: String,
+    pub aiders: Vec<Aider>,
+    /// 今天由本程序采纳的助力记录 ID
+    pub adopted: Option<String>,
+    /// 助力列表获取失败的原因
+    pub error: Option<String>,
+}
+
+impl fmt::Display for WishAids {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "愿望 {} → ", self.code)?;
+        if let Some(error) = &self.error {
+            return write!(f, "助力列表获取失败：{}", error);
+        }
+        if self.aiders.is_empty() {
+            return write!(f, "暂无助力");
+        }
+        let aiders: Vec<String> = self.aiders.iter().map(Aider::to_string).collect();
+        write!(f, "助力者 [{}] → ", aiders.join(", "))?;
+        match &self.adopted {
+            Some(aid_id) => match self.aiders.iter().find(|aider| &aider.aid_id == aid_id) {
+                Some(aider) => write!(f, "已采纳 {}", aider),
+                None => write!(f, "已采纳助力 {}", aid_id),
+            },
+            None => write!(f, "未采纳"),
+        }
+    }
+}
+
+/// 渲染一个账号所有愿望的关联视图，每个愿望一行
+pub fn render(name: &str, wishes: &[WishAids]) -> String {
+    let mut text = format!("[{}] 愿望助力情况：", name);
+    if wishes.is_empty() {
+        text.push_str("没有愿望");
+    }
+    for wish in wishes {
+        text.push_str(&format!("\n  {}", wish));
+    }
+    text
+}
@@ -0,0 +1,85 @@
+//! 许愿后的审核状态轮询
+//!
+//! 许愿后需要等愿望审核通过才能被助力。轮询愿望详情中的 audit_status，
+//! 审核通过后继续；被拒绝时放弃本账号的助力流程；长时间不出结果时按配置降级处理
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// audit_status：审核中
+const AUDIT_PENDING: i32 = 0;
+/// audit_status：审核通过
+const AUDIT_APPROVED: i32 = 1;
+
+/// 愿望的审核状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditState {
+    Pending,
+    Approved,
+    /// 审核被拒，其余未知取值都按被拒处理
+    Rejected,
+}
+
+impl AuditState {
+    pub fn from_status(audit_status: i32) -> Self {
+        match audit_status {
+            AUDIT_PENDING => AuditState::Pending,
+            AUDIT_APPROVED => AuditState::Approved,
+            _ => AuditState::Rejected,
+        }
+    }
+}
+
+impl fmt::Display for AuditState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuditState::Pending => write!(f, "审核中"),
+            AuditState::Approved => write!(f, "已通过"),
+            AuditState::Rejected => write!(f, "未通过"),
+        }
+    }
+}
+
+/// 审核轮询超时后的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OnAuditTimeout {
+    /// 放弃本账号的助力流程
+    #[default]
+    Skip,
+    /// 照常继续助力流程，由 115 决定是否拒绝
+    Proceed,
+    /// 发送通知请人工介入，并放弃本账号的助力流程
+    Notify,
+}
+
+impl fmt::Display for OnAuditTimeout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OnAuditTimeout::Skip => write!(f, "skip"),
+            OnAuditTimeout::Proceed => write!(f, "proceed"),
+            OnAuditTimeout::Notify => write!(f, "notify"),
+        }
+    }
+}
+
+/// 审核轮询的配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuditConfig {
+    /// 轮询间隔（秒）
+    pub poll_interval: u64,
+    /// 最长等待时间（秒）
+    pub timeout: u64,
+    pub on_timeout: OnAuditTimeout,
+}
+
+impl Default for AuditConfig {
+    fn default() -> Self {
+        Self {
+            poll_interval: 10,
+            timeout: 120,
+            on_timeout: OnAuditTimeout::default(),
+        }
+    }
+}
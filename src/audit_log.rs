@@ -0,0 +1,88 @@
+//! 写操作审计日志
+//!
+//! 许愿、助力、采纳这三类写操作另外记录到一份独立的审计日志，每条一行，
+//! 只含时间、账号、操作、目标愿望和结果，不与普通日志混在一起，便于审计和追溯。
+//! 审计记录通过专门的日志 target 输出，只写入审计日志的 appender
+
+use crate::report::ActionRecord;
+use serde::{Deserialize, Serialize};
+
+/// 审计记录使用的日志 target
+pub const AUDIT_TARGET: &str = "wish_115::audit";
+
+/// 审计日志的格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditLogFormat {
+    /// 制表符分隔的文本
+    #[default]
+    Text,
+    /// 每行一个 JSON 对象
+    Json,
+}
+
+/// 审计日志的配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuditLogConfig {
+    pub enabled: bool,
+    pub path: String,
+    pub format: AuditLogFormat,
+}
+
+impl Default for AuditLogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: "logs/audit.log".to_string(),
+            format: AuditLogFormat::default(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct AuditEntry<'a> {
+    time: String,
+    account: usize,
+    name: &'a str,
+    action: &'static str,
+    wish_code: &'a str,
+    result: &'static str,
+    message: &'a str,
+    reward: i64,
+}
+
+impl AuditLogConfig {
+    /// 记录一次写操作，未启用时不记录
+    pub fn record(&self, account_index: usize, name: &str, action: &ActionRecord) {
+        if !self.enabled {
+            return;
+        }
+
+        let entry = AuditEntry {
+            time: action.time.format("%Y-%m-%d %H:%M:%S").to_string(),
+            account: account_index + 1,
+            name,
+            action: action.action,
+            wish_code: &action.wish_code,
+            result: if action.success { "success" } else { "failure" },
+            message: &action.message,
+            reward: action.reward,
+        };
+        let line = match self.format {
+            AuditLogFormat::Text => format!(
+                "{}\t账号-{}\t{}\t{}\t{}\t{}\t{}\t{}",
+                entry.time,
+                entry.account,
+                entry.name,
+                entry.action,
+                if entry.wish_code.is_empty() { "-" } else { entry.wish_code },
+                entry.result,
+                entry.reward,
+                entry.message.replace(['\t', '\r', '\n'], " "),
+            ),
+            AuditLogFormat::Json => serde_json::to_string(&entry).unwrap_or_default(),
+        };
+        tracing::info!(target: AUDIT_TARGET, "{}", line);
+    }
+}
@@ -0,0 +1,235 @@
+//! 多个助愿账号之间的负载均衡
+//!
+//! 每次助力前挑选一个助愿账号，让多个助愿账号均匀分担助力请求。
+//! 今日助力额度已用完的账号不再被挑选。
+//! 同一助愿账号的相邻两次助力之间保持最小间隔，多个许愿账号、并发助力共用该间隔
+
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// 助愿账号的选择策略
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BalanceStrategy {
+    /// 选本轮已用次数最少的账号，次数相同时选靠前的
+    #[default]
+    LeastUsed,
+    /// 按权重平滑轮询
+    WeightedRoundRobin,
+}
+
+impl fmt::Display for BalanceStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BalanceStrategy::LeastUsed => write!(f, "least_used"),
+            BalanceStrategy::WeightedRoundRobin => write!(f, "weighted_round_robin"),
+        }
+    }
+}
+
+/// 负载均衡的配置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BalanceConfig {
+    pub strategy: BalanceStrategy,
+    /// 各助愿账号的权重，按 aid_cookie、aid_cookies 的顺序对应，未填的账号权重为 1
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub weights: Vec<u32>,
+}
+
+/// 挑选出的助愿账号
+pub struct AidAccount<'a> {
+    /// 账号序号，从 0 开始
+    pub index: usize,
+    pub cookie: &'a str,
+}
+
+/// 各助愿账号的使用情况
+struct BalancerState {
+    /// 本轮已用次数
+    used: Vec<u64>,
+    /// 平滑加权轮询的当前权重
+    current_weights: Vec<i64>,
+    /// 今日助力额度是否已用完
+    exhausted: Vec<bool>,
+    /// 最近一次助力（或已预约的下一次助力）的时间
+    last_aid: Vec<Option<Instant>>,
+}
+
+/// 助愿账号选择器，线程安全
+pub struct AidBalancer {
+    strategy: BalanceStrategy,
+    cookies: Vec<String>,
+    weights: Vec<i64>,
+    state: Mutex<BalancerState>,
+}
+
+impl AidBalancer {
+    pub fn new(cookies: Vec<String>, config: &BalanceConfig) -> Self {
+        let weights = (0..cookies.len())
+            .map(|i| i64::from(config.weights.get(i).copied().unwrap_or(1)))
+            .collect();
+        let state = BalancerState {
+            used: vec![0; cookies.len()],
+            current_weights: vec![0; cookies.len()],
+            exhausted: vec![false; cookies.len()],
+            last_aid: vec![None; cookies.len()],
+        };
+
+        Self {
+            strategy: config.strategy,
+            cookies,
+            weights,
+            state: Mutex::new(state),
+        }
+    }
+
+    /// 助愿账号数量
+    pub fn len(&self) -> usize {
+        self.cookies.len()
+    }
+
+    /// 按策略挑选一个助愿账号，没有配置助愿账号或额度都已用完时返回 None
+    ///
+    /// 挑选不计入使用次数，助力请求真正发送前再调用 `record_use`，
+    /// 查询详情失败、不能给自己助力等提前放弃的情况不会让均衡出现偏差
+    pub fn pick(&self) -> Option<AidAccount<'_>> {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let available = self.available(&state);
+        let index = match self.strategy {
+            BalanceStrategy::LeastUsed => available.iter().copied().min_by_key(|&i| state.used[i])?,
+            BalanceStrategy::WeightedRoundRobin => available
+                .iter()
+                .copied()
+                .max_by_key(|&i| (state.current_weights[i] + self.weights[i], std::cmp::Reverse(i)))?,
+        };
+
+        Some(AidAccount {
+            index,
+            cookie: &self.cookies[index],
+        })
+    }
+
+    /// 记录助愿账号用于一次助力，更新使用次数和轮询权重
+    pub fn record_use(&self, index: usize) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if index >= self.cookies.len() {
+            return;
+        }
+        if self.strategy == BalanceStrategy::WeightedRoundRobin {
+            let available = self.available(&state);
+            let total: i64 = available.iter().map(|&i| self.weights[i]).sum();
+            for &i in &available {
+                state.current_weights[i] += self.weights[i];
+            }
+            state.current_weights[index] -= total;
+        }
+        state.used[index] += 1;
+    }
+
+    /// 额度未用完的助愿账号序号
+    fn available(&self, state: &BalancerState) -> Vec<usize> {
+        (0..self.cookies.len()).filter(|&i| !state.exhausted[i]).collect()
+    }
+
+    /// 为助愿账号预约一次助力，返回需要等待的时间
+    ///
+    /// 预约时间不早于该账号上一次预约的时间加上 cooldown，调用方等待返回的时间后再发送助力请求
+    pub fn reserve(&self, index: usize, cooldown: Duration) -> Duration {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        let Some(last) = state.last_aid.get_mut(index) else {
+            return Duration::ZERO;
+        };
+
+        let at = match *last {
+            Some(previous) => (previous + cooldown).max(now),
+            None => now,
+        };
+        *last = Some(at);
+        at - now
+    }
+
+    /// 标记助愿账号今日额度已用完，返回是否为首次标记
+    pub fn mark_exhausted(&self, index: usize) -> bool {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        match state.exhausted.get_mut(index) {
+            Some(exhausted) if !*exhausted => {
+                *exhausted = true;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// 是否所有助愿账号的额度都已用完
+    pub fn all_exhausted(&self) -> bool {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        !state.exhausted.is_empty() && state.exhausted.iter().all(|&exhausted| exhausted)
+    }
+
+    /// 额度已用完的助愿账号序号
+    pub fn exhausted(&self) -> Vec<usize> {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        (0..state.exhausted.len()).filter(|&i| state.exhausted[i]).collect()
+    }
+
+    /// 各助愿账号本轮已用次数
+    pub fn usage(&self) -> Vec<u64> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner()).used.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn balancer(accounts: usize, config: &BalanceConfig) -> AidBalancer {
+        AidBalancer::new((0..accounts).map(|i| format!("UID={}", i)).collect(), config)
+    }
+
+    /// 挑选并记录使用，返回挑中的序号
+    fn pick_and_use(balancer: &AidBalancer) -> usize {
+        let index = balancer.pick().unwrap().index;
+        balancer.record_use(index);
+        index
+    }
+
+    #[test]
+    fn pick_without_use_does_not_shift_least_used() {
+        let balancer = balancer(2, &BalanceConfig::default());
+        assert_eq!(balancer.pick().unwrap().index, 0);
+        assert_eq!(balancer.pick().unwrap().index, 0);
+        assert_eq!(balancer.usage(), vec![0, 0]);
+
+        assert_eq!(pick_and_use(&balancer), 0);
+        assert_eq!(pick_and_use(&balancer), 1);
+        assert_eq!(pick_and_use(&balancer), 0);
+        assert_eq!(balancer.usage(), vec![2, 1]);
+    }
+
+    #[test]
+    fn weighted_round_robin_follows_weights() {
+        let config = BalanceConfig { strategy: BalanceStrategy::WeightedRoundRobin, weights: vec![2, 1] };
+        let balancer = balancer(2, &config);
+        // 只挑选不发送时轮询位置不变
+        assert_eq!(balancer.pick().unwrap().index, 0);
+        let picks: Vec<_> = (0..6).map(|_| pick_and_use(&balancer)).collect();
+        assert_eq!(picks, vec![0, 1, 0, 0, 1, 0]);
+    }
+
+    #[test]
+    fn exhausted_accounts_are_skipped() {
+        let balancer = balancer(2, &BalanceConfig::default());
+        assert!(balancer.mark_exhausted(0));
+        assert!(!balancer.mark_exhausted(0));
+        assert_eq!(pick_and_use(&balancer), 1);
+        assert!(balancer.mark_exhausted(1));
+        assert!(balancer.all_exhausted());
+        assert!(balancer.pick().is_none());
+    }
+}
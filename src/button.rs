@@ -0,0 +1,86 @@
+//! 愿望详情中的 button 字段
+//!
+//! button 表示查询者当前对该愿望可执行的操作，随查询的账号不同而不同：助愿账号看到的是能否助力，
+//! 许愿账号看到的是能否采纳。取值按活动页面愿望卡片上的按钮状态推定，尚未逐一实测确认；
+//! 未列出的取值按未知处理，只记录日志、不据此跳过操作，遇到新取值时补充到这里。
+//! 配置中的 skip_aid_buttons 可以额外指定不助力的取值，用于程序更新前临时应对
+
+use std::fmt;
+
+/// button：没有可执行的操作（已助力过、活动已结束等）
+const BUTTON_NONE: i32 = 0;
+/// button：可以助力
+const BUTTON_AID: i32 = 1;
+/// button：有待采纳的助力，许愿者可以采纳
+const BUTTON_ADOPT: i32 = 2;
+/// button：助力已被采纳，愿望已完成
+const BUTTON_DONE: i32 = 3;
+
+/// 查询者对愿望可执行的操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DesireButton {
+    None,
+    Aid,
+    Adopt,
+    Done,
+    /// 未整理的取值
+    Unknown(i32),
+}
+
+impl DesireButton {
+    pub fn from_value(button: i32) -> Self {
+        match button {
+            BUTTON_NONE => DesireButton::None,
+            BUTTON_AID => DesireButton::Aid,
+            BUTTON_ADOPT => DesireButton::Adopt,
+            BUTTON_DONE => DesireButton::Done,
+            other => DesireButton::Unknown(other),
+        }
+    }
+
+    /// 是否可以助力，未知取值按可以处理，交给服务端判断
+    pub fn can_aid(self) -> bool {
+        matches!(self, DesireButton::Aid | DesireButton::Unknown(_))
+    }
+
+    /// 是否可以采纳，未知取值按可以处理，交给服务端判断
+    pub fn can_adopt(self) -> bool {
+        matches!(self, DesireButton::Adopt | DesireButton::Unknown(_))
+    }
+}
+
+impl fmt::Display for DesireButton {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DesireButton::None => write!(f, "无可执行操作"),
+            DesireButton::Aid => write!(f, "可助力"),
+            DesireButton::Adopt => write!(f, "可采纳"),
+            DesireButton::Done => write!(f, "已采纳"),
+            DesireButton::Unknown(button) => write!(f, "未知取值 {}", button),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_values_drive_decisions() {
+        assert!(DesireButton::from_value(BUTTON_AID).can_aid());
+        assert!(!DesireButton::from_value(BUTTON_AID).can_adopt());
+        assert!(DesireButton::from_value(BUTTON_ADOPT).can_adopt());
+        assert!(!DesireButton::from_value(BUTTON_ADOPT).can_aid());
+        for button in [BUTTON_NONE, BUTTON_DONE] {
+            assert!(!DesireButton::from_value(button).can_aid());
+            assert!(!DesireButton::from_value(button).can_adopt());
+        }
+    }
+
+    #[test]
+    fn unknown_values_do_not_block() {
+        let button = DesireButton::from_value(9);
+        assert_eq!(button, DesireButton::Unknown(9));
+        assert!(button.can_aid() && button.can_adopt());
+    }
+}
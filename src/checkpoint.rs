@@ -0,0 +1,133 @@
+//! 账号处理进度的断点记录
+//!
+//! 处理每个账号前后把进度写入状态文件，进程中途崩溃或被杀后重新运行时，
+//! 如果断点是当天、且账号列表没有变化，则跳过已处理完的账号从断点继续。
+//! 正在处理中的账号会从头重新处理，其中已完成的操作由幂等记录跳过。
+//! 一轮正常结束后删除断点文件
+
+use anyhow::Result;
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::{fs, path::PathBuf};
+use tracing::{info, warn};
+
+/// 状态文件名
+pub const CHECKPOINT_FILE: &str = "checkpoint.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct State {
+    /// 断点所属的日期，跨天后失效
+    date: String,
+    /// 账号列表的指纹，增删或调整账号顺序后失效
+    accounts: String,
+    /// 已处理完的账号数，即下一个要处理的账号序号
+    next: usize,
+    /// 正在处理的账号序号
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    processing: Option<usize>,
+}
+
+/// 本轮的断点记录，关闭时不读写文件
+#[derive(Debug)]
+pub struct Checkpoint {
+    path: PathBuf,
+    enabled: bool,
+    state: State,
+}
+
+impl Checkpoint {
+    /// 从状态目录加载断点，`accounts` 为各账号 cookie 指纹按顺序拼接的结果。
+    /// 文件不存在、损坏、不是当天或账号列表已变化时从第一个账号开始
+    pub fn load(state_dir: &str, enabled: bool, accounts: String) -> Self {
+        let path = PathBuf::from(state_dir).join(CHECKPOINT_FILE);
+        let fresh = State {
+            date: Local::now().format("%Y-%m-%d").to_string(),
+            accounts,
+            next: 0,
+            processing: None,
+        };
+
+        let text = match fs::read_to_string(&path) {
+            Ok(text) if enabled => text,
+            _ => return Self { path, enabled, state: fresh },
+        };
+
+        let state = match serde_json::from_str::<State>(&text) {
+            Err(e) => {
+                warn!("断点文件 {} 已损坏，从第一个账号开始处理: {}", path.display(), e);
+                fresh
+            }
+            Ok(last) if last.date != fresh.date => {
+                info!("断点文件是 {} 的，已过期，从第一个账号开始处理", last.date);
+                fresh
+            }
+            Ok(last) if last.accounts != fresh.accounts => {
+                info!("账号列表与断点记录时不同，从第一个账号开始处理");
+                fresh
+            }
+            Ok(last) => {
+                match last.processing {
+                    Some(index) => warn!("上次运行在处理账号-{} 时中断，从该账号继续", index + 1),
+                    None => info!("检测到今天的断点，已处理 {} 个账号，从账号-{} 继续", last.next, last.next + 1),
+                }
+                State {
+                    next: last.processing.unwrap_or(last.next),
+                    processing: None,
+                    ..last
+                }
+            }
+        };
+
+        Self { path, enabled, state }
+    }
+
+    /// 下一个要处理的账号序号，之前的账号在断点前已处理完
+    pub fn next(&self) -> usize {
+        self.state.next
+    }
+
+    /// 开始处理某个账号
+    pub fn begin(&mut self, index: usize) {
+        self.state.processing = Some(index);
+        self.persist();
+    }
+
+    /// 某个账号处理完成（包括因禁用而跳过）
+    pub fn finish(&mut self, index: usize) {
+        self.state.processing = None;
+        self.state.next = index + 1;
+        self.persist();
+    }
+
+    /// 本轮正常结束，删除断点文件
+    pub fn clear(&self) {
+        if !self.enabled {
+            return;
+        }
+        if let Err(e) = fs::remove_file(&self.path) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                warn!("删除断点文件 {} 失败: {}", self.path.display(), e);
+            }
+        }
+    }
+
+    fn persist(&self) {
+        if !self.enabled {
+            return;
+        }
+        if let Err(e) = self.save() {
+            warn!("保存断点文件 {} 失败: {}", self.path.display(), e);
+        }
+    }
+
+    /// 先写临时文件再重命名，避免写到一半崩溃留下损坏的断点
+    fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let tmp = self.path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_string_pretty(&self.state)?)?;
+        fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+}
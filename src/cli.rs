@@ -0,0 +1,186 @@
+//! 命令行参数解析
+//!
+//! 参数很少，不引入 clap，手动解析即可
+
+use crate::curl::CookieTarget;
+use anyhow::{bail, Result};
+
+/// 命令行参数
+#[derive(Debug, Default)]
+pub struct CliArgs {
+    /// 交互式填写 cookie 并生成配置文件
+    pub setup: bool,
+    /// 把明文配置文件加密保存
+    pub encrypt_config: bool,
+    /// 清除账号失败状态，重新启用被自动禁用的账号
+    pub reset_disabled: bool,
+    /// 从粘贴的 curl 命令或 header 中提取 cookie 写入配置
+    pub parse_curl: Option<CookieTarget>,
+    /// 只打印各账号即将提交的许愿请求，不真正提交
+    pub preview_wish: bool,
+    /// 把配置文件权限收紧为 600
+    pub fix_perms: bool,
+    /// 配置文件路径
+    pub config: Option<String>,
+    /// 列出各配置项的默认值和生效值
+    pub show_defaults: bool,
+    /// 命令行传入的许愿账号 cookie，可重复，替换配置文件中的 wish_cookies
+    pub wish_cookies: Vec<String>,
+    /// 命令行传入的助愿账号 cookie，替换配置文件中的 aid_cookie
+    pub aid_cookie: Option<String>,
+    /// 不读取配置文件，只用命令行传入的 cookie 和默认配置运行一次
+    pub once: bool,
+    /// 账号整体处理失败时写入诊断包
+    pub diag_on_account_failure: bool,
+    /// 只读地导出所有账号的愿望状态
+    pub status: bool,
+    /// 探测各活动接口的可用性
+    pub probe: bool,
+    /// probe 时不发送写接口的探测请求
+    pub dry_run: bool,
+    /// status、probe 的输出文件，不填则输出到控制台
+    pub output: Option<String>,
+    /// 只处理带有这些标签之一的账号
+    pub tags: Vec<String>,
+    /// 覆盖配置文件中的 time_scale
+    pub time_scale: Option<f64>,
+}
+
+impl CliArgs {
+    /// 解析进程的命令行参数
+    pub fn parse() -> Result<Self> {
+        Self::parse_from(std::env::args().skip(1))
+    }
+
+    fn parse_from(args: impl IntoIterator<Item = String>) -> Result<Self> {
+        let mut cli = CliArgs::default();
+
+        let mut args = args.into_iter().peekable();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--setup" => cli.setup = true,
+                "--encrypt-config" => cli.encrypt_config = true,
+                "--reset-disabled" => cli.reset_disabled = true,
+                "--preview-wish" => cli.preview_wish = true,
+                "--fix-perms" => cli.fix_perms = true,
+                "--show-defaults" => cli.show_defaults = true,
+                "--once" => cli.once = true,
+                "status" => cli.status = true,
+                "probe" => cli.probe = true,
+                "--dry-run" => cli.dry_run = true,
+                "-o" | "--output" => match args.next() {
+                    Some(path) => cli.output = Some(path),
+                    None => bail!("--output 需要指定输出文件路径"),
+                },
+                "--diag-on-account-failure" => cli.diag_on_account_failure = true,
+                "-c" | "--config" => match args.next() {
+                    Some(path) => cli.config = Some(path),
+                    None => bail!("--config 需要指定配置文件路径"),
+                },
+                "--wish-cookie" => match args.next() {
+                    Some(cookie) => cli.wish_cookies.push(cookie),
+                    None => bail!("--wish-cookie 需要指定 cookie"),
+                },
+                "--group" => match args.next() {
+                    Some(tag) => cli.tags.push(tag),
+                    None => bail!("--group 需要指定标签"),
+                },
+                "--tags" => match args.next() {
+                    Some(tags) => cli.tags.extend(
+                        tags.split(',').map(str::trim).filter(|tag| !tag.is_empty()).map(str::to_string),
+                    ),
+                    None => bail!("--tags 需要指定以逗号分隔的标签"),
+                },
+                "--time-scale" => match args.next().map(|value| value.parse::<f64>()) {
+                    Some(Ok(scale)) => cli.time_scale = Some(scale),
+                    Some(Err(_)) => bail!("--time-scale 需要一个数字，例如 0.5"),
+                    None => bail!("--time-scale 需要指定系数"),
+                },
+                "--aid-cookie" => match args.next() {
+                    Some(cookie) => cli.aid_cookie = Some(cookie),
+                    None => bail!("--aid-cookie 需要指定 cookie"),
+                },
+                "--parse-curl" => {
+                    let target = args.peek().and_then(|value| CookieTarget::parse(value));
+                    if target.is_some() {
+                        args.next();
+                    }
+                    cli.parse_curl = Some(target.unwrap_or(CookieTarget::Wish));
+                }
+                "-V" | "--version" => {
+                    print_version();
+                    std::process::exit(0);
+                }
+                "-h" | "--help" => {
+                    print_help();
+                    std::process::exit(0);
+                }
+                other => bail!("未知参数: {}，使用 --help 查看用法", other),
+            }
+        }
+
+        if cli.dry_run && !cli.probe {
+            bail!("--dry-run 只能用于 probe 命令");
+        }
+
+        if cli.once {
+            if cli.config.is_some() {
+                bail!("--once 不读取配置文件，不能与 --config 同时使用");
+            }
+            if cli.wish_cookies.is_empty() || cli.aid_cookie.is_none() {
+                bail!("--once 需要同时通过 --wish-cookie 和 --aid-cookie 传入 cookie");
+            }
+        }
+
+        Ok(cli)
+    }
+}
+
+fn print_help() {
+    println!("用法: wish_115 [选项]");
+    println!("      wish_115 status [-o <文件>]");
+    println!("      wish_115 probe [--dry-run] [-o <文件>]");
+    println!();
+    println!("命令:");
+    println!("  status             只读地列出所有账号的愿望及其审核状态、助力数、奖励、是否已采纳，");
+    println!("                     不做任何写操作；-o, --output <文件> 写入文件而不是输出到控制台");
+    println!("  probe              用第一个许愿账号逐个探测 my_desire、get_desire_info、wish、aid_desire、adopt 接口，");
+    println!("                     报告 HTTP 状态、是否返回合法 JSON 和业务 code；写接口只发送缺少必填参数、");
+    println!("                     会被服务端拒绝的请求，--dry-run 时不发送写接口；-o 同 status");
+    println!();
+    println!("选项:");
+    println!("  -c, --config <路径>");
+    println!("                     使用指定的配置文件（默认为当前目录下的 config.yaml），");
+    println!("                     配置中的相对路径相对于配置文件所在目录解析");
+    println!("  --setup            交互式填写 cookie 并写入 config.yaml");
+    println!("  --encrypt-config   用主密码把 config.yaml 加密保存为 config.yaml.enc");
+    println!("  --reset-disabled   重新启用因连续失败被自动禁用的账号");
+    println!("  --fix-perms        把配置文件权限收紧为 600（仅所有者可读写）");
+    println!("  --preview-wish     打印各账号即将提交的许愿请求（URL、header、表单），不真正提交");
+    println!("  --parse-curl [aid|wish]");
+    println!("                     从粘贴的 curl 命令或请求 header 中提取 cookie 写入配置，");
+    println!("                     aid 写入助愿账号，wish（默认）新增或更新许愿账号");
+    println!("  --wish-cookie <cookie>");
+    println!("                     许愿账号 cookie，可重复指定多个，替换配置文件中的 wish_cookies");
+    println!("  --aid-cookie <cookie>");
+    println!("                     助愿账号 cookie，替换配置文件中的 aid_cookie");
+    println!("  --group <标签>     只处理带有该标签的账号，可重复指定多个，带有其中任一标签即处理");
+    println!("  --tags <标签1,标签2>");
+    println!("                     同 --group，以逗号分隔一次指定多个标签");
+    println!("  --once             不读取配置文件，只用 --wish-cookie、--aid-cookie 和默认配置运行一次，");
+    println!("                     不能与 --config 同时使用");
+    println!("  --time-scale <系数>");
+    println!("                     所有等待时间乘以该系数，0 表示跳过全部等待，覆盖配置中的 time_scale");
+    println!("  --diag-on-account-failure");
+    println!("                     账号整体处理失败时，把该账号本轮的请求响应（已隐藏 cookie）、时间线、");
+    println!("                     日志和配置摘要写入 diag/account_N_<run_id>/，便于反馈问题");
+    println!("  --show-defaults    列出各配置项的默认值和生效值（标出被配置覆盖的项）以及内置的等待时间等");
+    println!("  -V, --version      显示版本和构建信息");
+    println!("  -h, --help         显示帮助信息");
+}
+
+fn print_version() {
+    println!("wish_115 {}", env!("CARGO_PKG_VERSION"));
+    println!("git commit: {}", env!("WISH115_GIT_COMMIT"));
+    println!("目标平台: {}", env!("WISH115_BUILD_TARGET"));
+}
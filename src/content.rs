@@ -0,0 +1,127 @@
+//! 许愿内容管理
+//!
+//! 负责从候选内容池中为各账号挑选许愿内容，并检查内容多样性，
+//! 避免多个账号使用完全相同的文案而被关联
+
+use chrono::Local;
+use tracing::{info, warn};
+use rand::{seq::SliceRandom, Rng};
+use std::{collections::HashSet, fs, sync::Mutex};
+
+/// 默认许愿内容
+pub const DEFAULT_WISH_CONTENT: &str = "gogogo";
+
+/// 默认助力内容
+pub const DEFAULT_AID_CONTENT: &str = "gogogo";
+
+/// 内容文件中分隔多行候选内容的行
+const ENTRY_SEPARATOR: &str = "---";
+
+/// 从模板文件读取候选内容，文件不存在、读取失败或没有内容时告警并返回 None
+///
+/// 文件中有单独一行的 `---` 时按它分隔，每段为一条候选（可以多行）；
+/// 否则每个非空行为一条候选
+pub fn load_file(path: &str) -> Option<Vec<String>> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) => {
+            warn!("读取内容文件 {} 失败，使用默认内容: {}", path, e);
+            return None;
+        }
+    };
+
+    let entries: Vec<String> = if text.lines().any(|line| line.trim() == ENTRY_SEPARATOR) {
+        text.lines()
+            .collect::<Vec<_>>()
+            .split(|line| line.trim() == ENTRY_SEPARATOR)
+            .map(|lines| lines.join("\n").trim().to_string())
+            .filter(|entry| !entry.is_empty())
+            .collect()
+    } else {
+        text.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect()
+    };
+
+    if entries.is_empty() {
+        warn!("内容文件 {} 中没有内容，使用默认内容", path);
+        return None;
+    }
+
+    info!("从内容文件 {} 读取到 {} 条候选内容", path, entries.len());
+    Some(entries)
+}
+
+/// 替换内容中的占位符：`{date}` 为当天日期，`{account}` 为许愿账号序号（从 1 开始）
+pub fn fill_placeholders(content: &str, account_index: usize) -> String {
+    content
+        .replace("{date}", &Local::now().format("%Y-%m-%d").to_string())
+        .replace("{account}", &(account_index + 1).to_string())
+}
+
+/// 许愿内容候选池
+pub struct ContentPool {
+    candidates: Vec<String>,
+    /// 是否在同一轮内不放回抽取
+    distinct: bool,
+    /// 本轮尚未使用的候选内容
+    remaining: Mutex<Vec<String>>,
+}
+
+impl ContentPool {
+    pub fn new(candidates: Vec<String>, distinct: bool) -> Self {
+        let candidates = if candidates.is_empty() {
+            vec![DEFAULT_WISH_CONTENT.to_string()]
+        } else {
+            candidates
+        };
+
+        Self {
+            remaining: Mutex::new(candidates.clone()),
+            candidates,
+            distinct,
+        }
+    }
+
+    /// 挑选一条许愿内容
+    ///
+    /// 不放回模式下，候选内容全部用过一遍后才会重新开始重复使用
+    pub fn pick(&self) -> String {
+        let mut rng = rand::thread_rng();
+
+        if !self.distinct {
+            return self.candidates.choose(&mut rng).cloned().unwrap_or_default();
+        }
+
+        let mut remaining = self.remaining.lock().unwrap_or_else(|e| e.into_inner());
+        if remaining.is_empty() {
+            warn!("候选许愿内容已全部用过一遍，开始重复使用");
+            *remaining = self.candidates.clone();
+        }
+
+        let index = rng.gen_range(0..remaining.len());
+        remaining.swap_remove(index)
+    }
+
+    /// 检查内容多样性，候选内容少于账号数时给出告警
+    pub fn check_diversity(&self, account_count: usize) {
+        let distinct_count = self.candidates.iter().collect::<HashSet<_>>().len();
+
+        if distinct_count < self.candidates.len() {
+            warn!(
+                "许愿内容候选中存在重复项（{} 条中只有 {} 条不同），建议去重",
+                self.candidates.len(),
+                distinct_count
+            );
+        }
+
+        if account_count > 1 && distinct_count < account_count {
+            warn!(
+                "共 {} 个账号但只有 {} 条不同的许愿内容，多账号将使用相同内容，可能被关联，建议在 wish_contents 中增加内容",
+                account_count, distinct_count
+            );
+        }
+    }
+}
@@ -0,0 +1,136 @@
+//! 许愿内容中 URL、联系方式的检测
+//!
+//! 115 不允许在愿望内容中留外链或联系方式，含有这些的内容会被拒。
+//! 提交前按内置规则和自定义正则检测，命中时按配置脱敏后提交或跳过许愿
+
+use anyhow::{anyhow, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// 内置规则：(名称, 正则)
+const BUILTIN_RULES: [(&str, &str); 5] = [
+    ("网址", r"(?i)(?:https?://|www\.)[^\s，。！？、]+"),
+    ("域名", r"(?i)(?-u:\b)[a-z0-9][a-z0-9-]*\.(?:com|cn|net|org|top|xyz|cc|me|io|vip|info)(?-u:\b)"),
+    ("手机号", r"1[3-9]\d{9}"),
+    ("QQ 号", r"(?i)(?:qq|扣扣|企鹅)\s*[:：号]?\s*\d{5,11}"),
+    ("微信号", r"(?i)(?:微信|weixin|wechat|vx|wx|v信)\s*[:：号]?\s*[a-z][-_a-z0-9]{5,19}"),
+];
+
+/// 脱敏时替换命中内容的文本
+const MASK: &str = "***";
+
+/// 命中规则后的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FilterAction {
+    /// 把命中的部分替换为 ***
+    #[default]
+    Mask,
+    /// 跳过本次许愿
+    Skip,
+}
+
+impl fmt::Display for FilterAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FilterAction::Mask => write!(f, "mask"),
+            FilterAction::Skip => write!(f, "skip"),
+        }
+    }
+}
+
+/// 内容检测的配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ContentFilterConfig {
+    pub enabled: bool,
+    pub action: FilterAction,
+    /// 是否启用内置的网址、手机号、QQ、微信规则
+    pub builtin: bool,
+    /// 自定义正则
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub patterns: Vec<String>,
+}
+
+impl Default for ContentFilterConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            action: FilterAction::Mask,
+            builtin: true,
+            patterns: Vec::new(),
+        }
+    }
+}
+
+/// 检测结果
+pub enum Checked {
+    /// 未命中任何规则
+    Clean(String),
+    /// 已脱敏，附带命中的规则名
+    Masked(String, Vec<String>),
+    /// 按配置跳过，附带命中的规则名
+    Rejected(Vec<String>),
+}
+
+/// 编译好的检测规则
+pub struct ContentFilter {
+    rules: Vec<(String, Regex)>,
+    action: FilterAction,
+}
+
+impl ContentFilter {
+    /// 按配置编译规则，自定义正则无效时返回错误
+    pub fn new(config: &ContentFilterConfig) -> Result<Self> {
+        let mut rules = Vec::new();
+        if config.enabled {
+            if config.builtin {
+                for (name, pattern) in BUILTIN_RULES {
+                    rules.push((name.to_string(), Regex::new(pattern)?));
+                }
+            }
+            for pattern in &config.patterns {
+                let regex = Regex::new(pattern).map_err(|e| anyhow!("自定义正则 {} 无效: {}", pattern, e))?;
+                rules.push((format!("自定义规则 {}", pattern), regex));
+            }
+        }
+
+        Ok(Self {
+            rules,
+            action: config.action,
+        })
+    }
+
+    /// 不检测任何内容
+    pub fn disabled() -> Self {
+        Self {
+            rules: Vec::new(),
+            action: FilterAction::default(),
+        }
+    }
+
+    /// 检测内容
+    pub fn check(&self, content: &str) -> Checked {
+        let hits: Vec<String> = self
+            .rules
+            .iter()
+            .filter(|(_, regex)| regex.is_match(content))
+            .map(|(name, _)| name.clone())
+            .collect();
+        if hits.is_empty() {
+            return Checked::Clean(content.to_string());
+        }
+
+        match self.action {
+            FilterAction::Skip => Checked::Rejected(hits),
+            FilterAction::Mask => {
+                let masked = self
+                    .rules
+                    .iter()
+                    .fold(content.to_string(), |text, (_, regex)| regex.replace_all(&text, MASK).into_owned());
+                Checked::Masked(masked, hits)
+            }
+        }
+    }
+}
@@ -0,0 +1,68 @@
+//! cookie 相关的辅助函数
+
+/// 115 登录 cookie 中必须包含的字段
+pub const REQUIRED_COOKIE_FIELDS: [&str; 3] = ["UID", "CID", "SEID"];
+
+/// 返回 cookie 中缺失的必需字段
+pub fn missing_fields(cookie: &str) -> Vec<&'static str> {
+    REQUIRED_COOKIE_FIELDS
+        .iter()
+        .copied()
+        .filter(|name| field(cookie, name).is_none())
+        .collect()
+}
+
+/// 读取 cookie 中指定字段的值
+pub fn field<'a>(cookie: &'a str, name: &str) -> Option<&'a str> {
+    cookie
+        .split(';')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| key.trim() == name)
+        .map(|(_, value)| value.trim())
+}
+
+/// 从 cookie 的 UID 字段解析用户 ID
+///
+/// UID 的值形如 `12345678_A1_1700000000`，第一个下划线前是用户 ID，其余部分与登录设备和时间有关，
+/// 同一账号重新登录后会变化，因此只比较用户 ID
+pub fn user_id(cookie: &str) -> Option<&str> {
+    let uid = field(cookie, "UID")?;
+    let id = uid.split('_').next().unwrap_or(uid);
+    (!id.is_empty()).then_some(id)
+}
+
+/// 计算 cookie 的指纹，用于在状态文件中标识账号而不保存 cookie 明文
+///
+/// cookie 更新后指纹随之变化，账号会被视为新账号
+pub fn fingerprint(cookie: &str) -> String {
+    use sha2::{Digest, Sha256};
+
+    let digest = Sha256::digest(cookie.trim().as_bytes());
+    digest.iter().take(8).map(|b| format!("{:02x}", b)).collect()
+}
+
+/// 规范化用户粘贴的 cookie：去掉 `Cookie:` 前缀，去除各字段两侧的空白和多余的分号，
+/// 以 `; ` 重新拼接
+///
+/// 规范化后仍含有换行等不能出现在请求头中的字符时返回错误
+pub fn normalize(cookie: &str) -> Result<String, String> {
+    let mut cookie = cookie.trim();
+    if cookie.get(..7).is_some_and(|prefix| prefix.eq_ignore_ascii_case("cookie:")) {
+        cookie = cookie[7..].trim_start();
+    }
+
+    let normalized = cookie
+        .split(';')
+        .map(str::trim)
+        .filter(|pair| !pair.is_empty())
+        .collect::<Vec<_>>()
+        .join("; ");
+
+    if let Some(c) = normalized.chars().find(|c| c.is_control()) {
+        return Err(format!("包含非法字符 {:?}，请检查是否粘贴了多行内容", c));
+    }
+    if reqwest::header::HeaderValue::from_str(&normalized).is_err() {
+        return Err("包含不能出现在请求头中的字符".to_string());
+    }
+    Ok(normalized)
+}
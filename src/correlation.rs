@@ -0,0 +1,55 @@
+//! 单个愿望处理过程的关联 ID
+//!
+//! 一个愿望的处理跨越查询详情、助力、等待、采纳多个步骤，并发助力时各愿望的日志相互交错。
+//! 处理某个愿望期间，当前任务带有该愿望的关联 ID，文本日志在每行消息前加上 `[wish:xxxxxxxx]`，
+//! JSON 日志输出为 wish 字段，便于按关联 ID 过滤出同一愿望的全部日志。
+//!
+//! 关联 ID 由愿望 code 计算得出，同一愿望在助力和稍后的批量采纳中使用相同的 ID
+
+use log::Record;
+use log4rs::encode::{Encode, Write};
+use sha2::{Digest, Sha256};
+use std::future::Future;
+
+tokio::task_local! {
+    static WISH_TAG: String;
+}
+
+/// 愿望的关联 ID：愿望 code 哈希的前 8 位十六进制
+pub fn id_for(wish_id: &str) -> String {
+    let digest = Sha256::digest(wish_id.as_bytes());
+    digest.iter().take(4).map(|b| format!("{:02x}", b)).collect()
+}
+
+/// 在愿望的关联 ID 下执行 future，其中输出的日志都带有该 ID
+pub async fn scope<F: Future>(wish_id: &str, future: F) -> F::Output {
+    WISH_TAG.scope(id_for(wish_id), future).await
+}
+
+/// 当前任务的关联 ID，不在处理愿望时返回 None
+pub fn current() -> Option<String> {
+    WISH_TAG.try_with(String::clone).ok()
+}
+
+/// 在消息前加上关联 ID 的 encoder 包装
+#[derive(Debug)]
+pub struct CorrelatedEncoder<E>(pub E);
+
+impl<E: Encode> Encode for CorrelatedEncoder<E> {
+    fn encode(&self, w: &mut dyn Write, record: &Record) -> anyhow::Result<()> {
+        match current() {
+            Some(tag) => self.0.encode(
+                w,
+                &Record::builder()
+                    .args(format_args!("[wish:{}] {}", tag, record.args()))
+                    .level(record.level())
+                    .target(record.target())
+                    .module_path(record.module_path())
+                    .file(record.file())
+                    .line(record.line())
+                    .build(),
+            ),
+            None => self.0.encode(w, record),
+        }
+    }
+}
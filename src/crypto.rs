@@ -0,0 +1,106 @@
+//! 配置文件加密
+//!
+//! 用主密码通过 PBKDF2-SHA256 派生密钥，使用 AES-256-GCM 加密整个配置文件。
+//! 加密文件格式：魔数(8 字节) + 盐(16 字节) + nonce(12 字节) + 密文
+
+use aes_gcm::{
+    aead::{Aead, KeyInit},
+    Aes256Gcm, Nonce,
+};
+use anyhow::{anyhow, bail, Result};
+use rand::RngCore;
+use sha2::Sha256;
+
+/// 主密码所在的环境变量
+pub const PASSWORD_ENV: &str = "WISH115_CONFIG_PASSWORD";
+
+const MAGIC: &[u8; 8] = b"W115ENC1";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const PBKDF2_ROUNDS: u32 = 200_000;
+
+/// 用主密码派生 AES-256 密钥
+fn derive_key(password: &str, salt: &[u8]) -> [u8; 32] {
+    let mut key = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(password.as_bytes(), salt, PBKDF2_ROUNDS, &mut key);
+    key
+}
+
+/// 加密明文
+pub fn encrypt(plaintext: &[u8], password: &str) -> Result<Vec<u8>> {
+    let mut rng = rand::thread_rng();
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    rng.fill_bytes(&mut salt);
+    rng.fill_bytes(&mut nonce);
+
+    let cipher = Aes256Gcm::new(&derive_key(password, &salt).into());
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), plaintext)
+        .map_err(|_| anyhow!("加密失败"))?;
+
+    let mut output = Vec::with_capacity(MAGIC.len() + SALT_LEN + NONCE_LEN + ciphertext.len());
+    output.extend_from_slice(MAGIC);
+    output.extend_from_slice(&salt);
+    output.extend_from_slice(&nonce);
+    output.extend_from_slice(&ciphertext);
+    Ok(output)
+}
+
+/// 解密密文，密码错误或文件损坏时返回明确的错误
+pub fn decrypt(data: &[u8], password: &str) -> Result<Vec<u8>> {
+    let header_len = MAGIC.len() + SALT_LEN + NONCE_LEN;
+    if data.len() < header_len || &data[..MAGIC.len()] != MAGIC {
+        bail!("不是有效的加密配置文件");
+    }
+
+    let salt = &data[MAGIC.len()..MAGIC.len() + SALT_LEN];
+    let nonce = &data[MAGIC.len() + SALT_LEN..header_len];
+    let cipher = Aes256Gcm::new(&derive_key(password, salt).into());
+
+    cipher
+        .decrypt(Nonce::from_slice(nonce), &data[header_len..])
+        .map_err(|_| anyhow!("解密失败：主密码错误或加密文件已损坏"))
+}
+
+/// 获取主密码：优先读取环境变量，否则在终端中输入
+pub fn read_password(confirm: bool) -> Result<String> {
+    if let Ok(password) = std::env::var(PASSWORD_ENV) {
+        if !password.is_empty() {
+            return Ok(password);
+        }
+    }
+
+    let password = rpassword::prompt_password("请输入配置文件主密码: ")?;
+    if password.is_empty() {
+        bail!("主密码不能为空");
+    }
+
+    if confirm {
+        let again = rpassword::prompt_password("请再次输入主密码: ")?;
+        if again != password {
+            bail!("两次输入的主密码不一致");
+        }
+    }
+
+    Ok(password)
+}
+
+/// 执行 --encrypt-config：把明文配置文件加密保存
+pub fn run_encrypt_config() -> Result<()> {
+    use crate::{config_file_path, encrypted_config_file_path, AppConfig};
+
+    let plaintext = std::fs::read(config_file_path())
+        .map_err(|e| anyhow!("读取明文配置文件 {} 失败: {}", config_file_path(), e))?;
+    // 先确认明文配置本身可以正常解析，避免加密一个无法使用的配置
+    AppConfig::read_from_file(config_file_path())?;
+
+    let password = read_password(true)?;
+    std::fs::write(encrypted_config_file_path(), encrypt(&plaintext, &password)?)?;
+    crate::perms::restrict(std::path::Path::new(encrypted_config_file_path()))?;
+
+    println!("已加密保存到 {}", encrypted_config_file_path());
+    println!("请确认程序可以正常使用加密配置后，删除明文配置文件 {}。", config_file_path());
+    println!("运行时可通过环境变量 {} 提供主密码，否则会在终端中提示输入。", PASSWORD_ENV);
+    Ok(())
+}
@@ -0,0 +1,256 @@
+//! 从浏览器复制的 curl 命令或请求 header 中提取 cookie
+//!
+//! 支持以下几种复制格式：
+//! - Chrome / Edge / Firefox 的 “Copy as cURL (bash)”，cookie 可能在 `-H 'cookie: ...'` 或 `-b '...'` 中
+//! - Chrome 的 “Copy as cURL (cmd)”，使用 `^"` 转义
+//! - 开发者工具里直接复制的请求 header 块，包括名称和值分两行显示的格式
+
+use crate::{account::AccountConfig, config_file_path, cookie, secret::Secret, AppConfig};
+use anyhow::{bail, Result};
+use std::{
+    io::{self, Read},
+    path::Path,
+};
+
+/// 解析出的 cookie 写入配置的位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CookieTarget {
+    /// 作为助愿账号的 cookie
+    Aid,
+    /// 作为许愿账号的 cookie，同一用户（UID 中的用户 ID 相同）的账号会被替换
+    Wish,
+}
+
+impl CookieTarget {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "aid" => Some(CookieTarget::Aid),
+            "wish" => Some(CookieTarget::Wish),
+            _ => None,
+        }
+    }
+}
+
+/// 从标准输入读取 curl 命令或 header 块，提取 cookie 写入配置文件
+pub fn run(target: CookieTarget) -> Result<()> {
+    println!("请粘贴从浏览器复制的 curl 命令或请求 header，粘贴完成后按 Ctrl-D（Windows 下按 Ctrl-Z 再回车）结束:");
+
+    let mut input = String::new();
+    io::stdin().read_to_string(&mut input)?;
+
+    let Some(cookie) = extract_cookie(&input) else {
+        bail!("未在输入中找到 Cookie，请确认复制的是完整的请求（包含 Cookie header）");
+    };
+
+    let missing = cookie::missing_fields(&cookie);
+    if !missing.is_empty() {
+        bail!("cookie 中缺少必需字段: {}，请确认复制的是已登录状态下的请求", missing.join(", "));
+    }
+
+    let mut config = if Path::new(config_file_path()).exists() {
+        AppConfig::read_from_file(config_file_path())?
+    } else {
+        AppConfig::default()
+    };
+
+    match target {
+        CookieTarget::Aid => {
+            config.aid_cookie = Secret::new(cookie);
+            println!("已更新助愿账号的 cookie");
+        }
+        CookieTarget::Wish => {
+            // 同一账号重新登录后 UID 的后缀会变，按其中的用户 ID 匹配
+            let user_id = cookie::user_id(&cookie);
+            let existing = config
+                .wish_cookies
+                .iter_mut()
+                .find(|account| user_id.is_some() && cookie::user_id(account.cookie.expose_secret()) == user_id);
+            match existing {
+                Some(account) => {
+                    account.cookie = Secret::new(cookie);
+                    println!("已更新同一用户的许愿账号的 cookie");
+                }
+                None => {
+                    config.wish_cookies.push(AccountConfig::new(cookie));
+                    println!("已新增第 {} 个许愿账号", config.wish_cookies.len());
+                }
+            }
+        }
+    }
+
+    config.save_to_file(config_file_path())?;
+    println!("配置已写入 {}", config_file_path());
+    Ok(())
+}
+
+/// 从 curl 命令或 header 块中提取 cookie，多个 Cookie header 会合并
+pub fn extract_cookie(input: &str) -> Option<String> {
+    let input = input.trim();
+    let cookies = if input.starts_with("curl") {
+        cookies_from_curl(input)
+    } else {
+        cookies_from_headers(input)
+    };
+
+    let cookie = cookies
+        .iter()
+        .map(|c| c.trim().trim_end_matches(';').trim())
+        .filter(|c| !c.is_empty())
+        .collect::<Vec<_>>()
+        .join("; ");
+
+    (!cookie.is_empty()).then_some(cookie)
+}
+
+/// 从 curl 命令的 `-H`/`--header` 和 `-b`/`--cookie` 参数中收集 cookie
+fn cookies_from_curl(command: &str) -> Vec<String> {
+    let command = if command.contains("^\"") {
+        unescape_cmd(command)
+    } else {
+        command.to_string()
+    };
+
+    let mut cookies = Vec::new();
+    let mut args = split_shell_words(&command).into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-H" | "--header" => {
+                if let Some(value) = args.next().as_deref().and_then(cookie_header_value) {
+                    cookies.push(value.to_string());
+                }
+            }
+            // 不含 '=' 时 -b 的参数是 cookie 文件路径，忽略
+            "-b" | "--cookie" => {
+                if let Some(value) = args.next().filter(|value| value.contains('=')) {
+                    cookies.push(value);
+                }
+            }
+            _ => {}
+        }
+    }
+    cookies
+}
+
+/// 从 header 块中收集 cookie，兼容 `Cookie: ...` 单行格式和名称、值分两行的格式
+fn cookies_from_headers(headers: &str) -> Vec<String> {
+    let mut cookies = Vec::new();
+    let mut lines = headers.lines().map(str::trim).filter(|line| !line.is_empty());
+    while let Some(line) = lines.next() {
+        let name = line.trim_end_matches(':');
+        if name.eq_ignore_ascii_case("cookie") {
+            if let Some(value) = lines.next() {
+                cookies.push(value.to_string());
+            }
+        } else if let Some(value) = cookie_header_value(line) {
+            cookies.push(value.to_string());
+        }
+    }
+    cookies
+}
+
+/// 若是 Cookie header 则返回其值
+fn cookie_header_value(header: &str) -> Option<&str> {
+    let (name, value) = header.split_once(':')?;
+    name.trim().eq_ignore_ascii_case("cookie").then(|| value.trim())
+}
+
+/// 还原 Windows cmd 格式中的 `^` 转义，结果按 bash 双引号规则解析
+fn unescape_cmd(command: &str) -> String {
+    let mut result = String::with_capacity(command.len());
+    let mut chars = command.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '^' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            // 行尾的 ^ 是续行符
+            Some('\r') => {
+                chars.next_if_eq(&'\n');
+            }
+            Some('\n') | None => {}
+            Some(escaped) => result.push(escaped),
+        }
+    }
+    result
+}
+
+/// 按 shell 规则把命令切分成参数，支持单引号、双引号、`$'...'` 和反斜杠续行
+fn split_shell_words(command: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut chars = command.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            '\\' => match chars.next() {
+                Some('\n') => {}
+                Some('\r') => {
+                    chars.next_if_eq(&'\n');
+                }
+                Some(escaped) => {
+                    word.push(escaped);
+                    in_word = true;
+                }
+                None => {}
+            },
+            '\'' => {
+                in_word = true;
+                for c in chars.by_ref() {
+                    if c == '\'' {
+                        break;
+                    }
+                    word.push(c);
+                }
+            }
+            '$' if chars.peek() == Some(&'\'') => {
+                chars.next();
+                in_word = true;
+                while let Some(c) = chars.next() {
+                    match c {
+                        '\'' => break,
+                        '\\' => match chars.next() {
+                            Some('n') => word.push('\n'),
+                            Some('t') => word.push('\t'),
+                            Some(escaped) => word.push(escaped),
+                            None => {}
+                        },
+                        _ => word.push(c),
+                    }
+                }
+            }
+            '"' => {
+                in_word = true;
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => match chars.peek() {
+                            Some('"' | '\\' | '$' | '`') => word.extend(chars.next()),
+                            Some('\n') => {
+                                chars.next();
+                            }
+                            _ => word.push('\\'),
+                        },
+                        _ => word.push(c),
+                    }
+                }
+            }
+            _ => {
+                word.push(c);
+                in_word = true;
+            }
+        }
+    }
+
+    if in_word {
+        words.push(word);
+    }
+    words
+}
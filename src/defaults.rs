@@ -0,0 +1,101 @@
+//! `--show-defaults`：列出各配置项的默认值和生效值
+//!
+//! 把默认配置和实际加载的配置展开为 `a.b.c` 形式的字段逐项对比，标出被配置覆盖的项；
+//! 另外列出代码中写死、不能通过配置修改的等待时间等内置值。cookie、密钥等敏感字段只显示为已隐藏
+
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt::Write;
+
+/// 字段名以这些词结尾时隐藏其值
+const SECRET_KEYS: [&str; 5] = ["cookie", "cookies", "secret", "webhook", "password"];
+
+/// 一个不可配置的内置值
+pub struct Builtin {
+    pub name: &'static str,
+    pub value: String,
+    pub description: &'static str,
+}
+
+/// 生成默认值与生效值的对比
+pub fn render<T: Serialize>(default: &T, effective: &T, builtins: &[Builtin]) -> String {
+    let default = flatten(&redact(serde_json::to_value(default).unwrap_or_default()));
+    let effective = flatten(&redact(serde_json::to_value(effective).unwrap_or_default()));
+
+    let mut names: Vec<&String> = default.iter().chain(&effective).map(|(name, _)| name).collect();
+    names.sort();
+    names.dedup();
+
+    let lookup = |fields: &[(String, String)], name: &str| {
+        fields
+            .iter()
+            .find(|(field, _)| field == name)
+            .map_or_else(|| "-".to_string(), |(_, value)| value.clone())
+    };
+
+    let mut text = String::new();
+    let _ = writeln!(text, "配置项（* 表示已被配置覆盖）:");
+    let mut overridden = 0;
+    for name in names {
+        let default_value = lookup(&default, name);
+        let effective_value = lookup(&effective, name);
+        if default_value == effective_value {
+            let _ = writeln!(text, "    {} = {}", name, effective_value);
+        } else {
+            overridden += 1;
+            let _ = writeln!(text, "  * {} = {}（默认: {}）", name, effective_value, default_value);
+        }
+    }
+    let _ = writeln!(text, "共 {} 项被配置覆盖", overridden);
+
+    let _ = writeln!(text);
+    let _ = writeln!(text, "内置值（不可配置）:");
+    for builtin in builtins {
+        let _ = writeln!(text, "    {} = {}  # {}", builtin.name, builtin.value, builtin.description);
+    }
+    text
+}
+
+/// 隐藏敏感字段的值
+fn redact(value: Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(key, value)| {
+                    let lower = key.to_lowercase();
+                    if SECRET_KEYS.iter().any(|secret| lower.ends_with(secret)) && !value.is_null() {
+                        (key, Value::String("<已隐藏>".to_string()))
+                    } else {
+                        (key, redact(value))
+                    }
+                })
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.into_iter().map(redact).collect()),
+        other => other,
+    }
+}
+
+/// 把嵌套的对象展开为 (a.b.c, 值)，数组整体作为一个值
+fn flatten(value: &Value) -> Vec<(String, String)> {
+    let mut fields = Vec::new();
+    flatten_into("", value, &mut fields);
+    fields
+}
+
+fn flatten_into(prefix: &str, value: &Value, fields: &mut Vec<(String, String)>) {
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            for (key, value) in map {
+                let name = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                flatten_into(&name, value, fields);
+            }
+        }
+        Value::String(s) => fields.push((prefix.to_string(), format!("{:?}", s))),
+        other => fields.push((prefix.to_string(), other.to_string())),
+    }
+}
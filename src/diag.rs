@@ -0,0 +1,176 @@
+//! 账号处理失败时的诊断包
+//!
+//! 开启后记录每个账号本轮的所有请求和响应，账号整体处理失败时连同流程时间线、
+//! 该账号处理期间的日志和配置摘要写入 `diag/account_N_<run_id>/`，用户可以直接打包发给维护者。
+//! 请求不记录 header，写入前会隐藏 cookie 字段
+
+use crate::{flow::AccountFlow, http, report::AccountReport, run_id, vcr::RequestKey};
+use anyhow::Result;
+use chrono::{Local, NaiveDateTime};
+use regex::Regex;
+use reqwest::Response;
+use serde::Serialize;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{LazyLock, Mutex},
+};
+
+/// 单个响应体最多保留的字节数
+const MAX_BODY_SIZE: usize = 64 * 1024;
+
+/// 找不到本账号开始处理的日志时，最多保留的日志行数
+const FALLBACK_LOG_LINES: usize = 500;
+
+/// 日志行开头的时间格式
+const LOG_TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// 形如 `UID=...` 的 cookie 字段
+static COOKIE_FIELD: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\b(UID|CID|SEID|KID)=[^;&\s]+").expect("内置正则有效"));
+
+/// 一次请求和响应
+#[derive(Debug, Clone, Serialize)]
+struct Exchange {
+    at: String,
+    method: String,
+    url: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    request_body: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// 单个账号本轮的诊断记录
+pub struct Recorder {
+    account_index: usize,
+    started: NaiveDateTime,
+    exchanges: Mutex<Vec<Exchange>>,
+    flow: Mutex<Option<AccountFlow>>,
+}
+
+impl Recorder {
+    pub fn new(account_index: usize) -> Self {
+        Self {
+            account_index,
+            started: Local::now().naive_local(),
+            exchanges: Mutex::new(Vec::new()),
+            flow: Mutex::new(None),
+        }
+    }
+
+    /// 记录一次请求的结果，读取响应体后返回内容相同的新响应
+    pub async fn capture(&self, key: &RequestKey, result: reqwest::Result<Response>) -> reqwest::Result<Response> {
+        let mut exchange = Exchange {
+            at: Local::now().format(LOG_TIME_FORMAT).to_string(),
+            method: key.method.clone(),
+            url: key.url.clone(),
+            request_body: key.body.clone(),
+            status: None,
+            response: None,
+            error: None,
+        };
+
+        let result = match result {
+            Ok(response) => {
+                exchange.status = Some(response.status().as_u16());
+                match http::read_body(response, usize::MAX).await {
+                    Ok((response, body, _)) => {
+                        let end = body.len().min(MAX_BODY_SIZE);
+                        exchange.response = Some(String::from_utf8_lossy(&body[..end]).into_owned());
+                        Ok(response)
+                    }
+                    Err(e) => {
+                        exchange.error = Some(e.to_string());
+                        Err(e)
+                    }
+                }
+            }
+            Err(e) => {
+                exchange.error = Some(e.to_string());
+                Err(e)
+            }
+        };
+
+        self.exchanges.lock().unwrap_or_else(|e| e.into_inner()).push(exchange);
+        result
+    }
+
+    /// 保存本账号的流程状态，写诊断包时作为时间线
+    pub fn set_flow(&self, flow: &AccountFlow) {
+        *self.flow.lock().unwrap_or_else(|e| e.into_inner()) = Some(flow.clone());
+    }
+
+    /// 在 dir 下写入诊断包，返回诊断包目录
+    pub fn write_bundle(
+        &self,
+        dir: &Path,
+        error: &str,
+        report: &AccountReport,
+        config_summary: &str,
+        log_file: &Path,
+    ) -> Result<PathBuf> {
+        let bundle = dir.join(format!("account_{}_{}", self.account_index + 1, run_id::current()));
+        fs::create_dir_all(&bundle)?;
+
+        fs::write(bundle.join("error.txt"), redact(error))?;
+
+        let exchanges = self.exchanges.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        fs::write(bundle.join("requests.json"), redact(&serde_json::to_string_pretty(&exchanges)?))?;
+
+        fs::write(bundle.join("timeline.txt"), redact(&self.render_timeline(report)))?;
+        fs::write(bundle.join("config.txt"), config_summary)?;
+
+        let logs = match fs::read_to_string(log_file) {
+            Ok(text) => self.logs_since_start(&text),
+            Err(e) => format!("读取日志文件 {} 失败: {}\n", log_file.display(), e),
+        };
+        fs::write(bundle.join("logs.txt"), redact(&logs))?;
+
+        Ok(bundle)
+    }
+
+    /// 流程状态转移和各次操作结果，按时间排列
+    fn render_timeline(&self, report: &AccountReport) -> String {
+        let mut lines = Vec::new();
+        if let Some(flow) = self.flow.lock().unwrap_or_else(|e| e.into_inner()).as_ref() {
+            for transition in &flow.transitions {
+                lines.push(format!("{} 状态 {} -> {}", transition.at, transition.from, transition.to));
+            }
+        }
+        for action in &report.actions {
+            lines.push(format!(
+                "{} {} {} {}{}",
+                action.time.format(LOG_TIME_FORMAT),
+                action.action,
+                if action.wish_code.is_empty() { "-" } else { &action.wish_code },
+                if action.success { "success" } else { "failed" },
+                if action.message.is_empty() { String::new() } else { format!(" {}", action.message) },
+            ));
+        }
+        // 每行以相同格式的时间开头，按字符串排序即按时间排序
+        lines.sort();
+        lines.join("\n") + "\n"
+    }
+
+    /// 截取本账号开始处理之后的日志，日志行没有可识别的时间时保留最后若干行
+    fn logs_since_start(&self, text: &str) -> String {
+        let lines: Vec<&str> = text.lines().collect();
+        let start = lines.iter().position(|line| {
+            line.get(..19)
+                .and_then(|prefix| NaiveDateTime::parse_from_str(prefix, LOG_TIME_FORMAT).ok())
+                .is_some_and(|time| time >= self.started)
+        });
+        let start = start.unwrap_or_else(|| lines.len().saturating_sub(FALLBACK_LOG_LINES));
+        lines[start..].join("\n") + "\n"
+    }
+}
+
+/// 隐藏文本中的 cookie 字段
+fn redact(text: &str) -> String {
+    COOKIE_FIELD.replace_all(text, "$1=<已隐藏>").into_owned()
+}
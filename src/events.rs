@@ -0,0 +1,180 @@
+//! 把处理事件实时写入 Unix domain socket
+//!
+//! 配置 event_socket 后在该路径监听，外部程序（面板、GUI 等）连上后即可按 JSON Lines
+//! 实时收到每个事件。写入不阻塞主流程：没有连接时事件直接丢弃，
+//! 读取过慢或已断开的连接会被移除。仅支持 Unix 平台
+
+use crate::report::ActionRecord;
+use serde::Serialize;
+
+/// 处理过程中的事件
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event {
+    RunStarted {
+        run_id: String,
+        accounts: usize,
+    },
+    AccountStarted {
+        account: usize,
+        name: String,
+    },
+    /// 一次许愿、助力或采纳的结果
+    Action {
+        account: usize,
+        action: &'static str,
+        wish_code: String,
+        success: bool,
+        message: String,
+        reward: i64,
+    },
+    AccountFinished {
+        account: usize,
+        aided: u32,
+        adopted: u32,
+        failed: u32,
+        error: Option<String>,
+    },
+    RunFinished {
+        succeeded: usize,
+        failed: usize,
+        requests: u64,
+    },
+}
+
+impl Event {
+    pub fn action(account_index: usize, record: &ActionRecord) -> Self {
+        Event::Action {
+            account: account_index + 1,
+            action: record.action,
+            wish_code: record.wish_code.clone(),
+            success: record.success,
+            message: record.message.clone(),
+            reward: record.reward,
+        }
+    }
+}
+
+#[cfg(unix)]
+#[derive(Serialize)]
+struct Line<'a> {
+    time: String,
+    #[serde(flatten)]
+    event: &'a Event,
+}
+
+/// 事件的输出端，未配置或不支持的平台上不做任何事
+pub struct EventSink {
+    #[cfg(unix)]
+    inner: Option<unix::Server>,
+}
+
+impl EventSink {
+    pub fn disabled() -> Self {
+        Self {
+            #[cfg(unix)]
+            inner: None,
+        }
+    }
+
+    /// 在 `path` 上监听，失败时告警并停用
+    pub fn bind(path: Option<&str>) -> Self {
+        let Some(path) = path.map(str::trim).filter(|path| !path.is_empty()) else {
+            return Self::disabled();
+        };
+
+        #[cfg(unix)]
+        {
+            match unix::Server::bind(path) {
+                Ok(server) => {
+                    tracing::info!("处理事件将实时写入 {}", path);
+                    Self { inner: Some(server) }
+                }
+                Err(e) => {
+                    tracing::warn!("监听事件 socket {} 失败，不输出事件: {}", path, e);
+                    Self::disabled()
+                }
+            }
+        }
+        #[cfg(not(unix))]
+        {
+            tracing::warn!("当前平台不支持 Unix domain socket，忽略 event_socket: {}", path);
+            Self::disabled()
+        }
+    }
+
+    pub fn emit(&self, event: &Event) {
+        #[cfg(unix)]
+        if let Some(server) = &self.inner {
+            let line = Line {
+                time: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+                event,
+            };
+            match serde_json::to_string(&line) {
+                Ok(line) => server.broadcast(&line),
+                Err(e) => tracing::debug!("序列化事件失败: {}", e),
+            }
+        }
+        #[cfg(not(unix))]
+        let _ = event;
+    }
+}
+
+#[cfg(unix)]
+mod unix {
+    use std::{
+        fs,
+        io::{self, Write},
+        os::unix::net::{UnixListener, UnixStream},
+        path::PathBuf,
+        sync::Mutex,
+    };
+
+    pub struct Server {
+        path: PathBuf,
+        listener: UnixListener,
+        clients: Mutex<Vec<UnixStream>>,
+    }
+
+    impl Server {
+        /// 上次运行残留的 socket 文件会被删除后重新创建
+        pub fn bind(path: &str) -> io::Result<Self> {
+            let path = PathBuf::from(path);
+            if path.exists() {
+                fs::remove_file(&path)?;
+            }
+            let listener = UnixListener::bind(&path)?;
+            listener.set_nonblocking(true)?;
+            Ok(Self {
+                path,
+                listener,
+                clients: Mutex::new(Vec::new()),
+            })
+        }
+
+        /// 接受新连接后把一行写给所有连接，写不进去（对方读得太慢或已断开）的连接直接移除
+        pub fn broadcast(&self, line: &str) {
+            let mut clients = self.clients.lock().unwrap_or_else(|e| e.into_inner());
+            while let Ok((stream, _)) = self.listener.accept() {
+                if stream.set_nonblocking(true).is_ok() {
+                    clients.push(stream);
+                }
+            }
+
+            let line = format!("{}\n", line);
+            clients.retain_mut(|stream| match stream.write_all(line.as_bytes()) {
+                Ok(()) => true,
+                Err(e) => {
+                    tracing::debug!("事件连接已断开或读取过慢，移除: {}", e);
+                    false
+                }
+            });
+        }
+    }
+
+    impl Drop for Server {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
@@ -0,0 +1,67 @@
+//! 进程退出码
+//!
+//! 供调用本程序的脚本、cron 根据退出码判断运行结果：
+//!
+//! | 退出码 | 含义 |
+//! | --- | --- |
+//! | 0 | 全部账号处理成功，或没有需要处理的账号（如断点续跑时账号已全部处理完） |
+//! | 1 | 部分账号处理失败 |
+//! | 2 | 配置错误（配置文件无法加载、首次运行刚生成默认配置、缺少 cookie、命令行参数错误） |
+//! | 3 | 全部账号处理失败，或发生致命错误（请求预算用尽、网络不可用、健康检查未通过、所有 cookie 已失效等） |
+
+use std::process::ExitCode;
+
+/// 一次运行的整体结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunStatus {
+    Success,
+    PartialFailure,
+    ConfigError,
+    Failure,
+}
+
+impl RunStatus {
+    /// 按成功、失败的账号数得出整体结果，有账号失败且没有任何账号成功时视为全部失败，
+    /// 一个账号都没有处理时视为成功
+    pub fn from_counts(succeeded: usize, failed: usize) -> Self {
+        match (succeeded, failed) {
+            (0, 0) => RunStatus::Success,
+            (0, _) => RunStatus::Failure,
+            (_, 0) => RunStatus::Success,
+            _ => RunStatus::PartialFailure,
+        }
+    }
+
+    pub fn code(self) -> u8 {
+        match self {
+            RunStatus::Success => 0,
+            RunStatus::PartialFailure => 1,
+            RunStatus::ConfigError => 2,
+            RunStatus::Failure => 3,
+        }
+    }
+}
+
+impl From<RunStatus> for ExitCode {
+    fn from(status: RunStatus) -> Self {
+        ExitCode::from(status.code())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_counts_maps_account_results() {
+        assert_eq!(RunStatus::from_counts(3, 0), RunStatus::Success);
+        assert_eq!(RunStatus::from_counts(2, 1), RunStatus::PartialFailure);
+        assert_eq!(RunStatus::from_counts(0, 2), RunStatus::Failure);
+    }
+
+    #[test]
+    fn nothing_processed_is_success() {
+        assert_eq!(RunStatus::from_counts(0, 0), RunStatus::Success);
+        assert_eq!(RunStatus::from_counts(0, 0).code(), 0);
+    }
+}
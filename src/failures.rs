@@ -0,0 +1,98 @@
+//! 账号连续失败计数
+//!
+//! 为每个账号维护跨轮次的连续失败次数，超过阈值后自动禁用该账号，
+//! 后续轮次直接跳过。状态按 cookie 指纹保存，更新 cookie 后账号自动重新启用
+
+use anyhow::Result;
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fs, path::PathBuf};
+
+/// 状态文件名
+pub const FAILURE_STATE_FILE: &str = "account_failures.json";
+
+/// 单个账号的失败状态
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AccountFailureState {
+    pub consecutive_failures: u32,
+    pub disabled: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disabled_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+/// 各账号的失败状态，键为 cookie 指纹
+#[derive(Debug, Default)]
+pub struct FailureTracker {
+    path: PathBuf,
+    threshold: u32,
+    accounts: HashMap<String, AccountFailureState>,
+}
+
+impl FailureTracker {
+    /// 从状态目录加载，文件不存在或损坏时从空状态开始
+    pub fn load(state_dir: &str, threshold: u32) -> Self {
+        let path = PathBuf::from(state_dir).join(FAILURE_STATE_FILE);
+        let accounts = match fs::read_to_string(&path) {
+            Ok(text) => serde_json::from_str(&text).unwrap_or_else(|e| {
+                tracing::warn!("账号失败状态文件 {} 解析失败，将重新计数: {}", path.display(), e);
+                HashMap::new()
+            }),
+            Err(_) => HashMap::new(),
+        };
+
+        Self {
+            path,
+            threshold,
+            accounts,
+        }
+    }
+
+    /// 查询账号的失败状态
+    pub fn get(&self, key: &str) -> Option<&AccountFailureState> {
+        self.accounts.get(key)
+    }
+
+    /// 账号是否已被禁用
+    pub fn is_disabled(&self, key: &str) -> bool {
+        self.get(key).is_some_and(|state| state.disabled)
+    }
+
+    /// 记录一轮成功，清零连续失败次数
+    pub fn record_success(&mut self, key: &str) {
+        self.accounts.remove(key);
+    }
+
+    /// 记录一轮失败，返回本次是否触发了禁用
+    pub fn record_failure(&mut self, key: &str, error: String) -> bool {
+        let state = self.accounts.entry(key.to_string()).or_default();
+        state.consecutive_failures += 1;
+        state.last_error = Some(error);
+
+        if self.threshold > 0 && !state.disabled && state.consecutive_failures >= self.threshold {
+            state.disabled = true;
+            state.disabled_at = Some(Local::now().format("%Y-%m-%d %H:%M:%S").to_string());
+            return true;
+        }
+        false
+    }
+
+    /// 保存到状态文件
+    pub fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, serde_json::to_string_pretty(&self.accounts)?)?;
+        Ok(())
+    }
+
+    /// 清除所有账号的失败状态，重新启用被禁用的账号
+    pub fn reset(state_dir: &str) -> Result<()> {
+        let path = PathBuf::from(state_dir).join(FAILURE_STATE_FILE);
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,116 @@
+//! 单账号处理流程的状态机
+//!
+//! 账号处理按 Init → Wished → Aiding → Adopting → Done 的顺序推进，
+//! 许愿未通过审核时从 Wished 直接进入 Done，任一步骤出错则进入 Failed。每次状态转移都会记录下来，并可持久化到文件
+
+use anyhow::Result;
+use chrono::Local;
+use tracing::info;
+use serde::{Deserialize, Serialize};
+use std::{fmt, fs, path::Path};
+
+/// 账号处理所处的状态
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WishState {
+    /// 尚未开始
+    Init,
+    /// 许愿步骤已完成（无论许愿是否成功）
+    Wished,
+    /// 正在为待处理愿望助力
+    Aiding,
+    /// 正在采纳助力
+    Adopting,
+    /// 全部完成
+    Done,
+    /// 处理失败，附带原因
+    Failed(String),
+}
+
+impl WishState {
+    /// 是否为终止状态
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, WishState::Done | WishState::Failed(_))
+    }
+
+    /// 是否允许从当前状态转移到目标状态
+    fn can_transition_to(&self, next: &WishState) -> bool {
+        use WishState::*;
+        match (self, next) {
+            (Done | Failed(_), _) => false,
+            (_, Failed(_)) => true,
+            (Init, Wished) | (Wished, Aiding | Done) | (Aiding, Adopting) | (Adopting, Done) => true,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for WishState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WishState::Init => write!(f, "Init"),
+            WishState::Wished => write!(f, "Wished"),
+            WishState::Aiding => write!(f, "Aiding"),
+            WishState::Adopting => write!(f, "Adopting"),
+            WishState::Done => write!(f, "Done"),
+            WishState::Failed(reason) => write!(f, "Failed({})", reason),
+        }
+    }
+}
+
+/// 一次状态转移记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Transition {
+    pub from: WishState,
+    pub to: WishState,
+    pub at: String,
+}
+
+/// 单个账号的处理流程
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountFlow {
+    pub account_index: usize,
+    pub state: WishState,
+    pub transitions: Vec<Transition>,
+}
+
+impl AccountFlow {
+    pub fn new(account_index: usize) -> Self {
+        Self {
+            account_index,
+            state: WishState::Init,
+            transitions: Vec::new(),
+        }
+    }
+
+    /// 转移到下一个状态，非法转移会返回错误且不改变当前状态
+    pub fn transition(&mut self, next: WishState) -> Result<()> {
+        if !self.state.can_transition_to(&next) {
+            anyhow::bail!("非法的状态转移: {} -> {}", self.state, next);
+        }
+
+        info!("[账号-{}] 状态转移: {} -> {}", self.account_index + 1, self.state, next);
+        self.transitions.push(Transition {
+            from: self.state.clone(),
+            to: next.clone(),
+            at: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        });
+        self.state = next;
+        Ok(())
+    }
+
+    /// 标记为失败，已处于终止状态时忽略
+    pub fn fail(&mut self, reason: impl Into<String>) {
+        if !self.state.is_terminal() {
+            let _ = self.transition(WishState::Failed(reason.into()));
+        }
+    }
+
+    /// 把当前流程状态以 JSON 写入文件
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
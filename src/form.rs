@@ -0,0 +1,140 @@
+//! 各接口表单字段名的映射与请求体编码方式
+//!
+//! 115 偶尔会修改表单字段名，这里把字段名做成可在配置中覆盖的映射，
+//! 默认值为当前接口使用的字段名，只需在配置里写出要修改的字段即可。
+//! 请求体默认按表单编码，接口改为接受 JSON 时可按接口切换
+
+use reqwest::RequestBuilder;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// 请求体的编码方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BodyEncoding {
+    /// application/x-www-form-urlencoded，所有值都是字符串
+    #[default]
+    Form,
+    /// application/json，数值字段按数字发送，其余按字符串发送
+    Json,
+}
+
+impl BodyEncoding {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            BodyEncoding::Form => "application/x-www-form-urlencoded",
+            BodyEncoding::Json => "application/json",
+        }
+    }
+
+    /// 按编码方式附加请求体并设置 Content-Type，`numeric` 中的字段在 JSON 中按数字发送
+    pub fn attach(&self, request: RequestBuilder, fields: &[(String, String)], numeric: &[&str]) -> RequestBuilder {
+        match self {
+            BodyEncoding::Form => request.form(fields),
+            BodyEncoding::Json => request.json(&Self::json_body(fields, numeric)),
+        }
+    }
+
+    /// 编码后的请求体文本，用于预览和调试日志
+    pub fn encode(&self, fields: &[(String, String)], numeric: &[&str]) -> String {
+        match self {
+            BodyEncoding::Form => {
+                let mut url = reqwest::Url::parse("http://localhost/").expect("固定的 URL 有效");
+                url.query_pairs_mut().extend_pairs(fields);
+                url.query().unwrap_or_default().to_string()
+            }
+            BodyEncoding::Json => Value::Object(Self::json_body(fields, numeric)).to_string(),
+        }
+    }
+
+    /// 数值字段的值不是合法整数时仍按字符串发送
+    fn json_body(fields: &[(String, String)], numeric: &[&str]) -> Map<String, Value> {
+        fields
+            .iter()
+            .map(|(name, value)| {
+                let value = match value.trim().parse::<i64>() {
+                    Ok(number) if numeric.contains(&name.as_str()) => Value::from(number),
+                    _ => Value::from(value.as_str()),
+                };
+                (name.clone(), value)
+            })
+            .collect()
+    }
+}
+
+/// 各接口请求体的编码方式
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BodyEncodings {
+    pub wish: BodyEncoding,
+    pub aid: BodyEncoding,
+    pub adopt: BodyEncoding,
+}
+
+/// 各接口的表单字段名
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FormFieldNames {
+    pub wish: WishFormFields,
+    pub aid: AidFormFields,
+    pub adopt: AdoptFormFields,
+}
+
+/// 许愿接口的表单字段名
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WishFormFields {
+    pub content: String,
+    pub images: String,
+    pub reward_space: String,
+}
+
+impl Default for WishFormFields {
+    fn default() -> Self {
+        Self {
+            content: "content".to_string(),
+            images: "images".to_string(),
+            reward_space: "rewardSpace".to_string(),
+        }
+    }
+}
+
+/// 助力接口的表单字段名
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AidFormFields {
+    pub id: String,
+    pub content: String,
+    pub images: String,
+    pub file_ids: String,
+}
+
+impl Default for AidFormFields {
+    fn default() -> Self {
+        Self {
+            id: "id".to_string(),
+            content: "content".to_string(),
+            images: "images".to_string(),
+            file_ids: "file_ids".to_string(),
+        }
+    }
+}
+
+/// 采纳接口的表单字段名
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AdoptFormFields {
+    pub did: String,
+    pub aid: String,
+    pub to_cid: String,
+}
+
+impl Default for AdoptFormFields {
+    fn default() -> Self {
+        Self {
+            did: "did".to_string(),
+            aid: "aid".to_string(),
+            to_cid: "to_cid".to_string(),
+        }
+    }
+}
@@ -0,0 +1,59 @@
+//! 运行前的健康检查
+//!
+//! 正式处理前先请求一次只读接口，确认 115 活动接口可达、活动仍在进行，
+//! 避免活动结束后仍然对每个账号白跑一遍流程
+
+use crate::risk;
+use std::fmt;
+
+/// 接口返回中表示活动已结束的关键字
+const ACTIVITY_ENDED_KEYWORDS: [&str; 4] = ["活动已结束", "活动结束", "活动未开始", "活动不存在"];
+
+/// 健康检查结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HealthStatus {
+    /// 接口可达，活动进行中
+    Healthy,
+    /// 活动已结束或未开始
+    ActivityEnded(String),
+    /// 活动维护中或已暂停
+    Maintenance(String),
+    /// 接口不可达（网络错误、HTTP 错误或返回内容无法解析）
+    Unreachable(String),
+    /// 接口可达但返回了业务错误（如 cookie 失效）
+    Abnormal(String),
+}
+
+impl HealthStatus {
+    pub fn is_healthy(&self) -> bool {
+        matches!(self, HealthStatus::Healthy)
+    }
+
+    /// 根据接口返回的业务字段判断活动状态
+    pub fn from_response(state: i32, code: i32, message: &str) -> Self {
+        if ACTIVITY_ENDED_KEYWORDS.iter().any(|keyword| message.contains(keyword)) {
+            return HealthStatus::ActivityEnded(message.to_string());
+        }
+        if risk::is_maintenance_message(message) {
+            return HealthStatus::Maintenance(message.to_string());
+        }
+
+        if state == 1 && code == 0 {
+            HealthStatus::Healthy
+        } else {
+            HealthStatus::Abnormal(format!("{} (状态: {}, 代码: {})", message, state, code))
+        }
+    }
+}
+
+impl fmt::Display for HealthStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HealthStatus::Healthy => write!(f, "活动接口可达，活动进行中"),
+            HealthStatus::ActivityEnded(msg) => write!(f, "活动已结束或未开始: {}", msg),
+            HealthStatus::Maintenance(msg) => write!(f, "活动维护中或已暂停: {}", msg),
+            HealthStatus::Unreachable(msg) => write!(f, "活动接口不可达: {}", msg),
+            HealthStatus::Abnormal(msg) => write!(f, "活动接口返回异常: {}", msg),
+        }
+    }
+}
@@ -0,0 +1,139 @@
+//! 运行结果快照与前后两次运行的差异
+//!
+//! 每次运行结束时把汇总数据保存为快照，下次运行结束时与之对比，
+//! 生成许愿数、助力数、成功率等的增减摘要，便于长期运行时观察趋势
+
+use crate::report::{AccountReport, WishOutcome};
+use anyhow::Result;
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::{fs, path::PathBuf};
+
+/// 快照文件名
+pub const SNAPSHOT_FILE: &str = "last_run.json";
+
+/// 差异报告的配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DiffReportConfig {
+    /// 是否保存快照并打印与上次运行的差异
+    pub enabled: bool,
+    /// 是否把差异摘要发送到通知渠道
+    pub notify: bool,
+}
+
+impl Default for DiffReportConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            notify: false,
+        }
+    }
+}
+
+/// 一次运行的汇总数据
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RunSnapshot {
+    /// 运行结束时间
+    pub time: String,
+    pub accounts: usize,
+    /// 许愿成功的账号数（含当天已完成的）
+    pub wished: u32,
+    pub aided: u32,
+    pub adopted: u32,
+    pub failed: u32,
+    pub reward: i64,
+}
+
+impl RunSnapshot {
+    pub fn from_reports(reports: &[AccountReport]) -> Self {
+        Self {
+            time: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            accounts: reports.len(),
+            wished: reports
+                .iter()
+                .filter(|r| matches!(r.wish, WishOutcome::Success(_) | WishOutcome::AlreadyDone(_)))
+                .count() as u32,
+            aided: reports.iter().map(|r| r.aided).sum(),
+            adopted: reports.iter().map(|r| r.adopted).sum(),
+            failed: reports.iter().map(|r| r.failed).sum(),
+            reward: reports.iter().map(|r| r.reward).sum(),
+        }
+    }
+
+    /// 成功操作占全部操作的百分比，没有任何操作时返回 None
+    pub fn success_rate(&self) -> Option<f64> {
+        let succeeded = self.wished + self.aided + self.adopted;
+        let total = succeeded + self.failed;
+        (total > 0).then(|| succeeded as f64 * 100.0 / total as f64)
+    }
+
+    fn path(state_dir: &str) -> PathBuf {
+        PathBuf::from(state_dir).join(SNAPSHOT_FILE)
+    }
+
+    /// 读取上一次运行的快照，不存在或损坏时返回 None
+    pub fn load_last(state_dir: &str) -> Option<Self> {
+        let path = Self::path(state_dir);
+        let text = fs::read_to_string(&path).ok()?;
+        serde_json::from_str(&text)
+            .inspect_err(|e| tracing::warn!("运行快照文件 {} 解析失败，跳过差异对比: {}", path.display(), e))
+            .ok()
+    }
+
+    pub fn save(&self, state_dir: &str) -> Result<()> {
+        let path = Self::path(state_dir);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// 生成本次运行的汇总
+pub fn render_current(current: &RunSnapshot) -> String {
+    format!(
+        "本次运行：账号 {}，许愿 {}，助力 {}，采纳 {}，失败 {}，奖励 {}，成功率 {}",
+        current.accounts,
+        current.wished,
+        current.aided,
+        current.adopted,
+        current.failed,
+        current.reward,
+        format_rate(current.success_rate()),
+    )
+}
+
+/// 生成与上次运行的差异摘要
+pub fn render_diff(last: &RunSnapshot, current: &RunSnapshot) -> String {
+    let rate_change = match (last.success_rate(), current.success_rate()) {
+        (Some(last), Some(current)) => format!("{:+.1} 个百分点", current - last),
+        _ => "无法比较".to_string(),
+    };
+
+    format!(
+        "与上次运行（{}）相比：许愿 {}（{}），助力 {}（{}），采纳 {}（{}），失败 {}（{}），奖励 {}（{}），成功率 {}（{}）",
+        last.time,
+        current.wished,
+        signed(current.wished as i64 - last.wished as i64),
+        current.aided,
+        signed(current.aided as i64 - last.aided as i64),
+        current.adopted,
+        signed(current.adopted as i64 - last.adopted as i64),
+        current.failed,
+        signed(current.failed as i64 - last.failed as i64),
+        current.reward,
+        signed(current.reward - last.reward),
+        format_rate(current.success_rate()),
+        rate_change,
+    )
+}
+
+fn signed(delta: i64) -> String {
+    format!("{:+}", delta)
+}
+
+fn format_rate(rate: Option<f64>) -> String {
+    rate.map_or_else(|| "-".to_string(), |rate| format!("{:.1}%", rate))
+}
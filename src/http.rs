@@ -0,0 +1,263 @@
+//! 请求封装用到的 HTTP 辅助函数
+
+use chrono::{DateTime, Utc};
+use flate2::{write::GzEncoder, Compression};
+use reqwest::{
+    header::{HeaderValue, CONTENT_ENCODING, CONTENT_LENGTH, LOCATION, RETRY_AFTER},
+    redirect, Request, Response, Url,
+};
+use std::{error::Error as _, fmt, io::Write, time::Duration};
+
+/// 限流等待时间的上限，避免服务端返回异常大的 Retry-After
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(600);
+
+/// 读取响应的 Retry-After header，支持秒数和 HTTP 日期两种格式
+pub fn retry_after(response: &Response) -> Option<Duration> {
+    let value = response.headers().get(RETRY_AFTER)?.to_str().ok()?.trim();
+
+    let wait = match value.parse::<u64>() {
+        Ok(secs) => Duration::from_secs(secs),
+        Err(_) => {
+            let at = DateTime::parse_from_rfc2822(value).ok()?.with_timezone(&Utc);
+            (at - Utc::now()).to_std().unwrap_or(Duration::ZERO)
+        }
+    };
+
+    Some(wait.min(MAX_RETRY_AFTER))
+}
+
+/// 最多跟随的重定向次数，与 reqwest 的默认值一致
+const MAX_REDIRECTS: usize = 10;
+
+/// 跟随重定向的策略：照常跟随，但每一跳都记录跳转目标
+///
+/// 接口请求被重定向通常意味着会话有问题，记录下跳转到了哪里（登录页还是验证页）便于判断原因
+pub fn redirect_policy() -> redirect::Policy {
+    redirect::Policy::custom(|attempt| {
+        let from = attempt.previous().last().map(Url::as_str).unwrap_or_default();
+        tracing::warn!(
+            "请求被重定向（{}）: {} -> {}{}",
+            attempt.status(),
+            from,
+            attempt.url(),
+            redirect_hint(attempt.url().as_str())
+        );
+        if attempt.previous().len() > MAX_REDIRECTS {
+            attempt.error("重定向次数过多")
+        } else {
+            attempt.follow()
+        }
+    })
+}
+
+/// 3xx 响应的 Location，用于记录未被跟随的重定向
+pub fn redirect_location(response: &Response) -> Option<&str> {
+    if !response.status().is_redirection() {
+        return None;
+    }
+    response.headers().get(LOCATION)?.to_str().ok()
+}
+
+/// 按跳转目标推测重定向的原因
+pub fn redirect_hint(location: &str) -> &'static str {
+    let location = location.to_ascii_lowercase();
+    if location.contains("login") || location.contains("passport") {
+        "，跳转到了登录页，cookie 可能已失效"
+    } else if location.contains("verify") || location.contains("captcha") || location.contains("security") {
+        "，跳转到了验证页，可能触发了风控"
+    } else {
+        ""
+    }
+}
+
+/// 指数退避的等待时间：base * 2^attempt
+pub fn backoff_delay(base: Duration, attempt: u32) -> Duration {
+    base.saturating_mul(2u32.saturating_pow(attempt)).min(MAX_RETRY_AFTER)
+}
+
+/// 请求体不小于 min_size 字节时用 gzip 压缩并设置 Content-Encoding，返回是否压缩
+///
+/// 流式请求体或已设置 Content-Encoding 的请求保持不变
+pub fn gzip_body(request: &mut Request, min_size: usize) -> bool {
+    if request.headers().contains_key(CONTENT_ENCODING) {
+        return false;
+    }
+    let Some(body) = request.body().and_then(|body| body.as_bytes()) else {
+        return false;
+    };
+    if body.len() < min_size {
+        return false;
+    }
+
+    let mut encoder = GzEncoder::new(Vec::with_capacity(body.len() / 2), Compression::default());
+    let Ok(compressed) = encoder.write_all(body).and_then(|()| encoder.finish()) else {
+        return false;
+    };
+
+    *request.body_mut() = Some(compressed.into());
+    request.headers_mut().insert(CONTENT_ENCODING, HeaderValue::from_static("gzip"));
+    true
+}
+
+/// 读取响应体，超过 max_size 字节的部分丢弃，返回内容相同（或被截断）的新响应以及是否被截断
+///
+/// body 已被解压，新响应不再带 Content-Encoding 和 Content-Length
+pub async fn read_limited(response: Response, max_size: usize) -> reqwest::Result<(Response, bool)> {
+    read_body(response, max_size)
+        .await
+        .map(|(response, _, truncated)| (response, truncated))
+}
+
+/// 同 [`read_limited`]，同时返回读取到的响应体
+pub async fn read_body(mut response: Response, max_size: usize) -> reqwest::Result<(Response, Vec<u8>, bool)> {
+    let mut builder = ::http::Response::builder()
+        .status(response.status())
+        .version(response.version());
+    for (name, value) in response.headers() {
+        if name != CONTENT_ENCODING && name != CONTENT_LENGTH {
+            builder = builder.header(name, value);
+        }
+    }
+
+    let mut body = Vec::new();
+    let mut truncated = false;
+    while let Some(chunk) = response.chunk().await? {
+        let room = max_size.saturating_sub(body.len());
+        if chunk.len() > room {
+            body.extend_from_slice(&chunk[..room]);
+            truncated = true;
+            break;
+        }
+        body.extend_from_slice(&chunk);
+    }
+
+    let rebuilt = builder
+        .body(body.clone())
+        .unwrap_or_else(|_| ::http::Response::new(body.clone()));
+    Ok((Response::from(rebuilt), body, truncated))
+}
+
+/// 网络层错误的类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NetErrorKind {
+    /// 连接或读取超时
+    Timeout,
+    /// 连接被拒绝、被重置等
+    Connect,
+    /// 域名解析失败
+    Dns,
+    /// TLS 握手或证书错误
+    Tls,
+    /// 读取响应 body 时中断
+    Body,
+    /// 响应内容解码失败
+    Decode,
+    /// 重定向次数过多等重定向错误
+    Redirect,
+    /// 其他错误
+    Other,
+}
+
+/// 对网络层错误的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorAction {
+    /// 临时性错误，可以重试
+    Retry,
+    /// 放弃本次请求，继续后续操作
+    Skip,
+    /// 运行环境问题，重试和继续都没有意义，中止本轮处理
+    Abort,
+}
+
+impl NetErrorKind {
+    /// 该类错误的处理方式
+    pub fn action(self) -> ErrorAction {
+        match self {
+            NetErrorKind::Timeout | NetErrorKind::Connect | NetErrorKind::Body => ErrorAction::Retry,
+            NetErrorKind::Dns | NetErrorKind::Tls => ErrorAction::Abort,
+            NetErrorKind::Decode | NetErrorKind::Redirect | NetErrorKind::Other => ErrorAction::Skip,
+        }
+    }
+
+    /// 排查建议
+    pub fn hint(self) -> &'static str {
+        match self {
+            NetErrorKind::Timeout => "网络较慢或 115 服务器响应慢，稍后重试",
+            NetErrorKind::Connect => "连接被拒绝或中断，检查网络连接和代理设置",
+            NetErrorKind::Dns => "域名解析失败，检查 DNS 设置或网络是否可用",
+            NetErrorKind::Tls => "TLS 握手失败，检查系统时间、根证书或是否有代理/防火墙拦截 HTTPS",
+            NetErrorKind::Body => "读取响应时连接中断，通常是网络不稳定",
+            NetErrorKind::Decode => "响应内容无法解码，可能是接口返回了异常内容",
+            NetErrorKind::Redirect => "重定向异常，可能是登录状态失效被跳转",
+            NetErrorKind::Other => "未知网络错误，查看详细错误信息",
+        }
+    }
+}
+
+impl fmt::Display for NetErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            NetErrorKind::Timeout => "超时",
+            NetErrorKind::Connect => "连接失败",
+            NetErrorKind::Dns => "DNS 解析失败",
+            NetErrorKind::Tls => "TLS 错误",
+            NetErrorKind::Body => "读取响应中断",
+            NetErrorKind::Decode => "解码失败",
+            NetErrorKind::Redirect => "重定向错误",
+            NetErrorKind::Other => "其他错误",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// 判断 reqwest 错误的类别
+///
+/// reqwest 只区分超时、连接等大类，DNS 和 TLS 错误需要从错误链的描述中识别
+pub fn classify_reqwest_error(error: &reqwest::Error) -> NetErrorKind {
+    if error.is_timeout() {
+        return NetErrorKind::Timeout;
+    }
+
+    let mut chain = error.to_string();
+    let mut source = error.source();
+    while let Some(e) = source {
+        chain.push_str(": ");
+        chain.push_str(&e.to_string());
+        source = e.source();
+    }
+    let chain = chain.to_lowercase();
+
+    if ["dns error", "failed to lookup address", "name or service not known", "no such host"]
+        .iter()
+        .any(|keyword| chain.contains(keyword))
+    {
+        NetErrorKind::Dns
+    } else if ["tls", "ssl", "certificate", "handshake"].iter().any(|keyword| chain.contains(keyword)) {
+        NetErrorKind::Tls
+    } else if error.is_connect() {
+        NetErrorKind::Connect
+    } else if error.is_body() {
+        NetErrorKind::Body
+    } else if error.is_decode() {
+        NetErrorKind::Decode
+    } else if error.is_redirect() {
+        NetErrorKind::Redirect
+    } else if ["connection reset", "connection closed", "broken pipe"].iter().any(|keyword| chain.contains(keyword)) {
+        NetErrorKind::Connect
+    } else {
+        NetErrorKind::Other
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_delay_doubles_and_caps() {
+        let base = Duration::from_secs(3);
+        assert_eq!(backoff_delay(base, 0), Duration::from_secs(3));
+        assert_eq!(backoff_delay(base, 2), Duration::from_secs(12));
+        assert_eq!(backoff_delay(base, 40), MAX_RETRY_AFTER);
+    }
+}
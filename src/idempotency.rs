@@ -0,0 +1,202 @@
+//! 许愿、助力、采纳操作的幂等记录
+//!
+//! 每个完成的操作按 `操作:对象:日期` 生成幂等键记录到状态文件，同一天重跑时
+//! 已完成的操作直接跳过。对象的取值规则：
+//! - 许愿：许愿账号的 cookie 指纹，每个账号每天许愿一次
+//! - 助力：愿望 id，记录的值为助力 id，重跑时可直接用于采纳
+//! - 采纳：愿望 id
+//!
+//! 日期取程序启动时的本地日期，跨过零点的一轮运行仍使用同一个日期
+
+use anyhow::Result;
+use chrono::{Duration, Local, NaiveDate};
+use tracing::warn;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fmt, fs, path::PathBuf, sync::Mutex};
+
+/// 状态文件名
+pub const IDEMPOTENCY_STATE_FILE: &str = "idempotency.json";
+
+/// 幂等记录保留的天数，更早的记录在加载时清理
+const RETAIN_DAYS: i64 = 7;
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// 需要幂等保护的操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    Wish,
+    Aid,
+    Adopt,
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Operation::Wish => write!(f, "wish"),
+            Operation::Aid => write!(f, "aid"),
+            Operation::Adopt => write!(f, "adopt"),
+        }
+    }
+}
+
+/// 生成幂等键
+pub fn key(operation: Operation, subject: &str, date: NaiveDate) -> String {
+    format!("{}:{}:{}", operation, subject, date.format(DATE_FORMAT))
+}
+
+/// 一条已完成操作的记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Record {
+    /// 操作产生的结果 id，如愿望 id、助力 id
+    value: String,
+    completed_at: String,
+}
+
+/// 已完成操作的记录，各账号共享，线程安全
+#[derive(Debug)]
+pub struct IdempotencyStore {
+    path: PathBuf,
+    enabled: bool,
+    date: NaiveDate,
+    records: Mutex<HashMap<String, Record>>,
+}
+
+impl IdempotencyStore {
+    /// 从状态目录加载，关闭时不读写文件，所有操作都视为未完成
+    pub fn load(state_dir: &str, enabled: bool) -> Self {
+        let path = PathBuf::from(state_dir).join(IDEMPOTENCY_STATE_FILE);
+        let date = Local::now().date_naive();
+
+        let mut records: HashMap<String, Record> = match fs::read_to_string(&path) {
+            Ok(text) if enabled => serde_json::from_str(&text).unwrap_or_else(|e| {
+                warn!("幂等记录文件 {} 解析失败，将重新记录: {}", path.display(), e);
+                HashMap::new()
+            }),
+            _ => HashMap::new(),
+        };
+
+        let oldest = date - Duration::days(RETAIN_DAYS);
+        records.retain(|key, _| {
+            key.rsplit(':')
+                .next()
+                .and_then(|d| NaiveDate::parse_from_str(d, DATE_FORMAT).ok())
+                .is_some_and(|d| d > oldest)
+        });
+
+        Self {
+            path,
+            enabled,
+            date,
+            records: Mutex::new(records),
+        }
+    }
+
+    /// 查询操作今天是否已完成，返回记录的结果 id
+    pub fn get(&self, operation: Operation, subject: &str) -> Option<String> {
+        if !self.enabled {
+            return None;
+        }
+        let records = self.records.lock().unwrap_or_else(|e| e.into_inner());
+        records.get(&key(operation, subject, self.date)).map(|r| r.value.clone())
+    }
+
+    /// 记录操作已完成并立即写入文件，写入失败只告警
+    pub fn complete(&self, operation: Operation, subject: &str, value: &str) {
+        if !self.enabled {
+            return;
+        }
+
+        let mut records = self.records.lock().unwrap_or_else(|e| e.into_inner());
+        records.insert(
+            key(operation, subject, self.date),
+            Record {
+                value: value.to_string(),
+                completed_at: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            },
+        );
+
+        if let Err(e) = self.save(&records) {
+            warn!("写入幂等记录文件 {} 失败: {}", self.path.display(), e);
+        }
+    }
+
+    fn save(&self, records: &HashMap<String, Record>) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, serde_json::to_string_pretty(records)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 每个测试使用独立的状态目录
+    fn state_dir(name: &str) -> String {
+        let dir = std::env::temp_dir().join(format!("wish_115_idempotency_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir.to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn key_joins_operation_subject_and_date() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 5).unwrap();
+        assert_eq!(key(Operation::Wish, "abc", date), "wish:abc:2024-03-05");
+        assert_eq!(key(Operation::Aid, "1", date), "aid:1:2024-03-05");
+        assert_eq!(key(Operation::Adopt, "1", date), "adopt:1:2024-03-05");
+    }
+
+    #[test]
+    fn completed_operation_is_found_on_the_same_day() {
+        let dir = state_dir("complete");
+        let store = IdempotencyStore::load(&dir, true);
+        assert_eq!(store.get(Operation::Aid, "wish"), None);
+        store.complete(Operation::Aid, "wish", "aid-1");
+        assert_eq!(store.get(Operation::Aid, "wish"), Some("aid-1".to_string()));
+        assert_eq!(store.get(Operation::Adopt, "wish"), None);
+
+        // 重新加载后仍能读到当天的记录
+        let reloaded = IdempotencyStore::load(&dir, true);
+        assert_eq!(reloaded.get(Operation::Aid, "wish"), Some("aid-1".to_string()));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn load_prunes_records_older_than_retain_days() {
+        let dir = state_dir("prune");
+        let today = Local::now().date_naive();
+        let record = Record { value: "v".to_string(), completed_at: String::new() };
+        let recent = key(Operation::Wish, "recent", today - Duration::days(RETAIN_DAYS - 1));
+        let expired = key(Operation::Wish, "expired", today - Duration::days(RETAIN_DAYS));
+        let records = HashMap::from([
+            (recent.clone(), record.clone()),
+            (expired.clone(), record.clone()),
+            ("wish:bad-date".to_string(), record),
+        ]);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(PathBuf::from(&dir).join(IDEMPOTENCY_STATE_FILE), serde_json::to_string(&records).unwrap()).unwrap();
+
+        let store = IdempotencyStore::load(&dir, true);
+        let records = store.records.lock().unwrap();
+        assert!(records.contains_key(&recent));
+        assert!(!records.contains_key(&expired));
+        assert_eq!(records.len(), 1);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn disabled_store_neither_reads_nor_writes() {
+        let dir = state_dir("disabled");
+        IdempotencyStore::load(&dir, true).complete(Operation::Wish, "account", "wish-1");
+
+        let store = IdempotencyStore::load(&dir, false);
+        assert_eq!(store.get(Operation::Wish, "account"), None);
+        store.complete(Operation::Wish, "other", "wish-2");
+        assert_eq!(store.get(Operation::Wish, "other"), None);
+        assert_eq!(IdempotencyStore::load(&dir, true).get(Operation::Wish, "other"), None);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
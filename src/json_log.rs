@@ -0,0 +1,115 @@
+//! JSON 格式的日志输出，供 ELK、Loki 等日志采集系统使用
+//!
+//! 每条日志输出为一行 JSON，包含 timestamp、level、run_id、account_index、event、message 字段，
+//! 处理某个愿望期间还带有该愿望的关联 ID（wish 字段）。
+//! 当前账号和所处步骤保存在任务本地的上下文中，与关联 ID 一样随任务走：
+//! 并发助力时各任务各自带着自己的步骤，不会互相覆盖；新启动的任务用 [`event_scope`] 带上创建时的账号
+
+use crate::{correlation, run_id};
+use chrono::Local;
+use log::Record;
+use log4rs::encode::{Encode, Write};
+use serde::Serialize;
+use std::future::Future;
+
+/// 不在处理任何账号时的事件名
+pub const EVENT_RUN: &str = "run";
+
+/// 当前日志上下文
+#[derive(Debug, Clone, Copy)]
+struct LogContext {
+    account_index: Option<usize>,
+    event: &'static str,
+}
+
+tokio::task_local! {
+    static CONTEXT: LogContext;
+}
+
+/// 当前任务的日志上下文，不在处理任何账号时账号为空、事件为 run
+fn current() -> LogContext {
+    CONTEXT.try_with(|context| *context).unwrap_or(LogContext {
+        account_index: None,
+        event: EVENT_RUN,
+    })
+}
+
+/// 在处理某个账号的上下文中执行 future，其中输出的日志都带有该账号
+pub fn account_scope<F: Future>(account_index: usize, future: F) -> impl Future<Output = F::Output> {
+    CONTEXT.scope(LogContext { account_index: Some(account_index), event: EVENT_RUN }, future)
+}
+
+/// 在当前账号的某个处理步骤（如 wish、aid、adopt）中执行 future
+///
+/// 账号在调用时取得，返回的 future 交给新任务执行时仍带有调用方的账号
+pub fn event_scope<F: Future>(event: &'static str, future: F) -> impl Future<Output = F::Output> {
+    CONTEXT.scope(LogContext { event, ..current() }, future)
+}
+
+/// 一条 JSON 日志
+#[derive(Serialize)]
+struct JsonRecord<'a> {
+    timestamp: String,
+    level: &'a str,
+    run_id: &'a str,
+    /// 账号序号，从 1 开始，与文本日志中的 [账号-N] 一致
+    #[serde(skip_serializing_if = "Option::is_none")]
+    account_index: Option<usize>,
+    event: &'a str,
+    /// 愿望的关联 ID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    wish: Option<String>,
+    message: String,
+    target: &'a str,
+}
+
+/// 把日志编码为单行 JSON 的 encoder
+#[derive(Debug, Default)]
+pub struct JsonEncoder;
+
+impl Encode for JsonEncoder {
+    fn encode(&self, w: &mut dyn Write, record: &Record) -> anyhow::Result<()> {
+        let LogContext { account_index, event } = current();
+
+        let json = JsonRecord {
+            timestamp: Local::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, false),
+            level: record.level().as_str(),
+            run_id: run_id::current(),
+            account_index: account_index.map(|index| index + 1),
+            event,
+            wish: correlation::current(),
+            message: record.args().to_string(),
+            target: record.target(),
+        };
+
+        serde_json::to_writer(&mut *w, &json)?;
+        w.write_all(b"\n")?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn concurrent_tasks_keep_their_own_event() {
+        assert_eq!(current().event, EVENT_RUN);
+        account_scope(2, async {
+            let aid = tokio::spawn(event_scope("aid", async {
+                tokio::task::yield_now().await;
+                current()
+            }));
+            let adopt = tokio::spawn(event_scope("adopt", async {
+                tokio::task::yield_now().await;
+                current()
+            }));
+            let (aid, adopt) = (aid.await.unwrap(), adopt.await.unwrap());
+            assert_eq!((aid.account_index, aid.event), (Some(2), "aid"));
+            assert_eq!((adopt.account_index, adopt.event), (Some(2), "adopt"));
+            assert_eq!(current().event, EVENT_RUN);
+        })
+        .await;
+        assert_eq!(current().account_index, None);
+    }
+}
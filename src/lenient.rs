@@ -0,0 +1,119 @@
+//! 宽松的反序列化器
+//!
+//! 115 偶尔会把同一字段在数字和字符串之间来回切换（如 `aid: 123` 与 `aid: "123"`），
+//! 这些反序列化器同时接受两种形式，避免单个字段类型变化导致整个响应解析失败。
+//! 无数据时 115 也可能用 null 代替空列表
+
+use serde::{de::Error, Deserialize, Deserializer};
+use serde_json::Value;
+use std::str::FromStr;
+
+/// 接受数字或数字字符串的整数字段，空字符串和 null 视为 0
+pub fn int<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: TryFrom<i64> + FromStr + Default,
+{
+    match Value::deserialize(deserializer)? {
+        Value::Null => Ok(T::default()),
+        Value::Number(n) => {
+            let n = n
+                .as_i64()
+                .or_else(|| n.as_f64().filter(|f| (i64::MIN as f64..i64::MAX as f64).contains(f)).map(|f| f as i64))
+                .ok_or_else(|| D::Error::custom(format!("无法解析的数字: {}", n)))?;
+            T::try_from(n).map_err(|_| D::Error::custom(format!("数字超出范围: {}", n)))
+        }
+        Value::String(s) if s.trim().is_empty() => Ok(T::default()),
+        Value::String(s) => s
+            .trim()
+            .parse()
+            .map_err(|_| D::Error::custom(format!("无法解析为数字的字符串: {}", s))),
+        Value::Bool(b) => T::try_from(i64::from(b))
+            .map_err(|_| D::Error::custom("布尔值无法转换为数字")),
+        other => Err(D::Error::custom(format!("期望数字或字符串，实际为: {}", other))),
+    }
+}
+
+/// 接受字符串或数字的字符串字段，null 视为空字符串
+pub fn string<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Value::deserialize(deserializer)? {
+        Value::Null => Ok(String::new()),
+        Value::String(s) => Ok(s),
+        Value::Number(n) => Ok(n.to_string()),
+        Value::Bool(b) => Ok(b.to_string()),
+        other => Err(D::Error::custom(format!("期望字符串或数字，实际为: {}", other))),
+    }
+}
+
+/// 值为 null 时视为类型默认值（如空列表），字段缺失时需配合 `#[serde(default)]`
+pub fn null_as_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + Default,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Deserialize)]
+    struct Fields {
+        #[serde(default, deserialize_with = "int")]
+        small: i32,
+        #[serde(default, deserialize_with = "int")]
+        large: i64,
+        #[serde(default, deserialize_with = "string")]
+        text: String,
+        #[serde(default, deserialize_with = "null_as_default")]
+        list: Vec<String>,
+    }
+
+    fn parse(json: &str) -> Result<Fields, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    #[test]
+    fn int_accepts_numbers_and_numeric_strings() {
+        let fields = parse(r#"{"small": 12, "large": "34"}"#).unwrap();
+        assert_eq!((fields.small, fields.large), (12, 34));
+        let fields = parse(r#"{"small": " 5 ", "large": 6.0}"#).unwrap();
+        assert_eq!((fields.small, fields.large), (5, 6));
+    }
+
+    #[test]
+    fn int_treats_empty_string_and_null_as_zero() {
+        let fields = parse(r#"{"small": "", "large": null}"#).unwrap();
+        assert_eq!((fields.small, fields.large), (0, 0));
+    }
+
+    #[test]
+    fn int_rejects_out_of_range_and_garbage() {
+        assert!(parse(r#"{"small": 3000000000}"#).is_err());
+        assert!(parse(r#"{"small": "3000000000"}"#).is_err());
+        assert!(parse(r#"{"large": 1e30}"#).is_err());
+        assert!(parse(r#"{"large": "99999999999999999999"}"#).is_err());
+        assert!(parse(r#"{"small": "abc"}"#).is_err());
+        assert!(parse(r#"{"small": [1]}"#).is_err());
+    }
+
+    #[test]
+    fn string_accepts_strings_numbers_and_null() {
+        assert_eq!(parse(r#"{"text": "abc"}"#).unwrap().text, "abc");
+        assert_eq!(parse(r#"{"text": 123}"#).unwrap().text, "123");
+        assert_eq!(parse(r#"{"text": ""}"#).unwrap().text, "");
+        assert_eq!(parse(r#"{"text": null}"#).unwrap().text, "");
+        assert!(parse(r#"{"text": {}}"#).is_err());
+    }
+
+    #[test]
+    fn null_as_default_handles_null_missing_and_values() {
+        assert!(parse(r#"{"list": null}"#).unwrap().list.is_empty());
+        assert!(parse("{}").unwrap().list.is_empty());
+        assert_eq!(parse(r#"{"list": ["a"]}"#).unwrap().list, vec!["a".to_string()]);
+    }
+}
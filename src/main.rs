@@ -8,83 +8,364 @@
 //! - 采纳助力
 //! - 多账号处理
 
+mod account;
+mod aid_view;
+mod audit;
+mod audit_log;
+mod balancer;
+mod button;
+mod cache;
+mod checkpoint;
+mod cli;
+mod content;
+mod content_filter;
+mod cookie;
+mod correlation;
+mod crypto;
+mod curl;
+mod defaults;
+mod diag;
+mod events;
+mod exit_code;
+mod failures;
+mod flow;
+mod form;
+mod health;
+mod history;
+mod idempotency;
+mod json_log;
+mod http;
+mod lenient;
+mod memory;
+mod notify;
+mod overrides;
+mod pace;
+mod paging;
+mod perms;
+mod preview;
+mod probe;
+mod proxy;
+mod report;
+mod reward;
+mod retry;
+mod risk;
+mod run_id;
+mod secret;
+mod setup;
+mod sign;
+mod status;
+mod syslog_appender;
+mod throttle;
+mod timestamp;
+mod unknown_fields;
+mod user_agent;
+mod vcr;
+
 use anyhow::Result;
-use config::{ConfigError, File};
-use log::{error, info, warn, LevelFilter};
-use reqwest::{Client, ClientBuilder};
+use config::{ConfigError, File, FileFormat};
+use log::LevelFilter;
+use tracing::{debug, error, info, instrument, warn};
+use reqwest::{header::COOKIE, Client, ClientBuilder, RequestBuilder, Response, StatusCode};
 use serde::{Deserialize, Serialize};
 use std::{
+    borrow::Cow,
     collections::HashSet,
-    fs,
+    fmt, fs,
     path::Path,
+    process::ExitCode,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, OnceLock,
+    },
+    time::{Duration, Instant},
 };
+use tokio::{sync::Semaphore, task::JoinSet};
 use log4rs::{
     append::{
         console::ConsoleAppender,
         file::FileAppender,
     },
-    config::{Appender, Config, Root},
-    encode::pattern::PatternEncoder,
+    config::{Appender, Config, Logger, Root},
+    encode::{pattern::PatternEncoder, Encode},
+    Handle,
 };
+use account::AccountConfig;
+use audit::{AuditConfig, AuditState, OnAuditTimeout};
+use audit_log::{AuditLogConfig, AUDIT_TARGET};
+use balancer::{AidBalancer, BalanceConfig};
+use button::DesireButton;
+use cache::TtlCache;
 use chrono::Local;
+use checkpoint::Checkpoint;
+use cli::CliArgs;
+use events::{Event, EventSink};
+use exit_code::RunStatus;
+use content::{ContentPool, DEFAULT_AID_CONTENT, DEFAULT_WISH_CONTENT};
+use content_filter::{Checked, ContentFilter, ContentFilterConfig};
+use correlation::CorrelatedEncoder;
+use failures::FailureTracker;
+use flow::{AccountFlow, WishState};
+use form::{BodyEncodings, FormFieldNames};
+use health::HealthStatus;
+use history::{DiffReportConfig, RunSnapshot};
+use http::{ErrorAction, NetErrorKind};
+use idempotency::{IdempotencyStore, Operation};
+use json_log::JsonEncoder;
+use notify::NotifyChannel;
+use paging::Pager;
+use preview::RequestPreview;
+use probe::{ProbeOutcome, ProbeResult};
+use proxy::{ProxyConfig, ProxyPool};
+use report::{AccountReport, SuccessRateAlertConfig, WishOutcome, WishReward};
+use reward::{RewardSpaceConfig, RewardSpaceStrategy};
+use retry::{RetryConfig, RetryPolicy};
+use secret::Secret;
+use sign::RequestSigner;
+use throttle::{Throttle, ThrottleConfig};
+use syslog_appender::SyslogAppender;
+use user_agent::UserAgentConfig;
+use vcr::{Cassette, VcrConfig, VcrMode};
 
 // Constants
-const CONFIG_FILE_PATH: &str = "config.yaml";
+const DEFAULT_CONFIG_FILE_PATH: &str = "config.yaml";
+const ENCRYPTED_CONFIG_SUFFIX: &str = ".enc"; // 加密配置文件 = 配置文件路径 + 后缀
+const DEFAULT_STATE_DIR: &str = "state";
+
+/// 默认的诊断包目录
+const DEFAULT_DIAG_DIR: &str = "diag";
+const DEFAULT_ADOPT_TO_CID: &str = "0"; // 采纳奖励默认存入根目录
+const DEFAULT_AID_CONCURRENCY: usize = 1; // 单账号内同时助力的愿望数
+const DEFAULT_DISABLE_AFTER_FAILURES: u32 = 3; // 账号连续失败多少轮后自动禁用
+const DEFAULT_COMPRESSION_MIN_SIZE: usize = 1024; // 请求体达到该字节数才压缩
+const DEFAULT_MAX_RESPONSE_SIZE: usize = 1024 * 1024; // 响应体读取上限（字节）
+const DEFAULT_SYSLOG_FACILITY: &str = "user"; // 写入 syslog 时默认的 facility
 const DEFAULT_WAIT_TIME: u64 = 60; // 默认等待时间(秒)
-const USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36";
+const AID_SUCCESS_DELAY: u64 = 10; // 助力成功后的等待时间(秒)
+const ADOPT_DELAY: u64 = 3; // 助力后到采纳、批量采纳之间的等待时间(秒)
+const ACCOUNT_INTERVAL: u64 = 30; // 相邻两个账号之间的等待时间(秒)
+const PENDING_WISH_LIMIT: usize = 10; // 每次获取的待处理愿望数
+const STATUS_WISH_LIMIT: usize = 50; // status 命令每个账号列出的愿望数
+const DESIRE_PAGE_SIZE: usize = 20; // 愿望列表每页请求的条目数
+const AID_LIST_LIMIT: &str = "20"; // 助力关联视图中每个愿望列出的助力数
+const DEFAULT_DESIRE_CODE_CACHE_SIZE: usize = 256;
+const DEFAULT_DESIRE_CODE_CACHE_TTL: u64 = 600; // 愿望 code 缓存有效期(秒)
+const DEFAULT_DESIRE_INFO_DEBOUNCE: u64 = 5; // 同一愿望详情的最小请求间隔(秒)
+const DEFAULT_ACCOUNT_SOFT_LIMIT: usize = 50; // 超过该账号数时提示一轮的预计耗时
+const DEFAULT_TIME_SCALE: f64 = 1.0;
+// 助力请求模拟 115 iOS 客户端；Host、Content-Length、Accept-Encoding、Connection 由 reqwest 按实际请求生成
+const AID_USER_AGENT: &str = "Mozilla/5.0 (iPhone; CPU iPhone OS 12_3_1 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Mobile/15E148 UDown/32.9.2";
+const USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/133.0.0.0 Safari/537.36";
 
 // API Endpoints
-const API_BASE_URL: &str = "https://act.115.com/api/1.0/web/1.0/act2024xys";
+const API_BASE_URL: &str = "https://act.115.com/api/1.0/web/1.0";
+const DEFAULT_ACTIVITY_PATH: &str = "act2024xys"; // 活动标识，接口地址为 API_BASE_URL/<activity_path>/<端点>
 const WISH_ENDPOINT: &str = "/wish";
 const MY_DESIRE_ENDPOINT: &str = "/my_desire";
 const AID_DESIRE_ENDPOINT: &str = "/aid_desire";
 const ADOPT_ENDPOINT: &str = "/adopt";
 const GET_DESIRE_INFO_ENDPOINT: &str = "/get_desire_info";
+const DESIRE_AID_LIST_ENDPOINT: &str = "/desire_aid_list";
+const FILE_MOVE_URL: &str = "https://webapi.115.com/files/move"; // 网盘文件移动接口
+const SPACE_INFO_URL: &str = "https://webapi.115.com/files/index_info"; // 网盘空间信息接口
+
+/// 拼接接口完整地址
+fn api_url(api_base: &str, activity_path: &str, endpoint: &str) -> String {
+    format!("{}/{}{}", api_base, activity_path.trim_matches('/'), endpoint)
+}
+
+/// 默认配置文件开头的说明，完整的配置项见仓库中的 config.yaml 示例
+const DEFAULT_CONFIG_HEADER: &str = "\
+# 115 许愿助手配置，填写 aid_cookie 和 wish_cookies 中的 cookie 后即可运行
+# 完整的配置项及说明见仓库中的 config.yaml 示例
+#
+# wish_contents: 许愿内容候选，每个账号从中随机挑选一条，可以写多条避免所有账号内容相同
+#   distinct_wish_contents 为 true 时同一轮内不同账号尽量使用不同的内容
+# reward_space: 许愿的奖励空间（rewardSpace），strategy 可选：
+#   fixed 固定为 value；random 在 min~max 之间随机；dynamic 按账号网盘剩余空间取值，不超过 max
+
+";
+
+/// 配置文件路径，由 --config 指定，默认为当前目录下的 config.yaml
+static CONFIG_PATHS: OnceLock<(String, String)> = OnceLock::new();
+
+/// 设置配置文件路径，需在读取配置之前调用，只有第一次调用生效
+fn set_config_file_path(path: &str) {
+    let _ = CONFIG_PATHS.set((path.to_string(), format!("{}{}", path, ENCRYPTED_CONFIG_SUFFIX)));
+}
+
+fn config_paths() -> &'static (String, String) {
+    CONFIG_PATHS.get_or_init(|| {
+        (
+            DEFAULT_CONFIG_FILE_PATH.to_string(),
+            format!("{}{}", DEFAULT_CONFIG_FILE_PATH, ENCRYPTED_CONFIG_SUFFIX),
+        )
+    })
+}
+
+/// 明文配置文件路径
+fn config_file_path() -> &'static str {
+    &config_paths().0
+}
+
+/// 加密配置文件路径
+fn encrypted_config_file_path() -> &'static str {
+    &config_paths().1
+}
 
-/// 设置日志系统
-fn setup_logger() -> Result<()> {
+/// 初始化日志系统，返回的 Handle 用于加载配置后按 logging 配置重新设置
+fn setup_logger() -> Result<Handle> {
     // 创建 logs 目录
     std::fs::create_dir_all("logs")?;
 
-    // 生成日志文件名（使用当前日期）
-    let log_file_name = format!(
-        "logs/115helper_{}.log",
-        Local::now().format("%Y-%m-%d")
-    );
+    // 初始化日志系统
+    let handle = log4rs::init_config(logger_config(&LoggingConfig::default())?)?;
+
+    info!("日志系统初始化完成");
+    Ok(handle)
+}
+
+/// 日志文件名（使用当前日期）
+fn log_file_name() -> String {
+    format!("logs/115helper_{}.log", Local::now().format("%Y-%m-%d"))
+}
+
+/// 按 logging 配置构建日志配置
+fn logger_config(logging: &LoggingConfig) -> Result<Config> {
+    let log_file_name = log_file_name();
+
+    // 日志格式
+    let encoder = || -> Box<dyn Encode> {
+        match logging.format {
+            LogFormat::Text => Box::new(CorrelatedEncoder(PatternEncoder::new(&format!(
+                "{{d(%Y-%m-%d %H:%M:%S)}} [{{l}}] [{}] - {{m}}{{n}}",
+                run_id::current()
+            )))),
+            LogFormat::Json => Box::new(JsonEncoder),
+        }
+    };
 
     // 控制台输出
     let stdout = ConsoleAppender::builder()
-        .encoder(Box::new(PatternEncoder::new("{d(%Y-%m-%d %H:%M:%S)} [{l}] - {m}{n}")))
+        .encoder(encoder())
         .build();
 
     // 文件输出
     let file = FileAppender::builder()
-        .encoder(Box::new(PatternEncoder::new("{d(%Y-%m-%d %H:%M:%S)} [{l}] - {m}{n}")))
+        .encoder(encoder())
         .build(log_file_name)?;
 
     // 创建日志配置
-    let config = Config::builder()
+    let mut builder = Config::builder()
         .appender(Appender::builder().build("stdout", Box::new(stdout)))
-        .appender(Appender::builder().build("file", Box::new(file)))
-        .build(
-            Root::builder()
-                .appender("stdout")
-                .appender("file")
-                .build(LevelFilter::Info),
-        )?;
+        .appender(Appender::builder().build("file", Box::new(file)));
+    let mut root = Root::builder().appender("stdout").appender("file");
 
-    // 初始化日志系统
-    log4rs::init_config(config)?;
+    // syslog 输出
+    if logging.syslog {
+        let syslog = SyslogAppender::new(&logging.facility)?;
+        builder = builder.appender(Appender::builder().build("syslog", Box::new(syslog)));
+        root = root.appender("syslog");
+    }
 
-    info!("日志系统初始化完成");
-    Ok(())
+    // 审计日志单独写入一个文件，不进入控制台和普通日志
+    if logging.audit_log.enabled {
+        let audit = FileAppender::builder()
+            .encoder(Box::new(PatternEncoder::new("{m}{n}")))
+            .build(&logging.audit_log.path)?;
+        builder = builder
+            .appender(Appender::builder().build("audit", Box::new(audit)))
+            .logger(Logger::builder().appender("audit").additive(false).build(AUDIT_TARGET, LevelFilter::Info));
+    }
+
+    // 其他库的日志默认很多，只在 debug 及以上级别时打开连接相关的部分
+    let level = LevelFilter::from(logging.level);
+    for target in CONNECTION_LOG_TARGETS {
+        let target_level = if level >= LevelFilter::Debug { level } else { LevelFilter::Warn };
+        builder = builder.logger(Logger::builder().build(target, target_level));
+    }
+
+    Ok(builder.build(root.build(level))?)
+}
+
+/// 接口返回中除 state/code/message 外携带更精确错误信息的字段
+#[derive(Deserialize, Debug, Default)]
+struct ApiErrorFields {
+    #[serde(default)]
+    errcode: Option<serde_json::Value>,
+    #[serde(default)]
+    errno: Option<serde_json::Value>,
+    #[serde(default)]
+    error: Option<serde_json::Value>,
+}
+
+impl fmt::Display for ApiErrorFields {
+    /// 仅输出存在且非空的字段，全部缺失时输出空字符串
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let fields = [("errcode", &self.errcode), ("errno", &self.errno), ("error", &self.error)];
+        let parts: Vec<String> = fields
+            .iter()
+            .filter_map(|(name, value)| match value {
+                None | Some(serde_json::Value::Null) => None,
+                Some(serde_json::Value::String(v)) if v.is_empty() => None,
+                Some(serde_json::Value::String(v)) => Some(format!("{}: {}", name, v)),
+                Some(v) => Some(format!("{}: {}", name, v)),
+            })
+            .collect();
+
+        if parts.is_empty() {
+            Ok(())
+        } else {
+            write!(f, " [{}]", parts.join(", "))
+        }
+    }
+}
+
+/// 登录状态失效（cookie 过期）时返回的错误码
+const LOGIN_EXPIRED_CODE: i32 = 990001;
+
+/// 登录状态失效时 message 中出现的说法，只匹配明确表示未登录或需重新登录的短语
+const LOGIN_EXPIRED_MESSAGES: [&str; 4] = ["登录状态已失效", "登录已过期", "请重新登录", "未登录"];
+
+/// 接口返回中用于判断登录状态的字段，code 可能是数字也可能是字符串
+#[derive(Deserialize, Debug, Default)]
+struct LoginState {
+    #[serde(default, deserialize_with = "lenient::int")]
+    code: i64,
+    #[serde(default, deserialize_with = "lenient::string")]
+    message: String,
+}
+
+impl LoginState {
+    /// 按错误码或明确的失效说法判断 cookie 是否已失效
+    fn is_expired(&self) -> bool {
+        self.code == i64::from(LOGIN_EXPIRED_CODE) || LOGIN_EXPIRED_MESSAGES.iter().any(|phrase| self.message.contains(phrase))
+    }
+}
+
+/// 已知的 115 错误码及说明，用于服务端未返回 message 时补充
+const KNOWN_ERROR_CODES: &[(i32, &str)] = &[
+    (LOGIN_EXPIRED_CODE, "登录状态已失效，请更新 cookie"),
+];
+
+/// 失败日志中的错误描述：message 为空时按错误码补充说明，未知的错误码提示参考状态码
+fn describe_message(message: &str, state: i32, code: i32) -> Cow<'_, str> {
+    if !message.trim().is_empty() {
+        return Cow::Borrowed(message);
+    }
+    match KNOWN_ERROR_CODES.iter().find(|(known, _)| *known == code) {
+        Some((_, description)) => Cow::Owned(format!("服务端未返回错误信息，按错误码推测: {}", description)),
+        None => Cow::Owned(format!("服务端未返回错误信息，请参考状态码 {}/{}", state, code)),
+    }
 }
+
 #[derive(Deserialize, Debug)]
 struct WishResponse {
     state: i32,
     code: i32,
     message: String,
     data: WishData,
+    #[serde(flatten)]
+    error_fields: ApiErrorFields,
 }
 
 #[derive(Deserialize, Debug)]
@@ -99,11 +380,16 @@ struct MyDesiresResponse {
     code: i32,
     message: String,
     data: MyDesiresData,
+    #[serde(flatten)]
+    error_fields: ApiErrorFields,
 }
 
 #[derive(Deserialize, Debug)]
 struct MyDesiresData {
+    /// 无数据时可能为 null 或缺失，视为空列表
+    #[serde(default, deserialize_with = "lenient::null_as_default")]
     list: Vec<DesireItem>,
+    #[serde(default, deserialize_with = "lenient::int")]
     count: i32,
 }
 
@@ -113,584 +399,4318 @@ struct DesireInfoResponse {
     code: i32,
     message: String,
     data: DesireInfo,
+    #[serde(flatten)]
+    error_fields: ApiErrorFields,
 }
 
-#[derive(Debug, Deserialize)]
+#[allow(dead_code)] // 字段与接口返回结构保持一致，部分暂未使用
+#[derive(Debug, Clone, Deserialize)]
 struct UserInfo {
     user_name: String,
     face_l: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[allow(dead_code)] // 字段与接口返回结构保持一致，部分暂未使用
+#[derive(Debug, Clone, Deserialize)]
 struct DesireInfo {
+    #[serde(deserialize_with = "lenient::string")]
     id: String,
     content: String,
     images: String,
+    /// 最后编辑时间的 Unix 时间戳，展示时用 timestamp 模块转换
+    #[serde(deserialize_with = "lenient::int")]
     edit_time: i64,
+    #[serde(deserialize_with = "lenient::int")]
     audit_status: i32,
+    #[serde(deserialize_with = "lenient::int")]
     status: i32,
+    #[serde(deserialize_with = "lenient::int")]
     aid: i64,
+    #[serde(deserialize_with = "lenient::int")]
     reward: i64,
+    #[serde(deserialize_with = "lenient::int")]
     sj_reward: i64,
+    #[serde(deserialize_with = "lenient::string")]
     code: String,
+    #[serde(deserialize_with = "lenient::int")]
     aid_num: i32,
     images_data: Vec<String>,
     user_info: UserInfo,
+    #[serde(deserialize_with = "lenient::int")]
     is_my_desire: i32,
+    /// 查询者对该愿望可执行的操作，取值见 button 模块
+    #[serde(deserialize_with = "lenient::int")]
     button: i32,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct AppConfig {
-    aid_cookie: String,
-    wish_cookies: Vec<String>,
-}
+impl DesireInfo {
+    /// 查询者对该愿望可执行的操作
+    fn button(&self) -> DesireButton {
+        DesireButton::from_value(self.button)
+    }
 
-impl AppConfig {
-    /// 加载配置文件，如果不存在则创建默认配置
-    pub fn load() -> Result<Self, ConfigError> {
-        if !Path::new(CONFIG_FILE_PATH).exists() {
-            Self::create_default_config()?;
-            println!("已创建默认配置文件 config.yaml，请修改其中的 cookie 值后再运行程序。");
-            std::process::exit(1);
-        }
+    /// 查询者能否为该愿望助力
+    fn can_aid(&self) -> bool {
+        self.button().can_aid()
+    }
 
-        config::Config::builder()
-            .add_source(File::with_name(CONFIG_FILE_PATH))
-            .build()?
-            .try_deserialize()
+    /// 查询者能否采纳该愿望的助力
+    fn can_adopt(&self) -> bool {
+        self.button().can_adopt()
     }
+}
 
-    /// 创建默认配置文件
-    fn create_default_config() -> Result<(), ConfigError> {
-        let default_config = AppConfig {
-            aid_cookie: String::new(),
-            wish_cookies: vec![String::new()],
-        };
+/// 采纳策略
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum AdoptStrategy {
+    /// 助力成功后立即采纳
+    #[default]
+    Immediate,
+    /// 先为所有愿望助力，账号处理结束前统一采纳
+    Batch,
+}
 
-        let yaml = serde_yaml::to_string(&default_config)
-            .map_err(|e| ConfigError::Message(e.to_string()))?;
+/// 操作使用哪个账号的 cookie
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum CookieRole {
+    /// 当前处理的许愿账号
+    Wish,
+    /// 助愿账号（aid_cookie）
+    Aid,
+}
 
-        fs::write(CONFIG_FILE_PATH, yaml)
-            .map_err(|e| ConfigError::Message(e.to_string()))?;
+impl fmt::Display for CookieRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CookieRole::Wish => write!(f, "wish"),
+            CookieRole::Aid => write!(f, "aid"),
+        }
+    }
+}
 
-        Ok(())
+/// 各操作使用的 cookie 角色，许愿固定使用许愿账号，助力固定使用助愿账号
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+struct CookieRoles {
+    /// 采纳助力，采纳后奖励文件存入该账号的网盘，移动奖励文件也使用该账号
+    adopt: CookieRole,
+}
+
+impl Default for CookieRoles {
+    fn default() -> Self {
+        Self { adopt: CookieRole::Wish }
     }
 }
 
-#[derive(Deserialize, Debug)]
-struct DesireItem {
-    code: String,
-    aid_num: i32,
+/// 待处理愿望的排序字段
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SortField {
+    Code,
+    Reward,
+    CreateTime,
 }
 
-#[derive(Deserialize, Debug)]
-struct AidResponse {
-    state: i32,
-    code: i32,
-    message: String,
-    data: serde_json::Value,
+/// 一级排序键，写作 `字段` 或 `字段:asc`、`字段:desc`；
+/// 省略方向时奖励降序（高的优先），其余升序
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(into = "String")]
+struct SortKey {
+    field: SortField,
+    descending: bool,
 }
 
-#[derive(Deserialize, Debug)]
-struct AdoptResponse {
-    state: i32,
-    code: i32,
-    message: String,
-    data: serde_json::Value,
+impl SortKey {
+    fn compare(&self, a: &DesireItem, b: &DesireItem) -> std::cmp::Ordering {
+        let ordering = match self.field {
+            SortField::Code => a.code.cmp(&b.code),
+            SortField::Reward => a.reward.cmp(&b.reward),
+            SortField::CreateTime => a.create_time.cmp(&b.create_time),
+        };
+        if self.descending { ordering.reverse() } else { ordering }
+    }
 }
 
-/// 单账号客户端
-#[derive(Clone)]
-struct Api115ClientSingle {
-    client: Client,
-    wish_cookie: String,
-    aid_cookie: String,
-    account_index: usize,
+impl TryFrom<String> for SortKey {
+    type Error = String;
+
+    fn try_from(value: String) -> std::result::Result<Self, Self::Error> {
+        let (name, direction) = match value.split_once(':') {
+            Some((name, direction)) => (name.trim(), Some(direction.trim())),
+            None => (value.trim(), None),
+        };
+        let field = match name {
+            "code" => SortField::Code,
+            "reward" => SortField::Reward,
+            "create_time" => SortField::CreateTime,
+            other => return Err(format!("未知的排序字段: {}，可选 code、reward、create_time", other)),
+        };
+        let descending = match direction {
+            None => field == SortField::Reward,
+            Some("asc") => false,
+            Some("desc") => true,
+            Some(other) => return Err(format!("未知的排序方向: {}，可选 asc、desc", other)),
+        };
+        Ok(Self { field, descending })
+    }
 }
 
-impl Api115ClientSingle {
-    /// 创建新的单账号客户端实例
-    pub fn new(wish_cookie: String, aid_cookie: String, client: Client, account_index: usize) -> Self {
-        Self {
-            client,
-            wish_cookie,
-            aid_cookie,
-            account_index,
-        }
+impl From<SortKey> for String {
+    fn from(key: SortKey) -> Self {
+        let name = match key.field {
+            SortField::Code => "code",
+            SortField::Reward => "reward",
+            SortField::CreateTime => "create_time",
+        };
+        format!("{}:{}", name, if key.descending { "desc" } else { "asc" })
     }
+}
 
-    /// 处理单个账号的所有操作
-    async fn process_single_account(&self) -> Result<()> {
-        let account_msg = format!("===== 开始处理第 {} 个账号 =====", self.account_index + 1);
-        info!("{}", account_msg);
+/// 待处理愿望的处理顺序，多级排序键依次比较，可写单个键或键的列表
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "OneOrMany<String>")]
+struct WishOrder(Vec<SortKey>);
+
+impl Default for WishOrder {
+    fn default() -> Self {
+        Self(vec![SortKey { field: SortField::Code, descending: false }])
+    }
+}
 
-        // 执行许愿操作
-        self.handle_wish_process().await?;
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum OneOrMany<T> {
+    One(T),
+    Many(Vec<T>),
+}
 
-        // 处理待处理愿望
-        self.handle_pending_wishes().await?;
+impl TryFrom<OneOrMany<String>> for WishOrder {
+    type Error = String;
 
-        Ok(())
+    fn try_from(keys: OneOrMany<String>) -> std::result::Result<Self, Self::Error> {
+        let keys = match keys {
+            OneOrMany::One(key) => vec![key],
+            OneOrMany::Many(keys) => keys,
+        };
+        keys.into_iter().map(SortKey::try_from).collect::<std::result::Result<_, _>>().map(Self)
     }
+}
 
-    /// 处理许愿流程
-    async fn handle_wish_process(&self) -> Result<()> {
-        info!("[账号-{}] 准备开始许愿...", self.account_index + 1);
+impl WishOrder {
+    /// 按各级排序键依次比较排列愿望，全部相同时按 code 排列，保证顺序稳定
+    fn sort(&self, wishes: &mut [DesireItem]) {
+        wishes.sort_by(|a, b| {
+            self.0
+                .iter()
+                .fold(std::cmp::Ordering::Equal, |ordering, key| ordering.then_with(|| key.compare(a, b)))
+                .then_with(|| a.code.cmp(&b.code))
+        });
+    }
+}
 
-        match self.make_wish().await {
-            Ok(Some(wish_id)) => {
-                info!("[账号-{}] 许愿成功完成，ID: {}", self.account_index + 1, wish_id);
-            }
-            Ok(None) => {
-                warn!("[账号-{}] 许愿未成功完成", self.account_index + 1);
-            }
-            Err(e) => {
-                error!("[账号-{}] 许愿过程发生错误: {}", self.account_index + 1, e);
-            }
-        }
+/// 账号处理失败后对后续账号的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum OnAccountFailure {
+    /// 继续处理后续账号
+    #[default]
+    Continue,
+    /// 任一账号处理失败即停止
+    Stop,
+    /// 仅在触发风控时停止
+    StopOnRisk,
+}
 
-        Ok(())
+impl fmt::Display for OnAccountFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OnAccountFailure::Continue => write!(f, "continue"),
+            OnAccountFailure::Stop => write!(f, "stop"),
+            OnAccountFailure::StopOnRisk => write!(f, "stop_on_risk"),
+        }
     }
+}
 
-    /// 处理待处理愿望
-    async fn handle_pending_wishes(&self) -> Result<()> {
-        let pending_wishes = self.get_pending_wishes().await?;
+#[derive(Debug, Serialize, Deserialize)]
+struct AppConfig {
+    aid_cookie: Secret,
+    /// 更多助愿账号，助力时与 aid_cookie 一起按 aid_balance 策略分担
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    aid_cookies: Vec<Secret>,
+    /// 多个助愿账号间的负载均衡
+    #[serde(default)]
+    aid_balance: BalanceConfig,
+    #[serde(
+        deserialize_with = "account::deserialize_accounts",
+        serialize_with = "account::serialize_accounts"
+    )]
+    wish_cookies: Vec<AccountConfig>,
+    /// 账号 CSV 文件，其中的账号追加到 wish_cookies 之后
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    accounts_csv: Option<String>,
+    /// 接口路径中的活动标识，新一期活动换了路径时修改
+    #[serde(default = "default_activity_path")]
+    activity_path: String,
+    /// 整体运行的最大总请求数，不填则不限制
+    #[serde(default)]
+    max_total_requests: Option<u64>,
+    /// 跨账号、跨运行的全局请求速率平滑
+    #[serde(default)]
+    throttle: ThrottleConfig,
+    /// 许愿账号的 User-Agent 池
+    #[serde(default)]
+    user_agent: UserAgentConfig,
+    /// 许愿内容候选，每个账号从中挑选一条
+    #[serde(default = "default_wish_contents")]
+    wish_contents: Vec<String>,
+    /// 同一轮内不同账号是否尽量使用不同的许愿内容
+    #[serde(default = "default_true")]
+    distinct_wish_contents: bool,
+    /// 提交前检测许愿内容中的网址和联系方式
+    #[serde(default)]
+    content_filter: ContentFilterConfig,
+    /// 许愿内容模板文件，每行一条候选，设置后代替 wish_contents
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    wish_content_file: Option<String>,
+    /// 助力内容候选，每次助力随机挑选一条，支持 {date}、{account} 占位符
+    #[serde(default = "default_aid_contents")]
+    aid_contents: Vec<String>,
+    /// 助力内容模板文件，每行一条候选，设置后代替 aid_contents
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    aid_content_file: Option<String>,
+    /// 愿望 code 缓存的最大条目数，0 表示不缓存
+    #[serde(default = "default_desire_code_cache_size")]
+    desire_code_cache_size: usize,
+    /// 愿望 code 缓存有效期(秒)
+    #[serde(default = "default_desire_code_cache_ttl")]
+    desire_code_cache_ttl: u64,
+    /// 同一愿望详情的最小请求间隔(秒)，间隔内的重复请求复用上次结果，0 表示不去抖
+    #[serde(default = "default_desire_info_debounce")]
+    desire_info_debounce: u64,
+    /// 采纳策略：immediate 助力后立即采纳，batch 全部助力完再统一采纳
+    #[serde(default)]
+    adopt_strategy: AdoptStrategy,
+    /// 状态文件目录
+    #[serde(default = "default_state_dir")]
+    state_dir: String,
+    /// 是否把每个账号的流程状态持久化到状态目录
+    #[serde(default)]
+    persist_flow_state: bool,
+    /// 账号整体处理失败时是否把该账号本轮的请求响应、时间线、日志和配置摘要写入诊断包
+    #[serde(default)]
+    diag_on_account_failure: bool,
+    /// 诊断包目录
+    #[serde(default = "default_diag_dir")]
+    diag_dir: String,
+    /// 把处理事件以 JSON Lines 实时写入该路径的 Unix domain socket
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    event_socket: Option<String>,
+    /// 采纳助力时奖励存入的目录 cid，账号级配置优先，不填则存入根目录
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    adopt_to_cid: Option<String>,
+    /// 按错误类别的重试策略
+    #[serde(default)]
+    retry: RetryConfig,
+    /// 已废弃，等同于 retry.rate_limit.times
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    rate_limit_retries: Option<u32>,
+    /// 已废弃，等同于 retry.network.times
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    network_retries: Option<u32>,
+    /// 同一助愿账号相邻两次助力的最小间隔（秒），跨许愿账号生效，0 表示不限制
+    #[serde(default)]
+    aid_cooldown: u64,
+    /// 所有等待时间的缩放系数，0 表示跳过全部等待
+    #[serde(default = "default_time_scale")]
+    time_scale: f64,
+    /// 账号数超过该值时启动时提示一轮的预计耗时，0 表示不提示
+    #[serde(default = "default_account_soft_limit")]
+    account_soft_limit: usize,
+    /// 一轮结束时常驻内存超过该值（MB）则告警，0 表示不告警
+    #[serde(default)]
+    memory_warn_mb: u64,
+    /// 一轮结束后查询各愿望的助力列表，按愿望输出助力者和采纳情况
+    #[serde(default)]
+    aid_view: bool,
+    /// 单个账号内同时助力的愿望数，默认 1 即逐个助力
+    #[serde(default = "default_aid_concurrency")]
+    aid_concurrency: usize,
+    /// 待处理愿望的处理顺序
+    #[serde(default)]
+    wish_order: WishOrder,
+    /// 各操作使用的 cookie 角色
+    #[serde(default)]
+    cookie_roles: CookieRoles,
+    /// 不予助力的愿望 code
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    ignore_wishes: Vec<String>,
+    /// 只为这些愿望 code 助力，不填则处理全部，不能与 ignore_wishes 同时配置
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    only_wishes: Vec<String>,
+    /// 助力前是否先查询愿望详情获取可助力的 code，关闭时直接使用愿望列表中的 code
+    #[serde(default = "default_true")]
+    fetch_code_before_aid: bool,
+    /// 愿望详情的 button 为这些值时也跳过助力，用于 button 模块尚未收录的取值
+    #[serde(default)]
+    skip_aid_buttons: Vec<i32>,
+    /// 采纳成功后把奖励文件移动到的目录 cid，不填则不移动
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    move_rewards_to: Option<String>,
+    /// 与上次运行对比的差异报告
+    #[serde(default)]
+    diff_report: DiffReportConfig,
+    /// 许愿、助力、采纳成功率低于阈值时告警
+    #[serde(default)]
+    success_rate_alert: SuccessRateAlertConfig,
+    /// 许愿奖励空间的取值策略
+    #[serde(default)]
+    reward_space: RewardSpaceConfig,
+    /// 许愿后等待审核的轮询设置
+    #[serde(default)]
+    audit: AuditConfig,
+    /// 运行结束后接收汇总通知的机器人
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    notify: Vec<NotifyChannel>,
+    /// Markdown 报告的输出路径，支持 {date} 占位符，不填则不生成
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    markdown_report: Option<String>,
+    /// 运行结束后把每个账号每次操作的结果追加到该 CSV 文件，支持 {date} 占位符，不填则不写
+    #[serde(default)]
+    csv_report: Option<String>,
+    /// 各接口表单字段名的映射，用于在接口改字段名时快速适配
+    #[serde(default)]
+    form_fields: FormFieldNames,
+    /// 各接口请求体的编码方式
+    #[serde(default)]
+    body_encoding: BodyEncodings,
+    /// 运行前的健康检查
+    #[serde(default)]
+    health_check: HealthCheckConfig,
+    /// 各处理步骤的启用开关
+    #[serde(default)]
+    steps: StepsConfig,
+    /// 账号连续失败多少轮后自动禁用，0 表示不禁用
+    #[serde(default = "default_disable_after_failures")]
+    disable_after_failures: u32,
+    /// 请求体压缩
+    #[serde(default)]
+    request_compression: RequestCompressionConfig,
+    /// 响应体读取上限（字节），超出部分丢弃，0 表示不限制
+    #[serde(default = "default_max_response_size")]
+    max_response_size: usize,
+    /// 接口响应的录制与回放
+    #[serde(default)]
+    vcr: VcrConfig,
+    /// 出口代理池
+    #[serde(default)]
+    proxy: ProxyConfig,
+    /// 日志输出
+    #[serde(default)]
+    logging: LoggingConfig,
+    /// 记录当天已完成的许愿、助力、采纳操作，重跑时跳过
+    #[serde(default = "default_true")]
+    idempotency: bool,
+    /// 处理每个账号前后记录进度，崩溃后重跑时从断点继续
+    #[serde(default = "default_true")]
+    checkpoint: bool,
+    /// 账号处理失败后是否继续处理后续账号
+    #[serde(default)]
+    on_account_failure: OnAccountFailure,
+}
 
-        for wish_id in pending_wishes {
-            if let Ok(Some(aid_id)) = self.aid_desire(&wish_id).await {
-                tokio::time::sleep(tokio::time::Duration::from_secs(3)).await;
+/// 运行前健康检查的配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+struct HealthCheckConfig {
+    /// 是否在处理前检查活动接口
+    enabled: bool,
+    /// 检查未通过时是否仍然继续处理
+    continue_on_failure: bool,
+    /// 是否在处理前逐个检查许愿账号的 cookie 是否已失效，每个账号多一次请求，默认关闭
+    check_cookies: bool,
+}
 
-                match self.adopt_aid(&wish_id, &aid_id).await {
-                    Ok(true) => info!("愿望 {} 的助力已被成功采纳", wish_id),
-                    Ok(false) => warn!("采纳愿望 {} 的助力失败", wish_id),
-                    Err(e) => error!("采纳愿望 {} 的助力时发生错误: {}", wish_id, e),
-                }
-            }
-            tokio::time::sleep(tokio::time::Duration::from_secs(DEFAULT_WAIT_TIME)).await;
+impl Default for HealthCheckConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            continue_on_failure: false,
+            check_cookies: false,
         }
+    }
+}
 
-        Ok(())
+/// 各处理步骤的启用开关，默认全部启用
+///
+/// 关闭获取列表时不会助力和采纳；关闭助力但开启采纳时，只采纳今天已记录助力 ID 的愿望
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+struct StepsConfig {
+    /// 许愿
+    wish: bool,
+    /// 获取待处理愿望列表
+    fetch: bool,
+    /// 助力
+    aid: bool,
+    /// 采纳助力
+    adopt: bool,
+}
+
+impl Default for StepsConfig {
+    fn default() -> Self {
+        Self {
+            wish: true,
+            fetch: true,
+            aid: true,
+            adopt: true,
+        }
     }
+}
 
-    /// 账号创建许愿
-    pub async fn make_wish(&self) -> Result<Option<String>> {
-        info!("开始发送许愿请求...");
+/// 请求体 gzip 压缩的配置
+///
+/// 115 是否接受压缩的请求体尚未确认，默认关闭，开启前请先确认请求能正常返回
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+struct RequestCompressionConfig {
+    /// 是否压缩请求体
+    enabled: bool,
+    /// 请求体达到多少字节才压缩，过小的请求压缩后反而更大
+    min_size: usize,
+}
 
-        let url = "https://act.115.com/api/1.0/web/1.0/act2024xys/wish";
+impl Default for RequestCompressionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_size: DEFAULT_COMPRESSION_MIN_SIZE,
+        }
+    }
+}
 
-        let response = match self.client.post(url)
-            .header("Accept", "application/json, text/plain, */*")
-            .header("Accept-Language", "zh-CN,zh;q=0.9")
-            .header("Cache-Control", "no-cache")
-            .header("Content-Type", "application/x-www-form-urlencoded")
-            .header("Cookie", &self.wish_cookie)
-            .header("Origin", "https://v.115.com")
-            .header("Referer", "https://v.115.com/")
-            .header("User-Agent", "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/133.0.0.0 Safari/537.36")
-            .header("sec-ch-ua", "\"Not(A:Brand\";v=\"99\", \"Google Chrome\";v=\"133\", \"Chromium\";v=\"133\"")
-            .header("sec-ch-ua-mobile", "?0")
-            .header("sec-ch-ua-platform", "\"Windows\"")
-            .form(&[
-                ("content", "gogogog"),
-                ("images", ""),
-                ("rewardSpace", "5"),
-            ])
-            .send()
-            .await {
-            Ok(resp) => resp,
-            Err(e) => {
-                let msg = format!("发送许愿请求失败: {}", e);
-                error!("{}", msg);
-                return Ok(None);
-            }
-        };
+/// 日志格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum LogFormat {
+    /// 纯文本行
+    #[default]
+    Text,
+    /// 每行一条 JSON，便于日志采集
+    Json,
+}
 
-        if !response.status().is_success() {
-            let msg = format!("许愿请求失败，状态码: {}", response.status());
-            error!("{}", msg);
-            return Ok(None);
+/// 日志级别
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum LogLevel {
+    Error,
+    Warn,
+    #[default]
+    Info,
+    /// 同时输出 reqwest/hyper 建连、连接复用等底层细节
+    Debug,
+    Trace,
+}
+
+impl From<LogLevel> for LevelFilter {
+    fn from(level: LogLevel) -> Self {
+        match level {
+            LogLevel::Error => LevelFilter::Error,
+            LogLevel::Warn => LevelFilter::Warn,
+            LogLevel::Info => LevelFilter::Info,
+            LogLevel::Debug => LevelFilter::Debug,
+            LogLevel::Trace => LevelFilter::Trace,
         }
+    }
+}
 
-        let wish_response = match response.json::<WishResponse>().await {
-            Ok(resp) => resp,
-            Err(e) => {
-                let msg = format!("解析许愿响应失败: {}", e);
-                error!("{}", msg);
-                return Ok(None);
-            }
-        };
+/// debug 级别下输出连接细节的 HTTP 库日志 target
+const CONNECTION_LOG_TARGETS: [&str; 3] = ["reqwest", "hyper", "hyper_util"];
 
-        if wish_response.state == 1 && wish_response.code == 0 {
-            let msg = format!("许愿成功！ID: {} 等待60s时间用于审核", wish_response.data.xys_id);
-            info!("{}", msg);
-            // 等待一小段时间，避免请求过于频繁
-            tokio::time::sleep(tokio::time::Duration::from_secs(60)).await;
-            Ok(Some(wish_response.data.xys_id))
-        } else {
-            let msg = format!("许愿失败: {} (状态: {}, 代码: {})",
-                              wish_response.message, wish_response.state, wish_response.code);
-            warn!("{}", msg);
-            Ok(None)
+/// 日志输出的配置，控制台和文件日志始终开启
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+struct LoggingConfig {
+    /// 日志级别，debug 及以上时同时输出 HTTP 连接细节
+    level: LogLevel,
+    /// 控制台和文件日志的格式
+    format: LogFormat,
+    /// 是否同时写入本机 syslog
+    syslog: bool,
+    /// syslog 的 facility，如 user、daemon、local0
+    facility: String,
+    /// 只记录许愿、助力、采纳的审计日志
+    audit_log: AuditLogConfig,
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        Self {
+            level: LogLevel::default(),
+            format: LogFormat::default(),
+            syslog: false,
+            facility: DEFAULT_SYSLOG_FACILITY.to_string(),
+            audit_log: AuditLogConfig::default(),
         }
     }
+}
 
-    pub async fn get_pending_wishes(&self) -> Result<HashSet<String>> {
-        info!("开始获取待处理愿望列表...");
+fn default_wish_contents() -> Vec<String> {
+    vec![DEFAULT_WISH_CONTENT.to_string()]
+}
 
-        let url = "https://act.115.com/api/1.0/web/1.0/act2024xys/my_desire";
+fn default_aid_contents() -> Vec<String> {
+    vec![DEFAULT_AID_CONTENT.to_string()]
+}
 
-        let response = match self.client.get(url)
-            .query(&[
-                ("type", "0"),
-                ("start", "0"),
-                ("page", "1"),
-                ("limit", "10"),
-            ])
-            .header("Accept", "application/json, text/plain, */*")
-            .header("Accept-Language", "zh-CN,zh;q=0.9")
-            .header("Cache-Control", "no-cache")
-            .header("Cookie", &self.wish_cookie)
-            .header("Origin", "https://v.115.com")
-            .header("Referer", "https://v.115.com/")
-            .header("User-Agent", "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/133.0.0.0 Safari/537.36")
-            .send()
-            .await {
-            Ok(resp) => resp,
-            Err(e) => {
-                let msg = format!("获取愿望列表请求失败: {}", e);
-                error!("{}", msg);
-                return Ok(HashSet::new());
-            }
-        };
+fn default_true() -> bool {
+    true
+}
 
-        if !response.status().is_success() {
-            let msg = format!("获取愿望列表失败，状态码: {}", response.status());
-            error!("{}", msg);
-            return Ok(HashSet::new());
-        }
+fn default_aid_concurrency() -> usize {
+    DEFAULT_AID_CONCURRENCY
+}
 
-        let desires_response = match response.json::<MyDesiresResponse>().await {
-            Ok(resp) => resp,
-            Err(e) => {
-                let msg = format!("解析愿望列表响应失败: {}", e);
-                error!("{}", msg);
-                return Ok(HashSet::new());
-            }
-        };
+fn default_disable_after_failures() -> u32 {
+    DEFAULT_DISABLE_AFTER_FAILURES
+}
 
-        if desires_response.state == 1 && desires_response.code == 0 {
-            let pending_wishes: HashSet<String> = desires_response.data.list
-                .into_iter()
-                .filter(|item| item.aid_num == 0)
-                .map(|item| item.code)
-                .collect();
+fn default_max_response_size() -> usize {
+    DEFAULT_MAX_RESPONSE_SIZE
+}
 
-            let msg = format!("成功获取到 {} 个待处理愿望", pending_wishes.len());
-            info!("{}", msg);
-            Ok(pending_wishes)
-        } else {
-            let msg = format!("获取愿望列表失败: {} (状态: {}, 代码: {})",
-                              desires_response.message, desires_response.state, desires_response.code);
-            warn!("{}", msg);
-            Ok(HashSet::new())
+fn default_state_dir() -> String {
+    DEFAULT_STATE_DIR.to_string()
+}
+
+fn default_activity_path() -> String {
+    DEFAULT_ACTIVITY_PATH.to_string()
+}
+
+fn default_diag_dir() -> String {
+    DEFAULT_DIAG_DIR.to_string()
+}
+
+fn default_desire_code_cache_size() -> usize {
+    DEFAULT_DESIRE_CODE_CACHE_SIZE
+}
+
+fn default_account_soft_limit() -> usize {
+    DEFAULT_ACCOUNT_SOFT_LIMIT
+}
+
+fn default_time_scale() -> f64 {
+    DEFAULT_TIME_SCALE
+}
+
+fn default_desire_info_debounce() -> u64 {
+    DEFAULT_DESIRE_INFO_DEBOUNCE
+}
+
+fn default_desire_code_cache_ttl() -> u64 {
+    DEFAULT_DESIRE_CODE_CACHE_TTL
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            aid_cookie: Secret::default(),
+            aid_cookies: Vec::new(),
+            aid_balance: BalanceConfig::default(),
+            wish_cookies: Vec::new(),
+            accounts_csv: None,
+            activity_path: DEFAULT_ACTIVITY_PATH.to_string(),
+            max_total_requests: None,
+            throttle: ThrottleConfig::default(),
+            wish_contents: default_wish_contents(),
+            distinct_wish_contents: true,
+            content_filter: ContentFilterConfig::default(),
+            user_agent: UserAgentConfig::default(),
+            wish_content_file: None,
+            aid_contents: default_aid_contents(),
+            aid_content_file: None,
+            desire_code_cache_size: DEFAULT_DESIRE_CODE_CACHE_SIZE,
+            desire_code_cache_ttl: DEFAULT_DESIRE_CODE_CACHE_TTL,
+            desire_info_debounce: DEFAULT_DESIRE_INFO_DEBOUNCE,
+            adopt_strategy: AdoptStrategy::default(),
+            state_dir: default_state_dir(),
+            diag_on_account_failure: false,
+            diag_dir: default_diag_dir(),
+            event_socket: None,
+            persist_flow_state: false,
+            adopt_to_cid: None,
+            retry: RetryConfig::default(),
+            rate_limit_retries: None,
+            network_retries: None,
+            aid_cooldown: 0,
+            time_scale: DEFAULT_TIME_SCALE,
+            account_soft_limit: DEFAULT_ACCOUNT_SOFT_LIMIT,
+            memory_warn_mb: 0,
+            aid_view: false,
+            aid_concurrency: DEFAULT_AID_CONCURRENCY,
+            wish_order: WishOrder::default(),
+            cookie_roles: CookieRoles::default(),
+            ignore_wishes: Vec::new(),
+            only_wishes: Vec::new(),
+            fetch_code_before_aid: true,
+            skip_aid_buttons: Vec::new(),
+            move_rewards_to: None,
+            diff_report: DiffReportConfig::default(),
+            success_rate_alert: SuccessRateAlertConfig::default(),
+            reward_space: RewardSpaceConfig::default(),
+            audit: AuditConfig::default(),
+            notify: Vec::new(),
+            markdown_report: None,
+            csv_report: None,
+            form_fields: FormFieldNames::default(),
+            body_encoding: BodyEncodings::default(),
+            health_check: HealthCheckConfig::default(),
+            steps: StepsConfig::default(),
+            disable_after_failures: DEFAULT_DISABLE_AFTER_FAILURES,
+            request_compression: RequestCompressionConfig::default(),
+            max_response_size: DEFAULT_MAX_RESPONSE_SIZE,
+            vcr: VcrConfig::default(),
+            proxy: ProxyConfig::default(),
+            logging: LoggingConfig::default(),
+            idempotency: true,
+            checkpoint: true,
+            on_account_failure: OnAccountFailure::default(),
         }
     }
+}
 
-    pub async fn aid_desire(&self, wish_id: &str) -> Result<Option<String>> {
-        info!("开始为愿望 {} 提供助力...", wish_id);
+impl AppConfig {
+    /// 加载配置文件，如果不存在则创建默认配置；配置了 accounts_csv 时合并其中的账号
+    pub fn load() -> Result<Self, ConfigError> {
+        Self::read_config()?.merge_accounts_csv()
+    }
 
-        let wish_code = self.get_desire_code(wish_id).await?;
+    fn read_config() -> Result<Self, ConfigError> {
+        if Path::new(encrypted_config_file_path()).exists() {
+            if Path::new(config_file_path()).exists() {
+                warn!("同时存在明文配置 {} 和加密配置 {}，将使用加密配置，建议删除明文配置",
+                      config_file_path(), encrypted_config_file_path());
+            }
+            return Self::read_encrypted(encrypted_config_file_path()).map(Self::resolve_relative_paths);
+        }
 
-        if wish_code == "" {
-            let msg = format!("获取愿望 {} 的详情失败", wish_code);
-            error!("{}", msg);
-            return Ok(None);
+        if !Path::new(config_file_path()).exists() {
+            Self::create_default_config()?;
+            println!("已创建默认配置文件 {}，请修改其中的 cookie 值后再运行程序。", config_file_path());
+            println!("也可以运行 ./wish_115 --setup 按提示粘贴 cookie 自动生成配置。");
+            // 默认配置还不能直接运行，按配置错误退出
+            std::process::exit(RunStatus::ConfigError.code().into());
         }
 
-        let url = "https://act.115.com/api/1.0/web/1.0/act2024xys/aid_desire";
+        if let Some(mode) = perms::too_open(Path::new(config_file_path())) {
+            warn!(
+                "配置文件 {} 的权限为 {:o}，同组或其他用户也可以读取其中的 cookie，建议执行 chmod 600 {} 或运行 ./wish_115 --fix-perms",
+                config_file_path(), mode, config_file_path()
+            );
+        }
 
-        let payload = [
-            ("id", wish_code),
-            ("content", String::from("gogogo")),  // 使用相同的内容
-            ("images", String::new()),
-            ("file_ids", String::new()),
-        ];
+        Self::read_from_file(config_file_path()).map(Self::resolve_relative_paths)
+    }
 
-        let response = match self.client
-            .post(url)
-            .header("Host", "act.115.com")
-            .header("Accept", "application/json, text/plain, */*")
-            .header("Sec-Fetch-Site", "same-site")
-            .header("Accept-Language", "zh-CN,zh-Hans;q=0.9")
-            .header("Accept-Encoding", "gzip, deflate, br")
-            .header("Sec-Fetch-Mode", "cors")
-            .header("Content-Type", "application/x-www-form-urlencoded")
-            .header("Content-Length", "42")
-            .header("Origin", "https://v.115.com")
-            .header("User-Agent", "Mozilla/5.0 (iPhone; CPU iPhone OS 12_3_1 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Mobile/15E148 UDown/32.9.2")
-            .header("Referer", "https://v.115.com/")
-            .header("Connection", "keep-alive")
-            .header("Sec-Fetch-Dest", "empty")
-            .header("Cookie", &self.aid_cookie)
-            .form(&payload)
-            .send()
-            .await
-        {
-            Ok(resp) => resp,
-            Err(e) => {
-                let msg = format!("为愿望 {} 提供助力请求失败: {}", wish_id, e);
-                error!("{}", msg);
-                return Ok(None);
-            }
+    /// 把 accounts_csv 中的账号追加到 wish_cookies 之后
+    fn merge_accounts_csv(mut self) -> Result<Self, ConfigError> {
+        let Some(path) = &self.accounts_csv else {
+            return Ok(self);
         };
 
-        let status = response.status();
-        let response_text = response.text().await?;
-        info!("服务器响应状态: {}", status);
-        info!("服务器响应内容: {}", response_text);
+        let accounts = account::load_csv(path).map_err(|e| ConfigError::Message(format!("{:#}", e)))?;
+        info!("从账号 CSV 文件 {} 导入 {} 个账号", path, accounts.len());
+        self.wish_cookies.extend(accounts);
+        Ok(self)
+    }
 
-        let aid_response: AidResponse = match serde_json::from_str(&response_text) {
-            Ok(resp) => resp,
-            Err(e) => {
-                let msg = format!("解析愿望 {} 助力响应失败: {} (响应内容: {})",
-                                  wish_id, e, response_text);
-                error!("{}", msg);
-                return Ok(None);
+    /// 把配置中的相对路径改为相对于配置文件所在目录
+    ///
+    /// 只在运行时加载配置后调用，写回配置文件时保留用户填写的原始路径
+    fn resolve_relative_paths(mut self) -> Self {
+        let Some(base) = Path::new(config_file_path()).parent().filter(|dir| !dir.as_os_str().is_empty()) else {
+            return self;
+        };
+        let resolve = |path: &mut String| {
+            if !path.is_empty() && Path::new(path.as_str()).is_relative() {
+                *path = base.join(path.as_str()).to_string_lossy().into_owned();
             }
         };
 
-        if aid_response.state == 1 && aid_response.code == 0 {
-            if let Some(data) = aid_response.data.as_object() {
-                if let Some(aid_id) = data.get("aid_id").and_then(|v| v.as_str()) {
-                    info!("助力成功，等待10s时间防止频繁请求");
-                    tokio::time::sleep(tokio::time::Duration::from_secs(10)).await;
-                    let msg = format!("为愿望 {} 助力成功！aid_id: {}", wish_id, aid_id);
-                    info!("{}", msg);
-                    return Ok(Some(aid_id.to_string()));
-                }
+        resolve(&mut self.state_dir);
+        resolve(&mut self.diag_dir);
+        resolve(&mut self.logging.audit_log.path);
+        resolve(&mut self.vcr.cassette);
+        for path in [
+            &mut self.accounts_csv,
+            &mut self.event_socket,
+            &mut self.wish_content_file,
+            &mut self.aid_content_file,
+            &mut self.markdown_report,
+            &mut self.csv_report,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            resolve(path);
+        }
+        self
+    }
+
+    /// 用命令行传入的 cookie 替换配置文件中的对应项，命令行优先
+    fn apply_cli_cookies(&mut self, cli: &CliArgs) {
+        if !cli.wish_cookies.is_empty() {
+            if !self.wish_cookies.is_empty() {
+                info!("使用命令行传入的 {} 个许愿账号 cookie，忽略配置文件中的 wish_cookies", cli.wish_cookies.len());
             }
-            let msg = format!("为愿望 {} 助力成功但未返回 aid_id", wish_id);
-            warn!("{}", msg);
-            Ok(None)
-        } else {
-            let msg = format!("为愿望 {} 助力失败: {} (状态: {}, 代码: {})",
-                              wish_id, aid_response.message, aid_response.state, aid_response.code);
-            warn!("{}", msg);
-            Ok(None)
+            self.wish_cookies = cli.wish_cookies.iter().cloned().map(AccountConfig::new).collect();
+        }
+        if let Some(aid_cookie) = &cli.aid_cookie {
+            if !self.aid_cookie.is_empty() {
+                info!("使用命令行传入的 aid cookie，忽略配置文件中的 aid_cookie");
+            }
+            self.aid_cookie = Secret::new(aid_cookie.clone());
         }
     }
-    // 添加采纳助力的方法
-    pub async fn adopt_aid(&self, wish_id: &str, aid_id: &str) -> Result<bool> {
-        info!("开始采纳愿望 {} 的助力 {}...", wish_id, aid_id);
 
-        let url = "https://act.115.com/api/1.0/web/1.0/act2024xys/adopt";
+    /// 只保留带有给定标签之一的许愿账号，没有账号匹配时返回错误
+    fn retain_tagged_accounts(&mut self, tags: &[String]) -> Result<()> {
+        let selected: Vec<String> = self
+            .wish_cookies
+            .iter()
+            .enumerate()
+            .filter(|(_, account)| account.has_any_tag(tags))
+            .map(|(index, account)| match &account.name {
+                Some(name) => format!("#{}（{}）", index + 1, name),
+                None => format!("#{}", index + 1),
+            })
+            .collect();
+        if selected.is_empty() {
+            anyhow::bail!("没有账号带有标签 {}，请检查 wish_cookies 中各账号的 tags", tags.join(", "));
+        }
 
-        let response = match self.client.post(url)
-            .header("Accept", "application/json, text/plain, */*")
-            .header("Accept-Language", "zh-CN,zh;q=0.9")
-            .header("Content-Type", "application/x-www-form-urlencoded")
-            .header("Cookie", &self.wish_cookie)  // 使用许愿的 cookie
-            .header("Origin", "https://v.115.com")
-            .header("Referer", "https://v.115.com/")
-            .header("User-Agent", "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/133.0.0.0 Safari/537.36")
-            .form(&[
-                ("did", wish_id),
-                ("aid", aid_id),
-                ("to_cid", "0"),
-            ])
-            .send()
-            .await
-        {
-            Ok(resp) => resp,
-            Err(e) => {
-                let msg = format!("采纳助力请求失败: {}", e);
-                error!("{}", msg);
-                return Ok(false);
-            }
-        };
+        info!(
+            "按标签 {} 选中 {} 个账号（共 {} 个）: {}，以下日志中的账号序号按选中后的顺序编号",
+            tags.join(", "),
+            selected.len(),
+            self.wish_cookies.len(),
+            selected.join(", ")
+        );
+        self.wish_cookies.retain(|account| account.has_any_tag(tags));
+        Ok(())
+    }
 
-        if !response.status().is_success() {
-            let msg = format!("采纳助力失败，状态码: {}", response.status());
-            error!("{}", msg);
-            return Ok(false);
+    /// 校验可以按账号覆盖的配置项，全局配置和各账号合并 overrides 后的配置都要通过
+    fn validate(&self) -> Result<()> {
+        if !self.ignore_wishes.is_empty() && !self.only_wishes.is_empty() {
+            anyhow::bail!("ignore_wishes 与 only_wishes 不能同时配置，请只保留其中一个");
+        }
+        if self.cookie_roles.adopt == CookieRole::Aid && self.aid_cookie.is_empty() {
+            anyhow::bail!("cookie_roles.adopt 指定使用 aid，但未配置 aid_cookie");
         }
+        self.reward_space.validate().map_err(|e| anyhow::anyhow!("奖励空间配置无效: {}", e))?;
+        Ok(())
+    }
 
-        let adopt_response = match response.json::<AdoptResponse>().await {
-            Ok(resp) => resp,
-            Err(e) => {
-                let msg = format!("解析采纳助力响应失败: {}", e);
-                error!("{}", msg);
-                return Ok(false);
+    /// 校验各账号的 overrides：合并失败时返回指出具体账号的错误，
+    /// 未知字段和不能按账号覆盖的字段只告警
+    fn check_account_overrides(&self) -> Result<()> {
+        let fields = unknown_fields::struct_fields::<AppConfig>();
+        for (index, account) in self.wish_cookies.iter().enumerate() {
+            let Some(overrides) = &account.overrides else {
+                continue;
+            };
+            overrides::apply(self, overrides)
+                .and_then(|merged| merged.validate())
+                .map_err(|e| anyhow::anyhow!("第 {} 个许愿账号的 overrides 无效: {:#}", index + 1, e))?;
+
+            for key in overrides.as_mapping().into_iter().flat_map(|m| m.keys()).filter_map(|k| k.as_str()) {
+                if !fields.contains(&key) {
+                    match unknown_fields::suggest(key, fields) {
+                        Some(suggestion) => warn!("第 {} 个许愿账号的 overrides 中有未知字段 {}，是否想写 {}？", index + 1, key, suggestion),
+                        None => warn!("第 {} 个许愿账号的 overrides 中有未知字段 {}，将被忽略", index + 1, key),
+                    }
+                }
             }
-        };
+            let ignored = overrides::run_level_keys(overrides);
+            if !ignored.is_empty() {
+                warn!(
+                    "第 {} 个许愿账号的 overrides 中 {} 是整轮运行共用的配置，不能按账号覆盖，将被忽略",
+                    index + 1,
+                    ignored.join(", ")
+                );
+            }
+        }
+        Ok(())
+    }
 
-        if adopt_response.state == 1 && adopt_response.code == 0 {
-            let msg = format!("成功采纳愿望 {} 的助力 {}", wish_id, aid_id);
-            info!("{}", msg);
-            Ok(true)
-        } else {
-            let msg = format!("采纳助力失败: {} (状态: {}, 代码: {})",
-                              adopt_response.message, adopt_response.state, adopt_response.code);
-            warn!("{}", msg);
-            Ok(false)
+    /// 规范化所有 cookie，规范化后仍非法时返回指出具体账号的错误
+    fn normalize_cookies(&mut self) -> Result<()> {
+        for (index, account) in self.wish_cookies.iter_mut().enumerate() {
+            account.cookie = cookie::normalize(account.cookie.expose_secret())
+                .map(Secret::new)
+                .map_err(|e| anyhow::anyhow!("第 {} 个许愿账号的 cookie {}", index + 1, e))?;
         }
+        self.aid_cookie = cookie::normalize(self.aid_cookie.expose_secret())
+            .map(Secret::new)
+            .map_err(|e| anyhow::anyhow!("aid_cookie {}", e))?;
+        for (index, aid_cookie) in self.aid_cookies.iter_mut().enumerate() {
+            *aid_cookie = cookie::normalize(aid_cookie.expose_secret())
+                .map(Secret::new)
+                .map_err(|e| anyhow::anyhow!("aid_cookies 中第 {} 个 cookie {}", index + 1, e))?;
+        }
+        Ok(())
     }
 
-    // 获取愿望详情，多这一步的原因是愿望列表中的code，虽然看似一样，但是不知道什么原因，无法助力成功，而通过这个接口获取到的code可以成功助力
-    pub async fn get_desire_code(&self, id: &str) -> Result<String> {
-        info!("开始获取待助力愿望 {} 的详情...", id);
+    /// 账号数超过软上限时的预计耗时提示，未超过或关闭提示时返回 None
+    ///
+    /// 按每个账号都有 PENDING_WISH_LIMIT 个待处理愿望、助力后立即采纳估算，是一轮耗时的上限
+    fn account_count_hint(&self) -> Option<String> {
+        let count = self.wish_cookies.len();
+        if self.account_soft_limit == 0 || count <= self.account_soft_limit {
+            return None;
+        }
 
-        let url = "https://act.115.com/api/1.0/web/1.0/act2024xys/get_desire_info?id=".to_owned() + id;
+        let wishes = PENDING_WISH_LIMIT as u64;
+        let per_wish = DEFAULT_WAIT_TIME + AID_SUCCESS_DELAY + ADOPT_DELAY;
+        let concurrency = self.aid_concurrency.max(1) as u64;
+        let per_account = ACCOUNT_INTERVAL + wishes.div_ceil(concurrency) * per_wish;
+        let total = per_account * count as u64;
+        Some(format!(
+            "共配置了 {} 个账号（超过 {} 个），账号按顺序处理且每步之间有等待，按每个账号 {} 个待处理愿望估算，一轮最长约需 {:.1} 小时。\
+             这不是程序卡住；可适当调高 aid_concurrency，或把账号拆分到多个配置文件（--config）分批运行。\
+             设置 account_soft_limit: 0 可关闭此提示",
+            count,
+            self.account_soft_limit,
+            wishes,
+            total as f64 / 3600.0
+        ))
+    }
 
-        let response = match self.client.get(url)
-            .header("Accept", "application/json, text/plain, */*")
-            .header("Accept-Language", "zh-CN,zh;q=0.9")
-            .header("Cache-Control", "no-cache")
-            .header("Cookie", &self.aid_cookie)
-            .header("Origin", "https://v.115.com")
-            .header("Referer", "https://v.115.com/")
-            .header("User-Agent", "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/133.0.0.0 Safari/537.36")
-            .send()
-            .await {
-            Ok(resp) => resp,
-            Err(e) => {
-                let msg = format!("获取愿望详情请求失败: {}", e);
-                error!("{}", msg);
-                return Ok(String::new());
+    /// 检查各等待时间类配置（秒）的取值，返回需要提示的告警
+    ///
+    /// 这些配置都是无符号数，负值在加载配置时就会报错。0 表示完全不等待，
+    /// 可以使用但可能触发风控；过大的值多半是把毫秒误当成了秒
+    fn timing_warnings(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        let mut check = |name: &str, value: u64, zero: Option<&str>, max: u64| {
+            if value == 0 {
+                if let Some(zero) = zero {
+                    warnings.push(format!("{} 为 0，{}", name, zero));
+                }
+            } else if value > max {
+                warnings.push(format!("{} 为 {} 秒，超过 {} 秒，请确认单位是秒而不是毫秒", name, value, max));
             }
         };
 
-        if !response.status().is_success() {
-            let msg = format!("获取愿望详情失败，状态码: {}", response.status());
-            error!("{}", msg);
-            return Ok(String::new());
+        check("audit.poll_interval", self.audit.poll_interval, Some("按最小间隔 1 秒轮询审核状态，轮询过密容易触发风控"), 600);
+        check("audit.timeout", self.audit.timeout, Some("不等待审核结果，直接按 audit.on_timeout 处理"), 3600);
+        check("aid_cooldown", self.aid_cooldown, None, 3600);
+        check("desire_info_debounce", self.desire_info_debounce, None, 600);
+        check("desire_code_cache_ttl", self.desire_code_cache_ttl, None, 86400);
+        if self.proxy.max_requests > 0 {
+            check("proxy.cooldown", self.proxy.cooldown, Some("代理达到请求数阈值后不冷却，切换代理起不到分散请求的作用"), 86400);
         }
-        // 先获取原始响应文本进行调试
-        let response_text = match response.text().await {
-            Ok(text) => {
-                info!("收到的响应内容: {}", text);
-                text
-            }
-            Err(e) => {
-                let msg = format!("读取响应内容失败: {}", e);
-                error!("{}", msg);
-                return Ok(String::new());
+        for (name, policy) in [
+            ("retry.network", &self.retry.network),
+            ("retry.rate_limit", &self.retry.rate_limit),
+            ("retry.server_error", &self.retry.server_error),
+        ] {
+            if policy.times == 0 {
+                continue;
             }
-        };
+            let zero = (name == "retry.rate_limit").then_some("被限流后不等待立即重试，很可能再次被限流");
+            check(&format!("{}.backoff", name), policy.backoff, zero, 600);
+        }
 
-        // 尝试解析JSON
-        let desire_response: DesireInfoResponse = match serde_json::from_str(&response_text) {
-            Ok(resp) => resp,
-            Err(e) => {
-                let msg = format!("解析愿望详情响应失败: {} \n响应内容: {}", e, response_text);
-                error!("{}", msg);
-                return Ok(String::new());
-            }
-        };
+        warnings
+    }
 
+    /// 把已废弃的 rate_limit_retries、network_retries 合并到 retry 中
+    fn migrate_retry_options(&mut self) {
+        if let Some(times) = self.rate_limit_retries.take() {
+            warn!("rate_limit_retries 已废弃，请改用 retry.rate_limit.times");
+            self.retry.rate_limit.times = times;
+        }
+        if let Some(times) = self.network_retries.take() {
+            warn!("network_retries 已废弃，请改用 retry.network.times");
+            self.retry.network.times = times;
+        }
+    }
 
-        if desire_response.state == 1 && desire_response.code == 0 {
-            let msg = format!("成功获取到 {} 愿望详情", desire_response.data.code);
-            info!("{}", msg);
-            Ok(desire_response.data.code)
-        } else {
-            let msg = format!("获取愿望详情: {} (状态: {}, 代码: {})",
-                              desire_response.message, desire_response.state, desire_response.code);
-            warn!("{}", msg);
-            Ok(String::new())
+    /// 待处理愿望是否在处理范围内（不在 ignore_wishes 中，且配置了 only_wishes 时在其中）
+    fn should_handle_wish(&self, code: &str) -> bool {
+        if self.ignore_wishes.iter().any(|ignored| ignored.trim() == code) {
+            return false;
         }
+        self.only_wishes.is_empty() || self.only_wishes.iter().any(|only| only.trim() == code)
     }
-}
 
-/// 多账号客户端
-#[derive(Clone)]
-struct Api115Client {
-    client: Client,
-    wish_cookies: Vec<String>,
-    aid_cookie: String,
-}
+    /// 找出与助愿账号是同一个 115 用户的许愿账号，返回 (许愿账号序号, 助愿账号序号, 用户 ID)
+    ///
+    /// 同一用户给自己的愿望助力必然失败，按 cookie 中 UID 的用户 ID 比对，序号从 1 开始
+    fn uid_conflicts(&self) -> Vec<(usize, usize, String)> {
+        let aid_ids: Vec<Option<&str>> = self.all_aid_cookies_ref().map(cookie::user_id).collect();
+        let mut conflicts = Vec::new();
+        for (wish_index, account) in self.wish_cookies.iter().enumerate() {
+            let Some(wish_id) = cookie::user_id(account.cookie.expose_secret()) else {
+                continue;
+            };
+            for (aid_index, aid_id) in aid_ids.iter().enumerate() {
+                if *aid_id == Some(wish_id) {
+                    conflicts.push((wish_index + 1, aid_index + 1, wish_id.to_string()));
+                }
+            }
+        }
+        conflicts
+    }
 
-impl Api115Client {
-    /// 创建新的多账号客户端实例
-    pub fn new(wish_cookies: Vec<String>, aid_cookie: String) -> Self {
-        let client = ClientBuilder::new()
-            .gzip(true)
-            .deflate(true)
-            .brotli(true)
-            .build()
-            .unwrap_or_else(|_| Client::new());
+    /// 所有助愿账号的 cookie，aid_cookie 在前，跳过空值
+    fn all_aid_cookies_ref(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(&self.aid_cookie)
+            .chain(&self.aid_cookies)
+            .map(|cookie| cookie.expose_secret().trim())
+            .filter(|cookie| !cookie.is_empty())
+    }
 
-        Self {
-            client,
-            wish_cookies,
-            aid_cookie,
-        }
+    /// 同 all_aid_cookies_ref，返回复制的列表
+    fn all_aid_cookies(&self) -> Vec<String> {
+        self.all_aid_cookies_ref().map(str::to_string).collect()
     }
 
-    /// 对所有账号一个个处理，以防并发风控
-    pub async fn process_all_accounts(&self) -> Result<()> {
-        for (index, wish_cookie) in self.wish_cookies.iter().enumerate() {
-            info!("开始处理第 {} 个账号，共 {} 个账号", index + 1, self.wish_cookies.len());
+    /// 确定账号采纳奖励存入的目录 cid
+    ///
+    /// 优先使用账号级配置，其次是全局配置，都没有则存入根目录；cid 格式无效时告警并回退到根目录
+    fn adopt_cid_for(&self, account: &AccountConfig, account_index: usize) -> String {
+        let Some(cid) = account.adopt_to_cid.as_ref().or(self.adopt_to_cid.as_ref()) else {
+            return DEFAULT_ADOPT_TO_CID.to_string();
+        };
 
-            let single_client = Api115ClientSingle::new(
-                wish_cookie.clone(),
-                self.aid_cookie.clone(),
-                self.client.clone(),
-                index,
+        let cid = cid.trim();
+        if account::is_valid_cid(cid) {
+            cid.to_string()
+        } else {
+            warn!(
+                "[账号-{}] 采纳目录 cid \"{}\" 格式无效（应为纯数字），回退到根目录",
+                account_index + 1,
+                cid
             );
+            DEFAULT_ADOPT_TO_CID.to_string()
+        }
+    }
 
-            if let Err(e) = single_client.process_single_account().await {
-                error!("[账号-{}] 处理账号时出错: {}", index + 1, e);
-            }
-
-            // Add a delay between processing different accounts to avoid rate limiting
-            if index < self.wish_cookies.len() - 1 {
-                info!("等待60秒后处理下一个账号...");
-                tokio::time::sleep(tokio::time::Duration::from_secs(30)).await;
-            }
+    /// 从指定文件读取配置
+    pub fn read_from_file(path: &str) -> Result<Self, ConfigError> {
+        if let Ok(yaml) = fs::read_to_string(path) {
+            Self::warn_unknown_fields(&yaml);
         }
 
-        Ok(())
+        config::Config::builder()
+            .add_source(File::with_name(path))
+            .build()?
+            .try_deserialize()
     }
-}
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    // 初始化日志系统
-    if let Err(e) = setup_logger() {
-        eprintln!("初始化日志系统失败: {}", e);
-        return Ok(());
+    /// 读取并解密加密的配置文件
+    fn read_encrypted(path: &str) -> Result<Self, ConfigError> {
+        let data = fs::read(path)
+            .map_err(|e| ConfigError::Message(format!("读取加密配置文件失败: {}", e)))?;
+        let password = crypto::read_password(false)
+            .map_err(|e| ConfigError::Message(e.to_string()))?;
+        let plaintext = crypto::decrypt(&data, &password)
+            .map_err(|e| ConfigError::Message(e.to_string()))?;
+        let yaml = String::from_utf8(plaintext)
+            .map_err(|e| ConfigError::Message(format!("解密后的配置不是有效的 UTF-8 文本: {}", e)))?;
+        Self::warn_unknown_fields(&yaml);
+
+        config::Config::builder()
+            .add_source(File::from_str(&yaml, FileFormat::Yaml))
+            .build()?
+            .try_deserialize()
     }
 
-    info!("程序开始执行 - {}", Local::now().format("%Y-%m-%d %H:%M:%S"));
+    /// 对配置中未被识别的字段告警，并提示最接近的正确字段名
+    fn warn_unknown_fields(yaml: &str) {
+        for path in unknown_fields::find::<AppConfig>(yaml) {
+            let (parent, name) = path.rsplit_once('.').unwrap_or(("", &path));
+            let candidates = match parent {
+                "" => unknown_fields::struct_fields::<AppConfig>(),
+                "health_check" => unknown_fields::struct_fields::<HealthCheckConfig>(),
+                "steps" => unknown_fields::struct_fields::<StepsConfig>(),
+                "request_compression" => unknown_fields::struct_fields::<RequestCompressionConfig>(),
+                "logging" => unknown_fields::struct_fields::<LoggingConfig>(),
+                "logging.audit_log" => unknown_fields::struct_fields::<AuditLogConfig>(),
+                "vcr" => unknown_fields::struct_fields::<VcrConfig>(),
+                "proxy" => unknown_fields::struct_fields::<ProxyConfig>(),
+                "throttle" => unknown_fields::struct_fields::<ThrottleConfig>(),
+                "user_agent" => unknown_fields::struct_fields::<UserAgentConfig>(),
+                "retry" => unknown_fields::struct_fields::<RetryConfig>(),
+                "retry.network" | "retry.rate_limit" | "retry.server_error" => {
+                    unknown_fields::struct_fields::<RetryPolicy>()
+                }
+                "cookie_roles" => unknown_fields::struct_fields::<CookieRoles>(),
+                "content_filter" => unknown_fields::struct_fields::<ContentFilterConfig>(),
+                "reward_space" => unknown_fields::struct_fields::<RewardSpaceConfig>(),
+                "audit" => unknown_fields::struct_fields::<AuditConfig>(),
+                "diff_report" => unknown_fields::struct_fields::<DiffReportConfig>(),
+                "success_rate_alert" => unknown_fields::struct_fields::<SuccessRateAlertConfig>(),
+                "aid_balance" => unknown_fields::struct_fields::<BalanceConfig>(),
+                "form_fields" => unknown_fields::struct_fields::<FormFieldNames>(),
+                "form_fields.wish" => unknown_fields::struct_fields::<form::WishFormFields>(),
+                "form_fields.aid" => unknown_fields::struct_fields::<form::AidFormFields>(),
+                "form_fields.adopt" => unknown_fields::struct_fields::<form::AdoptFormFields>(),
+                "body_encoding" => unknown_fields::struct_fields::<BodyEncodings>(),
+                _ => &[],
+            };
 
-    // 加载并验证配置
-    let config = match AppConfig::load() {
-        Ok(cfg) => {
-            if cfg.wish_cookies.is_empty() {
-                error!("未配置任何 wish cookie");
-                return Ok(());
-            }
-            if cfg.aid_cookie.is_empty() {
-                error!("未配置 aid cookie");
-                return Ok(());
+            match unknown_fields::suggest(name, candidates) {
+                Some(suggestion) => warn!("配置中的字段 {} 无法识别，不会生效，是否想写 {}？", path, suggestion),
+                None => warn!("配置中的字段 {} 无法识别，不会生效，请检查是否拼写错误", path),
             }
-            cfg
         }
-        Err(e) => {
-            error!("加载配置文件失败: {}", e);
-            return Ok(());
+    }
+
+    /// 把配置写入指定文件
+    pub fn save_to_file(&self, path: &str) -> Result<(), ConfigError> {
+        self.save_with_header(path, "")
+    }
+
+    /// 把配置写入指定文件，header 作为注释写在最前面
+    fn save_with_header(&self, path: &str, header: &str) -> Result<(), ConfigError> {
+        // 写回配置文件需要保留 cookie 明文
+        let yaml = secret::exposed(|| serde_yaml::to_string(self))
+            .map_err(|e| ConfigError::Message(e.to_string()))?;
+
+        fs::write(path, format!("{}{}", header, yaml))
+            .map_err(|e| ConfigError::Message(e.to_string()))?;
+        // 配置中保存着 cookie，只允许所有者读写
+        if let Err(e) = perms::restrict(Path::new(path)) {
+            warn!("设置配置文件 {} 的权限失败: {}", path, e);
         }
-    };
 
-    // 创建客户端并处理所有账号
-    let client = Api115Client::new(config.wish_cookies, config.aid_cookie);
-    if let Err(e) = client.process_all_accounts().await {
-        error!("处理账号时发生错误: {}", e);
+        Ok(())
     }
 
-    info!("所有愿望处理完成 - {}", Local::now().format("%Y-%m-%d %H:%M:%S"));
+    /// 创建默认配置文件
+    fn create_default_config() -> Result<(), ConfigError> {
+        let default_config = AppConfig {
+            wish_cookies: vec![AccountConfig::default()],
+            ..Default::default()
+        };
 
-    Ok(())
-}
\ No newline at end of file
+        default_config.save_with_header(config_file_path(), DEFAULT_CONFIG_HEADER)
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct DesireItem {
+    #[serde(deserialize_with = "lenient::string")]
+    code: String,
+    #[serde(deserialize_with = "lenient::int")]
+    aid_num: i32,
+    #[serde(default, deserialize_with = "lenient::int")]
+    reward: i64,
+    #[serde(default, deserialize_with = "lenient::int")]
+    create_time: i64,
+}
+
+#[derive(Deserialize, Debug)]
+struct AidListResponse {
+    state: i32,
+    code: i32,
+    message: String,
+    #[serde(default, deserialize_with = "lenient::null_as_default")]
+    data: AidListData,
+    #[serde(flatten)]
+    error_fields: ApiErrorFields,
+}
+
+#[derive(Deserialize, Debug, Default)]
+struct AidListData {
+    /// 无数据时可能为 null 或缺失，视为空列表
+    #[serde(default, deserialize_with = "lenient::null_as_default")]
+    list: Vec<AidItem>,
+}
+
+#[derive(Deserialize, Debug)]
+struct AidItem {
+    #[serde(deserialize_with = "lenient::string")]
+    id: String,
+    #[serde(default, deserialize_with = "lenient::null_as_default")]
+    user_info: AidUserInfo,
+}
+
+#[derive(Deserialize, Debug, Default)]
+struct AidUserInfo {
+    #[serde(default)]
+    user_name: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct AidResponse {
+    state: i32,
+    code: i32,
+    message: String,
+    data: serde_json::Value,
+    #[serde(flatten)]
+    error_fields: ApiErrorFields,
+}
+
+/// 助力接口不同版本存放 aid_id 的键，按顺序尝试
+const AID_ID_KEYS: [&str; 3] = ["aid_id", "id", "aid"];
+
+/// 从助力响应的 data 中提取 aid_id，兼容字符串和数字，以及嵌套在 data.info 中的情况
+fn extract_aid_id(data: &serde_json::Value) -> Option<String> {
+    let from_object = |object: &serde_json::Map<String, serde_json::Value>| {
+        AID_ID_KEYS.iter().find_map(|key| match object.get(*key)? {
+            serde_json::Value::String(s) if !s.is_empty() && s != "0" => Some(s.clone()),
+            serde_json::Value::Number(n) if n.as_i64() != Some(0) => Some(n.to_string()),
+            _ => None,
+        })
+    };
+
+    let object = data.as_object()?;
+    from_object(object).or_else(|| object.get("info")?.as_object().and_then(from_object))
+}
+
+/// 采纳响应中可能存放奖励文件 id 的字段名
+const FILE_ID_KEYS: [&str; 3] = ["file_id", "fid", "file_ids"];
+
+/// 从采纳响应的 data 中提取奖励文件 id，兼容单个值、逗号分隔的字符串和数组
+fn extract_file_ids(data: &serde_json::Value) -> Vec<String> {
+    fn collect(value: &serde_json::Value, ids: &mut Vec<String>) {
+        match value {
+            serde_json::Value::String(s) => ids.extend(
+                s.split(',')
+                    .map(str::trim)
+                    .filter(|id| !id.is_empty() && *id != "0")
+                    .map(str::to_string),
+            ),
+            serde_json::Value::Number(n) if n.as_i64() != Some(0) => ids.push(n.to_string()),
+            serde_json::Value::Array(values) => values.iter().for_each(|v| collect(v, ids)),
+            _ => {}
+        }
+    }
+
+    let mut ids = Vec::new();
+    if let Some(object) = data.as_object() {
+        for key in FILE_ID_KEYS {
+            if let Some(value) = object.get(key) {
+                collect(value, &mut ids);
+            }
+        }
+    }
+    ids.dedup();
+    ids
+}
+
+#[allow(dead_code)] // 字段与接口返回结构保持一致，部分暂未使用
+#[derive(Deserialize, Debug)]
+struct AdoptResponse {
+    state: i32,
+    code: i32,
+    message: String,
+    data: serde_json::Value,
+    #[serde(flatten)]
+    error_fields: ApiErrorFields,
+}
+
+/// 请求预算，统计整体运行发出的请求数，达到上限后拒绝发起新请求
+#[derive(Debug, Default)]
+struct RequestBudget {
+    limit: Option<u64>,
+    used: AtomicU64,
+}
+
+impl RequestBudget {
+    pub fn new(limit: Option<u64>) -> Self {
+        Self {
+            limit,
+            used: AtomicU64::new(0),
+        }
+    }
+
+    /// 占用一次请求额度，额度已用完时返回 false
+    fn try_acquire(&self) -> bool {
+        match self.limit {
+            Some(limit) => self
+                .used
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |used| {
+                    (used < limit).then_some(used + 1)
+                })
+                .is_ok(),
+            None => {
+                self.used.fetch_add(1, Ordering::SeqCst);
+                true
+            }
+        }
+    }
+
+    /// 预算是否已经用完
+    fn is_exhausted(&self) -> bool {
+        self.limit.is_some_and(|limit| self.used() >= limit)
+    }
+
+    /// 已发出的请求数
+    fn used(&self) -> u64 {
+        self.used.load(Ordering::SeqCst)
+    }
+}
+
+/// 已达请求预算，用于结束本轮处理
+#[derive(Debug)]
+struct BudgetExhausted {
+    limit: u64,
+}
+
+impl fmt::Display for BudgetExhausted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "已达请求预算（{} 个请求），不再发起新请求", self.limit)
+    }
+}
+
+impl std::error::Error for BudgetExhausted {}
+
+/// 网络环境异常（DNS、TLS 错误），继续请求没有意义，用于结束本轮处理
+#[derive(Debug)]
+struct NetworkUnavailable {
+    kind: NetErrorKind,
+    message: String,
+}
+
+impl fmt::Display for NetworkUnavailable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "网络环境异常（{}）: {}，{}", self.kind, self.message, self.kind.hint())
+    }
+}
+
+impl std::error::Error for NetworkUnavailable {}
+
+/// 活动维护中或已暂停，继续请求只会对每个账号反复失败，用于结束本轮处理
+#[derive(Debug)]
+struct ActivityPaused {
+    message: String,
+}
+
+impl fmt::Display for ActivityPaused {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "115 活动维护中或已暂停: {}", self.message)
+    }
+}
+
+impl std::error::Error for ActivityPaused {}
+
+/// 是否为需要结束本轮处理的错误
+fn is_fatal(e: &anyhow::Error) -> bool {
+    e.is::<BudgetExhausted>() || e.is::<NetworkUnavailable>() || e.is::<ActivityPaused>()
+}
+
+/// 汇总单个愿望的助力结果，只保留第一个需要结束本轮处理的错误
+fn collect_aided(
+    result: Result<Option<(String, String)>>,
+    aided: &mut Vec<(String, String)>,
+    fatal: &mut Option<anyhow::Error>,
+) {
+    match result {
+        Ok(pending_adopt) => aided.extend(pending_adopt),
+        Err(e) => {
+            fatal.get_or_insert(e);
+        }
+    }
+}
+
+/// 一轮运行中各账号共享的状态
+struct RunContext {
+    config: AppConfig,
+    /// 活动接口地址前缀，正常运行时为 API_BASE_URL
+    api_base: String,
+    budget: RequestBudget,
+    /// 全局令牌桶
+    throttle: Throttle,
+    wish_contents: ContentPool,
+    aid_contents: ContentPool,
+    /// (愿望 id, 助愿账号 cookie 指纹) -> 愿望详情的缓存
+    ///
+    /// is_my_desire、button 随查询的助愿账号不同而不同，按助愿账号分别缓存
+    desire_infos: TtlCache<(String, String), DesireInfo>,
+    /// (愿望 id, 查询者 cookie 指纹) -> 最近一次详情请求的结果，用于去抖
+    ///
+    /// is_my_desire、button 等字段随查询者不同而不同，许愿账号和助愿账号的结果不能互相复用
+    desire_info_recent: TtlCache<(String, String), Option<DesireInfo>>,
+    /// 已处理完的各账号结果
+    reports: Mutex<Vec<AccountReport>>,
+    /// 当天已完成的操作
+    idempotency: IdempotencyStore,
+    /// 助力时挑选助愿账号
+    aid_accounts: AidBalancer,
+    /// 接口响应的录制与回放
+    vcr: Cassette,
+    /// 发送前为请求附加签名参数
+    signer: Box<dyn RequestSigner>,
+    /// 出口代理池
+    proxies: ProxyPool,
+    /// 各许愿账号合并 overrides 后的配置，按账号序号对应，未配置 overrides 的账号为 None
+    account_configs: Vec<Option<Arc<AppConfig>>>,
+    /// 各许愿账号覆盖了代理配置时使用的代理池，按账号序号对应
+    account_proxies: Vec<Option<Arc<ProxyPool>>>,
+    /// 许愿内容检测
+    content_filter: ContentFilter,
+    /// 各许愿账号本轮使用的 User-Agent，按账号序号对应
+    user_agents: Vec<String>,
+    /// 处理事件的实时输出
+    events: EventSink,
+}
+
+/// 各请求客户端共用的构建参数，代理池中每个代理的客户端也由它创建
+fn client_builder() -> ClientBuilder {
+    ClientBuilder::new().gzip(true).deflate(true).brotli(true).redirect(http::redirect_policy())
+}
+
+/// 单账号客户端
+///
+/// 账号配置不复制，按序号从共享的运行上下文中读取，账号很多时也只占用少量内存
+#[derive(Clone)]
+struct Api115ClientSingle {
+    client: Client,
+    account_index: usize,
+    /// 采纳奖励存入的目录 cid
+    adopt_to_cid: String,
+    ctx: Arc<RunContext>,
+    /// 本账号本轮的处理结果
+    report: Arc<Mutex<AccountReport>>,
+    /// 开启失败诊断包时记录本账号的请求和响应
+    diag: Option<Arc<diag::Recorder>>,
+    /// 账号配置了 overrides 时合并后的配置，未配置时使用全局配置
+    config: Option<Arc<AppConfig>>,
+    /// 账号覆盖了代理配置时使用的代理池
+    proxies: Option<Arc<ProxyPool>>,
+}
+
+impl Api115ClientSingle {
+    /// 创建新的单账号客户端实例
+    pub fn new(client: Client, account_index: usize, ctx: Arc<RunContext>) -> Self {
+        let config = ctx.account_configs.get(account_index).cloned().flatten();
+        let proxies = ctx.account_proxies.get(account_index).cloned().flatten();
+        let adopt_to_cid = config
+            .as_deref()
+            .unwrap_or(&ctx.config)
+            .adopt_cid_for(&ctx.config.wish_cookies[account_index], account_index);
+        let report = AccountReport {
+            name: ctx.config.wish_cookies[account_index].name.clone().unwrap_or_default(),
+            ..AccountReport::new(account_index)
+        };
+        let diag = ctx
+            .config
+            .diag_on_account_failure
+            .then(|| Arc::new(diag::Recorder::new(account_index)));
+        Self {
+            client,
+            account_index,
+            adopt_to_cid,
+            ctx,
+            report: Arc::new(Mutex::new(report)),
+            diag,
+            config,
+            proxies,
+        }
+    }
+
+    /// 本账号使用的代理池
+    fn proxies(&self) -> &ProxyPool {
+        self.proxies.as_deref().unwrap_or(&self.ctx.proxies)
+    }
+
+    /// 本账号生效的配置，账号配置了 overrides 时为合并后的配置
+    fn config(&self) -> &AppConfig {
+        self.config.as_deref().unwrap_or(&self.ctx.config)
+    }
+
+    /// 本账号的配置
+    fn account(&self) -> &AccountConfig {
+        &self.ctx.config.wish_cookies[self.account_index]
+    }
+
+    /// 本账号本轮使用的 User-Agent
+    fn user_agent(&self) -> &str {
+        &self.ctx.user_agents[self.account_index]
+    }
+
+    /// 活动接口端点的完整地址
+    fn api_url(&self, endpoint: &str) -> String {
+        api_url(&self.ctx.api_base, &self.ctx.config.activity_path, endpoint)
+    }
+
+    /// 按角色取 cookie
+    fn cookie_for(&self, role: CookieRole) -> &str {
+        match role {
+            CookieRole::Wish => self.account().cookie.expose_secret(),
+            CookieRole::Aid => self.config().aid_cookie.expose_secret(),
+        }
+    }
+
+    /// 统一发送请求，所有接口请求都经过这里
+    ///
+    /// 外层错误表示已达请求预算，需要向上传递以结束本轮；内层为请求本身的错误。
+    /// 临时网络错误、HTTP 5xx 和 HTTP 429 按 retry 中对应类别的次数重试，
+    /// 429 优先按 Retry-After 等待，没有该 header 则指数退避
+    async fn send_request(&self, request: RequestBuilder) -> Result<reqwest::Result<Response>> {
+        let retry = &self.config().retry;
+        let mut network_retries = 0;
+        let mut rate_limit_retries = 0;
+        let mut server_error_retries = 0;
+
+        loop {
+            if !self.ctx.budget.try_acquire() {
+                let limit = self.ctx.budget.limit.unwrap_or_default();
+                return Err(BudgetExhausted { limit }.into());
+            }
+            let wait = self.ctx.throttle.acquire();
+            if !wait.is_zero() {
+                debug!("[账号-{}] 全局请求速率已达上限，等待 {} ms", self.account_index + 1, wait.as_millis());
+                pace::sleep(wait).await;
+            }
+
+            // 无法复制的请求（如流式请求体）不能重试，直接发送
+            let Some(attempt) = request.try_clone() else {
+                return self.execute(request).await;
+            };
+
+            let response = match self.execute(attempt).await? {
+                Ok(resp) => resp,
+                Err(e) => {
+                    let kind = http::classify_reqwest_error(&e);
+                    match kind.action() {
+                        ErrorAction::Retry if network_retries < retry.network.times => {
+                            let wait = retry.network.delay(network_retries);
+                            network_retries += 1;
+                            warn!(
+                                "[账号-{}] 网络错误（{}）: {}，{}。{} 秒后进行第 {} 次重试",
+                                self.account_index + 1, kind, e, kind.hint(), wait.as_secs(), network_retries
+                            );
+                            pace::sleep(wait).await;
+                            continue;
+                        }
+                        ErrorAction::Abort => {
+                            return Err(NetworkUnavailable { kind, message: e.to_string() }.into());
+                        }
+                        _ => {
+                            warn!("[账号-{}] 网络错误（{}）: {}，{}", self.account_index + 1, kind, e, kind.hint());
+                            return Ok(Err(e));
+                        }
+                    }
+                }
+            };
+
+            let status = response.status();
+            if status.is_server_error() {
+                if server_error_retries >= retry.server_error.times {
+                    if server_error_retries > 0 {
+                        warn!("[账号-{}] 服务端错误({})，已重试 {} 次，放弃重试", self.account_index + 1, status, server_error_retries);
+                    }
+                    return Ok(Ok(response));
+                }
+                let wait = retry.server_error.delay(server_error_retries);
+                server_error_retries += 1;
+                warn!(
+                    "[账号-{}] 服务端错误({})，{} 秒后进行第 {} 次重试",
+                    self.account_index + 1, status, wait.as_secs(), server_error_retries
+                );
+                pace::sleep(wait).await;
+                continue;
+            }
+
+            if status != StatusCode::TOO_MANY_REQUESTS {
+                return Ok(Ok(response));
+            }
+
+            if rate_limit_retries >= retry.rate_limit.times {
+                warn!("[账号-{}] 请求被限流(429)，已重试 {} 次，放弃重试", self.account_index + 1, rate_limit_retries);
+                return Ok(Ok(response));
+            }
+
+            let wait = match http::retry_after(&response) {
+                Some(wait) => {
+                    info!("[账号-{}] 请求被限流(429)，按 Retry-After 等待 {} 秒", self.account_index + 1, wait.as_secs());
+                    wait
+                }
+                None => {
+                    let wait = retry.rate_limit.delay(rate_limit_retries);
+                    info!("[账号-{}] 请求被限流(429)，退避等待 {} 秒", self.account_index + 1, wait.as_secs());
+                    wait
+                }
+            };
+
+            rate_limit_retries += 1;
+            pace::sleep(wait).await;
+            info!("[账号-{}] 开始第 {} 次重试被限流的请求", self.account_index + 1, rate_limit_retries);
+        }
+    }
+
+    /// 发送一次请求：附加签名参数，按配置压缩较大的请求体，并按配置录制或回放响应
+    ///
+    /// 签名失败、回放模式下找不到匹配的录制时返回错误
+    async fn execute(&self, request: RequestBuilder) -> Result<reqwest::Result<Response>> {
+        let (client, request) = request.build_split();
+        let mut request = match request {
+            Ok(request) => request,
+            Err(e) => return Ok(Err(e)),
+        };
+
+        let vcr = &self.ctx.vcr;
+        let key = vcr::RequestKey::from_request(&request);
+        if vcr.mode() == VcrMode::Replay {
+            return match vcr.replay(&key) {
+                Some(response) => Ok(Ok(response)),
+                None => Err(anyhow::anyhow!("cassette 中没有与 {} 匹配的录制", key)),
+            };
+        }
+
+        // 签名基于未压缩的请求体计算
+        let cookie = request
+            .headers()
+            .get(COOKIE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        self.ctx.signer.sign(&mut request, &cookie)?;
+
+        let compression = &self.config().request_compression;
+        if compression.enabled && http::gzip_body(&mut request, compression.min_size) {
+            debug!("[账号-{}] 请求体已 gzip 压缩: {}", self.account_index + 1, request.url());
+        }
+
+        // 配置了代理池时改用代理对应的客户端发送
+        let client = self.proxies().acquire().unwrap_or(client);
+        let url = request.url().clone();
+        let started = Instant::now();
+        let mut result = client.execute(request).await;
+        match &result {
+            Ok(response) => debug!(
+                "[账号-{}] {} {} -> {} ({:?})，耗时 {} ms",
+                self.account_index + 1, key.method, url, response.status(), response.version(), started.elapsed().as_millis()
+            ),
+            Err(e) => debug!(
+                "[账号-{}] {} {} 失败，耗时 {} ms: {}",
+                self.account_index + 1, key.method, url, started.elapsed().as_millis(), e
+            ),
+        }
+        if let Some(location) = result.as_ref().ok().and_then(http::redirect_location) {
+            warn!(
+                "[账号-{}] {} 返回重定向但未跟随，Location: {}{}",
+                self.account_index + 1, url, location, http::redirect_hint(location)
+            );
+        }
+
+        let max_size = self.config().max_response_size;
+        if max_size > 0 {
+            if let Ok(response) = result {
+                result = http::read_limited(response, max_size).await.map(|(response, truncated)| {
+                    if truncated {
+                        warn!(
+                            "[账号-{}] 响应异常大（超过 {} 字节），已截断，可能是错误页或被风控返回了完整网页: {}",
+                            self.account_index + 1, max_size, url
+                        );
+                    }
+                    response
+                });
+            }
+        }
+
+        if let Some(diag) = &self.diag {
+            result = diag.capture(&key, result).await;
+        }
+
+        match result {
+            Ok(response) if vcr.mode() == VcrMode::Record => Ok(vcr.record(key, response).await),
+            result => Ok(result),
+        }
+    }
+
+    /// 处理单个账号的所有操作
+    #[instrument(name = "account", skip_all, fields(account = self.account_index + 1))]
+    async fn process_single_account(&self) -> Result<()> {
+        let account_msg = format!("===== 开始处理第 {} 个账号 =====", self.account_index + 1);
+        info!("{}", account_msg);
+
+        let mut flow = AccountFlow::new(self.account_index);
+        let result = json_log::account_scope(self.account_index, self.run_flow(&mut flow)).await;
+        if let Err(e) = &result {
+            flow.fail(e.to_string());
+        }
+        self.persist_flow(&flow);
+        if let Some(diag) = &self.diag {
+            diag.set_flow(&flow);
+        }
+
+        self.ctx.reports.lock().unwrap_or_else(|e| e.into_inner()).push(self.report());
+
+        result
+    }
+
+    /// 本账号当前的处理结果
+    fn report(&self) -> AccountReport {
+        self.report.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// 失败信息表明触发风控时记录到本账号的处理结果，表明活动维护或暂停时返回错误结束本轮处理
+    fn note_failure(&self, message: &str) -> Result<()> {
+        if risk::is_maintenance_message(message) {
+            return Err(ActivityPaused { message: message.to_string() }.into());
+        }
+        self.update_report(|r| {
+            if risk::is_risk_message(message) {
+                r.risk.get_or_insert_with(|| message.to_string());
+            }
+            r.last_failure = Some(message.to_string());
+        });
+        Ok(())
+    }
+
+    /// 更新本账号的处理结果，新记录的操作同时作为事件输出
+    fn update_report(&self, update: impl FnOnce(&mut AccountReport)) {
+        let mut report = self.report.lock().unwrap_or_else(|e| e.into_inner());
+        let recorded = report.actions.len();
+        update(&mut report);
+        for action in &report.actions[recorded..] {
+            self.ctx.events.emit(&Event::action(self.account_index, action));
+            self.config().logging.audit_log.record(self.account_index, &report.display_name(), action);
+        }
+    }
+
+    /// 按状态机推进账号处理流程，直到进入终止状态
+    async fn run_flow(&self, flow: &mut AccountFlow) -> Result<()> {
+        // 批量采纳模式下助力阶段暂存的 (wish_id, aid_id)
+        let mut aided = Vec::new();
+        // 许愿审核未通过时跳过助力流程
+        let mut skip_aid = false;
+
+        while !flow.state.is_terminal() {
+            let next = match flow.state {
+                WishState::Init if !self.config().steps.wish => {
+                    info!("[账号-{}] 已关闭许愿步骤（steps.wish: false），跳过许愿", self.account_index + 1);
+                    WishState::Wished
+                }
+                WishState::Init => {
+                    // 执行许愿操作
+                    skip_aid = !json_log::event_scope("wish", self.handle_wish_process()).await?;
+                    WishState::Wished
+                }
+                WishState::Wished if skip_aid => WishState::Done,
+                WishState::Wished => WishState::Aiding,
+                WishState::Aiding if !self.config().steps.fetch => {
+                    info!("[账号-{}] 已关闭获取列表步骤（steps.fetch: false），跳过助力和采纳", self.account_index + 1);
+                    WishState::Adopting
+                }
+                WishState::Aiding => {
+                    // 处理待处理愿望
+                    aided = json_log::event_scope("aid", self.handle_pending_wishes()).await?;
+                    WishState::Adopting
+                }
+                WishState::Adopting => {
+                    json_log::event_scope("adopt", self.handle_batch_adopt(&aided)).await?;
+                    WishState::Done
+                }
+                WishState::Done | WishState::Failed(_) => break,
+            };
+            flow.transition(next)?;
+            self.persist_flow(flow);
+        }
+
+        Ok(())
+    }
+
+    /// 开启失败诊断包时写入本账号的诊断包，写入失败只告警
+    fn write_diag(&self, error: &str) {
+        let Some(diag) = &self.diag else {
+            return;
+        };
+
+        let config = &self.ctx.config;
+        let summary = defaults::render(&AppConfig::default(), config, &builtin_values());
+        let log_file = log_file_name();
+        match diag.write_bundle(Path::new(&config.diag_dir), error, &self.report(), &summary, Path::new(&log_file)) {
+            Ok(dir) => warn!("[账号-{}] 已把本账号的诊断信息写入 {}，反馈问题时可打包发送该目录", self.account_index + 1, dir.display()),
+            Err(e) => warn!("[账号-{}] 写入诊断包失败: {}", self.account_index + 1, e),
+        }
+    }
+
+    /// 按配置把流程状态写入状态目录，写入失败只告警
+    fn persist_flow(&self, flow: &AccountFlow) {
+        if !self.config().persist_flow_state {
+            return;
+        }
+
+        let path = Path::new(&self.config().state_dir)
+            .join(format!("flow_account_{}.json", self.account_index + 1));
+        if let Err(e) = flow.save(&path) {
+            warn!("[账号-{}] 保存流程状态失败: {}", self.account_index + 1, e);
+        }
+    }
+
+    /// 处理许愿流程，返回是否继续本账号的助力流程
+    #[instrument(name = "wish", skip_all)]
+    async fn handle_wish_process(&self) -> Result<bool> {
+        let account_key = cookie::fingerprint(self.account().cookie.expose_secret());
+        if let Some(wish_id) = self.ctx.idempotency.get(Operation::Wish, &account_key) {
+            info!("[账号-{}] 今天已许愿（ID: {}），跳过许愿", self.account_index + 1, wish_id);
+            self.update_report(|r| r.wish = WishOutcome::AlreadyDone(wish_id));
+            return Ok(true);
+        }
+
+        info!("[账号-{}] 准备开始许愿...", self.account_index + 1);
+
+        match self.make_wish().await {
+            Ok(Some(wish_id)) => {
+                info!("[账号-{}] 许愿成功完成，ID: {}", self.account_index + 1, wish_id);
+                self.ctx.idempotency.complete(Operation::Wish, &account_key, &wish_id);
+                self.update_report(|r| {
+                    r.wish = WishOutcome::Success(wish_id.clone());
+                    r.record_success("wish", &wish_id, 0);
+                });
+                return self.wait_for_audit(&wish_id).await;
+            }
+            Ok(None) => {
+                warn!("[账号-{}] 许愿未成功完成", self.account_index + 1);
+                self.update_report(|r| {
+                    r.wish = WishOutcome::Failed;
+                    r.failed += 1;
+                    r.record_failure("wish", "", None);
+                });
+            }
+            Err(e) if is_fatal(&e) => return Err(e),
+            Err(e) => {
+                error!("[账号-{}] 许愿过程发生错误: {}", self.account_index + 1, e);
+                self.update_report(|r| {
+                    r.wish = WishOutcome::Error;
+                    r.failed += 1;
+                    r.record_failure("wish", "", Some(e.to_string()));
+                });
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// 轮询许愿的审核状态，返回是否继续本账号的助力流程
+    #[instrument(name = "audit", skip(self))]
+    async fn wait_for_audit(&self, wish_id: &str) -> Result<bool> {
+        let audit = &self.config().audit;
+        let interval = Duration::from_secs(audit.poll_interval.max(1));
+        let deadline = tokio::time::Instant::now() + pace::scale(Duration::from_secs(audit.timeout));
+        info!("[账号-{}] 等待愿望 {} 审核（最长 {} 秒）...", self.account_index + 1, wish_id, audit.timeout);
+
+        loop {
+            pace::sleep(interval).await;
+
+            if let Some(desire) = self.fetch_desire_info(wish_id, self.account().cookie.expose_secret()).await? {
+                match AuditState::from_status(desire.audit_status) {
+                    AuditState::Approved => {
+                        info!("[账号-{}] 愿望 {} 审核通过", self.account_index + 1, wish_id);
+                        return Ok(true);
+                    }
+                    AuditState::Rejected => {
+                        warn!(
+                            "[账号-{}] 愿望 {} 审核未通过（audit_status: {}），可能是许愿内容不符合要求，跳过本账号的助力流程",
+                            self.account_index + 1, wish_id, desire.audit_status
+                        );
+                        return Ok(false);
+                    }
+                    AuditState::Pending => {}
+                }
+            }
+
+            if tokio::time::Instant::now() >= deadline {
+                break;
+            }
+        }
+
+        let msg = format!(
+            "[账号-{}] 愿望 {} 等待 {} 秒仍未审核通过",
+            self.account_index + 1, wish_id, audit.timeout
+        );
+        match audit.on_timeout {
+            OnAuditTimeout::Skip => {
+                warn!("{}，跳过本账号的助力流程", msg);
+                Ok(false)
+            }
+            OnAuditTimeout::Proceed => {
+                warn!("{}，按配置照常继续助力流程", msg);
+                Ok(true)
+            }
+            OnAuditTimeout::Notify => {
+                warn!("{}，发送通知并跳过本账号的助力流程", msg);
+                if self.config().notify.is_empty() {
+                    warn!("未配置通知渠道（notify），无法发送审核超时通知");
+                }
+                notify::send_all(&self.client, &self.config().notify, "115 许愿审核超时，需要人工处理", &msg).await;
+                Ok(false)
+            }
+        }
+    }
+
+    /// 处理待处理愿望，返回批量采纳模式下待采纳的 (wish_id, aid_id)
+    ///
+    /// 并发度大于 1 时多个愿望同时助力，并发数由信号量限制
+    #[instrument(name = "aid", skip_all)]
+    async fn handle_pending_wishes(&self) -> Result<Vec<(String, String)>> {
+        if self.ctx.aid_accounts.all_exhausted() {
+            warn!("[账号-{}] 所有助愿账号今日助力额度已用完，跳过助力流程", self.account_index + 1);
+            return Ok(Vec::new());
+        }
+
+        let mut pending_wishes = self.get_pending_wishes().await?;
+        pending_wishes.retain(|code| {
+            let handle = self.config().should_handle_wish(code);
+            if !handle {
+                info!("[账号-{}] 愿望 {} 不在处理范围内（ignore_wishes/only_wishes），跳过", self.account_index + 1, code);
+            }
+            handle
+        });
+        if !pending_wishes.is_empty() {
+            if self.config().fetch_code_before_aid {
+                info!("[账号-{}] 助力模式：先查询愿望详情获取 code 再助力", self.account_index + 1);
+            } else {
+                info!("[账号-{}] 助力模式：直接使用愿望列表中的 code 助力（fetch_code_before_aid: false）", self.account_index + 1);
+            }
+        }
+        let concurrency = self.config().aid_concurrency.max(1);
+        // 批量采纳模式下暂存的 (wish_id, aid_id)
+        let mut aided = Vec::new();
+
+        if concurrency == 1 || pending_wishes.len() <= 1 {
+            for wish_id in pending_wishes {
+                aided.extend(correlation::scope(&wish_id.clone(), self.handle_pending_wish(wish_id)).await?);
+            }
+            return Ok(aided);
+        }
+
+        info!(
+            "[账号-{}] 以并发度 {} 为 {} 个待处理愿望助力",
+            self.account_index + 1,
+            concurrency,
+            pending_wishes.len()
+        );
+        let semaphore = Arc::new(Semaphore::new(concurrency));
+        let mut tasks = JoinSet::new();
+        // 需要结束本轮处理的错误，出现后不再启动新的助力
+        let mut fatal = None;
+
+        for wish_id in pending_wishes {
+            let permit = semaphore.clone().acquire_owned().await?;
+            while let Some(joined) = tasks.try_join_next() {
+                collect_aided(joined?, &mut aided, &mut fatal);
+            }
+            if fatal.is_some() {
+                break;
+            }
+
+            let this = self.clone();
+            // 任务本地的日志上下文不会传给新任务，创建时带上当前账号
+            tasks.spawn(json_log::event_scope("aid", async move {
+                let _permit = permit;
+                correlation::scope(&wish_id.clone(), this.handle_pending_wish(wish_id)).await
+            }));
+        }
+
+        while let Some(joined) = tasks.join_next().await {
+            collect_aided(joined?, &mut aided, &mut fatal);
+        }
+
+        match fatal {
+            Some(e) => Err(e),
+            None => Ok(aided),
+        }
+    }
+
+    /// 为单个待处理愿望助力，立即采纳模式下随后采纳；返回批量采纳模式下待采纳的 (wish_id, aid_id)
+    ///
+    /// 只在遇到需要结束本轮处理的错误时返回 Err
+    #[instrument(name = "pending_wish", skip(self))]
+    async fn handle_pending_wish(&self, wish_id: String) -> Result<Option<(String, String)>> {
+        let batch_adopt = self.config().adopt_strategy == AdoptStrategy::Batch;
+
+        // 今天已助力过的愿望不再助力，直接用记录的助力 id 继续采纳
+        if let Some(aid_id) = self.ctx.idempotency.get(Operation::Aid, &wish_id) {
+            info!("[账号-{}] 愿望 {} 今天已助力（助力 ID: {}），跳过助力", self.account_index + 1, wish_id, aid_id);
+            if batch_adopt {
+                return Ok(Some((wish_id, aid_id)));
+            }
+            json_log::event_scope("adopt", self.handle_adopt(&wish_id, &aid_id)).await?;
+            return Ok(None);
+        }
+
+        if !self.config().steps.aid {
+            info!("[账号-{}] 已关闭助力步骤（steps.aid: false），跳过为愿望 {} 助力", self.account_index + 1, wish_id);
+            return Ok(None);
+        }
+
+        let result = self.aid_desire(&wish_id).await;
+        match &result {
+            Ok(Some(aid_id)) => {
+                self.ctx.idempotency.complete(Operation::Aid, &wish_id, aid_id);
+                self.update_report(|r| {
+                    r.aided += 1;
+                    r.record_success("aid", &wish_id, 0);
+                });
+            }
+            Err(e) if is_fatal(e) => {}
+            Ok(None) => self.update_report(|r| {
+                r.failed += 1;
+                r.record_failure("aid", &wish_id, None);
+            }),
+            Err(e) => self.update_report(|r| {
+                r.failed += 1;
+                r.record_failure("aid", &wish_id, Some(e.to_string()));
+            }),
+        }
+
+        let pending_adopt = match result {
+            Ok(Some(aid_id)) if batch_adopt => Some((wish_id, aid_id)),
+            Ok(Some(aid_id)) => {
+                if self.config().steps.adopt {
+                    pace::sleep(Duration::from_secs(ADOPT_DELAY)).await;
+                }
+                json_log::event_scope("adopt", self.handle_adopt(&wish_id, &aid_id)).await?;
+                None
+            }
+            Err(e) if is_fatal(&e) => return Err(e),
+            _ => None,
+        };
+        pace::sleep(Duration::from_secs(DEFAULT_WAIT_TIME)).await;
+
+        Ok(pending_adopt)
+    }
+
+    /// 统一采纳批量模式下暂存的助力
+    async fn handle_batch_adopt(&self, aided: &[(String, String)]) -> Result<()> {
+        if aided.is_empty() {
+            return Ok(());
+        }
+        if !self.config().steps.adopt {
+            info!("[账号-{}] 已关闭采纳步骤（steps.adopt: false），跳过 {} 个待采纳的助力", self.account_index + 1, aided.len());
+            return Ok(());
+        }
+
+        info!("[账号-{}] 开始批量采纳 {} 个助力...", self.account_index + 1, aided.len());
+        for (wish_id, aid_id) in aided {
+            correlation::scope(wish_id, self.handle_adopt(wish_id, aid_id)).await?;
+            pace::sleep(Duration::from_secs(ADOPT_DELAY)).await;
+        }
+
+        Ok(())
+    }
+
+    /// 采纳助力并记录结果，仅在已达请求预算时返回错误
+    async fn handle_adopt(&self, wish_id: &str, aid_id: &str) -> Result<()> {
+        if !self.config().steps.adopt {
+            info!("已关闭采纳步骤（steps.adopt: false），跳过采纳愿望 {} 的助力", wish_id);
+            return Ok(());
+        }
+        if self.ctx.idempotency.get(Operation::Adopt, wish_id).is_some() {
+            info!("愿望 {} 的助力今天已采纳，跳过采纳", wish_id);
+            return Ok(());
+        }
+        // 以采纳所用的账号查询愿望详情，button 表明不可采纳时跳过；查询失败时照常尝试采纳
+        if let Some(desire) = self.fetch_desire_info(wish_id, self.cookie_for(self.config().cookie_roles.adopt)).await? {
+            if !desire.can_adopt() {
+                info!("愿望 {} 当前不可采纳（{}），跳过采纳", wish_id, desire.button());
+                return Ok(());
+            }
+        }
+
+        match self.adopt_aid(wish_id, aid_id).await {
+            Ok(true) => {
+                info!("愿望 {} 的助力已被成功采纳", wish_id);
+                self.ctx.idempotency.complete(Operation::Adopt, wish_id, aid_id);
+                self.update_report(|r| {
+                    r.record_adopted(wish_id);
+                    let reward = r.wish_rewards.get(wish_id).map_or(0, |reward| reward.promised);
+                    r.record_success("adopt", wish_id, reward);
+                });
+            }
+            Ok(false) => {
+                warn!("采纳愿望 {} 的助力失败", wish_id);
+                self.update_report(|r| {
+                    r.failed += 1;
+                    r.record_failure("adopt", wish_id, None);
+                });
+            }
+            Err(e) if is_fatal(&e) => return Err(e),
+            Err(e) => {
+                error!("采纳愿望 {} 的助力时发生错误: {}", wish_id, e);
+                self.update_report(|r| {
+                    r.failed += 1;
+                    r.record_failure("adopt", wish_id, Some(e.to_string()));
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 账号创建许愿
+    pub async fn make_wish(&self) -> Result<Option<String>> {
+        let available = match (self.account().reward_space, self.config().reward_space.strategy) {
+            (None, RewardSpaceStrategy::Dynamic) => self.fetch_available_space().await?,
+            _ => None,
+        };
+        let wish = self.prepare_wish(self.pick_reward_space(available));
+        self.submit_wish(&wish).await
+    }
+
+    /// 本账号许愿的奖励空间：账号级的 reward_space 优先，其次按生效配置中的策略选取
+    fn pick_reward_space(&self, available: Option<u64>) -> u32 {
+        self.account().reward_space.unwrap_or_else(|| self.config().reward_space.pick(available))
+    }
+
+    /// 查询账号网盘的剩余空间（GB，向下取整），查询失败时返回 None
+    async fn fetch_available_space(&self) -> Result<Option<u64>> {
+        let request = self.client.get(SPACE_INFO_URL)
+            .header("Accept", "application/json, text/plain, */*")
+            .header("Cookie", self.account().cookie.expose_secret())
+            .header("User-Agent", self.user_agent());
+
+        let value: serde_json::Value = match self.send_request(request).await? {
+            Ok(resp) => resp.json().await.unwrap_or_default(),
+            Err(e) => {
+                warn!("[账号-{}] 查询网盘剩余空间失败: {}", self.account_index + 1, e);
+                return Ok(None);
+            }
+        };
+
+        // 剩余空间以字节为单位，可能是数字也可能是字符串
+        let remain = value.pointer("/data/space_info/all_remain/size").and_then(|size| {
+            size.as_f64().or_else(|| size.as_str().and_then(|s| s.parse().ok()))
+        });
+        match remain {
+            Some(bytes) => {
+                let gb = (bytes / (1u64 << 30) as f64).floor() as u64;
+                info!("[账号-{}] 网盘剩余空间约 {} GB", self.account_index + 1, gb);
+                Ok(Some(gb))
+            }
+            None => {
+                warn!("[账号-{}] 未能从返回中读取网盘剩余空间，奖励空间取下界", self.account_index + 1);
+                Ok(None)
+            }
+        }
+    }
+
+    /// 构造许愿请求但不发送，用于预览确认
+    pub fn prepare_wish(&self, reward_space: u32) -> RequestPreview {
+        let content = match &self.account().content {
+            Some(content) => content.trim().to_string(),
+            None => self.ctx.wish_contents.pick().trim().to_string(),
+        };
+        // 按配置跳过时内容置空，提交时因内容为空而跳过许愿
+        let content = match self.ctx.content_filter.check(&content) {
+            Checked::Clean(content) => content,
+            Checked::Masked(masked, hits) => {
+                warn!("[账号-{}] 许愿内容中含有{}，已脱敏: {}", self.account_index + 1, hits.join("、"), masked);
+                masked
+            }
+            Checked::Rejected(hits) => {
+                warn!("[账号-{}] 许愿内容中含有{}，按配置跳过: {}", self.account_index + 1, hits.join("、"), content);
+                String::new()
+            }
+        };
+        let fields = &self.config().form_fields.wish;
+
+        let mut headers = vec![
+            ("Accept", "application/json, text/plain, */*".to_string()),
+            ("Accept-Language", "zh-CN,zh;q=0.9".to_string()),
+            ("Cache-Control", "no-cache".to_string()),
+            ("Cookie", self.account().cookie.expose_secret().to_string()),
+            ("Origin", "https://v.115.com".to_string()),
+            ("Referer", "https://v.115.com/".to_string()),
+            ("User-Agent", self.user_agent().to_string()),
+        ];
+        // sec-ch-ua 描述的是默认 User-Agent 对应的浏览器，换用池中的 User-Agent 时不发送，以免前后矛盾
+        if self.user_agent() == USER_AGENT {
+            headers.extend([
+                ("sec-ch-ua", "\"Not(A:Brand\";v=\"99\", \"Google Chrome\";v=\"133\", \"Chromium\";v=\"133\"".to_string()),
+                ("sec-ch-ua-mobile", "?0".to_string()),
+                ("sec-ch-ua-platform", "\"Windows\"".to_string()),
+            ]);
+        }
+
+        RequestPreview {
+            url: self.api_url(WISH_ENDPOINT),
+            headers,
+            form: vec![
+                (fields.content.clone(), content),
+                (fields.images.clone(), String::new()),
+                (fields.reward_space.clone(), reward_space.to_string()),
+            ],
+            encoding: self.config().body_encoding.wish,
+            numeric: vec![fields.reward_space.clone()],
+        }
+    }
+
+    /// 发送构造好的许愿请求
+    pub async fn submit_wish(&self, wish: &RequestPreview) -> Result<Option<String>> {
+        info!("开始发送许愿请求...");
+
+        let content_field = &self.config().form_fields.wish.content;
+        let content = wish.form.iter().find(|(name, _)| name == content_field).map(|(_, content)| content);
+        match content {
+            Some(content) if !content.trim().is_empty() => info!("本次许愿内容: {}", content),
+            _ => {
+                warn!("[账号-{}] 许愿内容为空，跳过许愿", self.account_index + 1);
+                return Ok(None);
+            }
+        }
+
+        debug!("许愿请求体: {}", wish.encoded_body());
+        let request = wish.build(&self.client);
+
+        let response = match self.send_request(request).await? {
+            Ok(resp) => resp,
+            Err(e) => {
+                let msg = format!("发送许愿请求失败: {}", e);
+                error!("{}", msg);
+                return Ok(None);
+            }
+        };
+
+        if !response.status().is_success() {
+            let msg = format!("许愿请求失败，状态码: {}", response.status());
+            error!("{}", msg);
+            self.note_failure(&msg)?;
+            return Ok(None);
+        }
+
+        let wish_response = match response.json::<WishResponse>().await {
+            Ok(resp) => resp,
+            Err(e) => {
+                let msg = format!("解析许愿响应失败: {}", e);
+                error!("{}", msg);
+                return Ok(None);
+            }
+        };
+
+        if wish_response.state == 1 && wish_response.code == 0 {
+            let msg = format!("许愿成功！ID: {}", wish_response.data.xys_id);
+            info!("{}", msg);
+            Ok(Some(wish_response.data.xys_id))
+        } else {
+            let msg = format!("许愿失败: {} (状态: {}, 代码: {}){}",
+                              describe_message(&wish_response.message, wish_response.state, wish_response.code), wish_response.state, wish_response.code, wish_response.error_fields);
+            warn!("{}", msg);
+            self.note_failure(&msg)?;
+            Ok(None)
+        }
+    }
+
+    /// 用本账号的 cookie 请求一次只读的愿望列表接口，返回 cookie 是否已失效
+    ///
+    /// 请求失败或返回其他错误时无法判断，按未失效处理，留给正式流程去报错
+    async fn is_cookie_expired(&self) -> Result<bool> {
+        let request = self.client.get(self.api_url(MY_DESIRE_ENDPOINT))
+            .query(&[
+                ("type", "0"),
+                ("start", "0"),
+                ("page", "1"),
+                ("limit", "1"),
+            ])
+            .header("Accept", "application/json, text/plain, */*")
+            .header("Accept-Language", "zh-CN,zh;q=0.9")
+            .header("Cache-Control", "no-cache")
+            .header("Cookie", self.account().cookie.expose_secret())
+            .header("Origin", "https://v.115.com")
+            .header("Referer", "https://v.115.com/")
+            .header("User-Agent", self.user_agent());
+
+        let response = match self.send_request(request).await? {
+            Ok(resp) => resp,
+            Err(e) => {
+                warn!("[账号-{}] 检查 cookie 的请求失败: {}", self.account_index + 1, e);
+                return Ok(false);
+            }
+        };
+        let Ok(state) = response.json::<LoginState>().await else {
+            return Ok(false);
+        };
+        Ok(state.is_expired())
+    }
+
+    /// 用助愿账号请求一次只读的愿望列表接口，确认活动接口可达且活动仍在进行
+    pub async fn check_activity_health(&self) -> Result<HealthStatus> {
+        let request = self.client.get(self.api_url(MY_DESIRE_ENDPOINT))
+            .query(&[
+                ("type", "0"),
+                ("start", "0"),
+                ("page", "1"),
+                ("limit", "1"),
+            ])
+            .header("Accept", "application/json, text/plain, */*")
+            .header("Accept-Language", "zh-CN,zh;q=0.9")
+            .header("Cache-Control", "no-cache")
+            .header("Cookie", self.config().aid_cookie.expose_secret())
+            .header("Origin", "https://v.115.com")
+            .header("Referer", "https://v.115.com/")
+            .header("User-Agent", USER_AGENT);
+
+        let response = match self.send_request(request).await? {
+            Ok(resp) => resp,
+            Err(e) => return Ok(HealthStatus::Unreachable(e.to_string())),
+        };
+
+        let status = response.status();
+        if !status.is_success() {
+            return Ok(HealthStatus::Unreachable(format!("状态码: {}", status)));
+        }
+
+        let value: serde_json::Value = match response.json().await {
+            Ok(value) => value,
+            Err(e) => return Ok(HealthStatus::Unreachable(format!("返回内容无法解析: {}", e))),
+        };
+
+        let field = |name: &str| value.get(name).and_then(|v| v.as_i64()).unwrap_or(-1) as i32;
+        let message = value.get("message").and_then(|v| v.as_str()).unwrap_or_default();
+        Ok(HealthStatus::from_response(field("state"), field("code"), message))
+    }
+
+    /// 获取待处理愿望的 code，按 wish_order 排序
+    pub async fn get_pending_wishes(&self) -> Result<Vec<String>> {
+        info!("开始获取待处理愿望列表...");
+
+        let Some(list) = self.list_my_desires(PENDING_WISH_LIMIT).await? else {
+            return Ok(Vec::new());
+        };
+        let mut pending: Vec<DesireItem> = list
+            .into_iter()
+            .filter(|item| item.aid_num == 0)
+            .collect();
+        self.config().wish_order.sort(&mut pending);
+
+        let mut seen = HashSet::new();
+        let pending_wishes: Vec<String> = pending
+            .into_iter()
+            .map(|item| item.code)
+            .filter(|code| seen.insert(code.clone()))
+            .collect();
+
+        let msg = format!("成功获取到 {} 个待处理愿望", pending_wishes.len());
+        info!("{}", msg);
+        Ok(pending_wishes)
+    }
+
+    /// 获取本账号最多 limit 个愿望，第一页请求或解析失败时返回 None
+    ///
+    /// 每页固定请求 DESIRE_PAGE_SIZE 条，多取到的在本地截断；条目数以实际获取到的为准，
+    /// count 只用来判断是否还有下一页；后续页失败时返回已获取的部分
+    async fn list_my_desires(&self, limit: usize) -> Result<Option<Vec<DesireItem>>> {
+        let mut pager = Pager::new(limit, DESIRE_PAGE_SIZE);
+        let mut list = Vec::new();
+        loop {
+            let Some(data) = self.request_desire_page(pager.start(), pager.page(), pager.page_size()).await? else {
+                if list.is_empty() {
+                    return Ok(None);
+                }
+                break;
+            };
+            let received = data.list.len();
+            list.extend(data.list);
+            if !pager.record(data.count, received) {
+                break;
+            }
+        }
+
+        if let Some(anomaly) = pager.anomaly() {
+            warn!("接口分页数据异常：{}，以实际获取到的 {} 个愿望为准", anomaly, list.len());
+        }
+        list.truncate(limit);
+        Ok(Some(list))
+    }
+
+    /// 请求愿望列表的一页，请求或解析失败时返回 None
+    async fn request_desire_page(&self, start: usize, page: usize, page_size: usize) -> Result<Option<MyDesiresData>> {
+        let request = self.client.get(self.api_url(MY_DESIRE_ENDPOINT))
+            .query(&[
+                ("type", "0".to_string()),
+                ("start", start.to_string()),
+                ("page", page.to_string()),
+                ("limit", page_size.to_string()),
+            ])
+            .header("Accept", "application/json, text/plain, */*")
+            .header("Accept-Language", "zh-CN,zh;q=0.9")
+            .header("Cache-Control", "no-cache")
+            .header("Cookie", self.account().cookie.expose_secret())
+            .header("Origin", "https://v.115.com")
+            .header("Referer", "https://v.115.com/")
+            .header("User-Agent", self.user_agent());
+
+        let response = match self.send_request(request).await? {
+            Ok(resp) => resp,
+            Err(e) => {
+                let msg = format!("获取愿望列表请求失败: {}", e);
+                error!("{}", msg);
+                return Ok(None);
+            }
+        };
+
+        if !response.status().is_success() {
+            let msg = format!("获取愿望列表失败，状态码: {}", response.status());
+            error!("{}", msg);
+            self.note_failure(&msg)?;
+            return Ok(None);
+        }
+
+        let desires_response = match response.json::<MyDesiresResponse>().await {
+            Ok(resp) => resp,
+            Err(e) => {
+                let msg = format!("解析愿望列表响应失败: {}", e);
+                error!("{}", msg);
+                return Ok(None);
+            }
+        };
+
+        if desires_response.state == 1 && desires_response.code == 0 {
+            Ok(Some(desires_response.data))
+        } else {
+            let msg = format!("获取愿望列表失败: {} (状态: {}, 代码: {}){}",
+                              describe_message(&desires_response.message, desires_response.state, desires_response.code), desires_response.state, desires_response.code, desires_response.error_fields);
+            warn!("{}", msg);
+            self.note_failure(&msg)?;
+            Ok(None)
+        }
+    }
+
+    /// 逐个探测活动接口的可用性
+    ///
+    /// 只读接口按正常参数查询；写接口只带一个空的必填字段，由服务端拒绝而不产生真实操作，dry_run 时不发送
+    async fn probe_endpoints(&self, dry_run: bool) -> Vec<ProbeResult> {
+        let fields = &self.config().form_fields;
+        let encodings = &self.config().body_encoding;
+        let wish_cookie = self.account().cookie.expose_secret();
+        let aid_cookie = self.config().aid_cookie.expose_secret();
+        let empty = |name: &String| [(name.clone(), String::new())];
+
+        let probes = [
+            (
+                MY_DESIRE_ENDPOINT,
+                false,
+                wish_cookie,
+                self.client.get(self.api_url(MY_DESIRE_ENDPOINT))
+                    .query(&[("type", "0"), ("start", "0"), ("page", "1"), ("limit", "1")]),
+            ),
+            (
+                GET_DESIRE_INFO_ENDPOINT,
+                false,
+                wish_cookie,
+                self.client.get(self.api_url(GET_DESIRE_INFO_ENDPOINT)).query(&[("id", "0")]),
+            ),
+            (
+                WISH_ENDPOINT,
+                true,
+                wish_cookie,
+                encodings.wish.attach(self.client.post(self.api_url(WISH_ENDPOINT)), &empty(&fields.wish.content), &[]),
+            ),
+            (
+                AID_DESIRE_ENDPOINT,
+                true,
+                aid_cookie,
+                encodings.aid.attach(self.client.post(self.api_url(AID_DESIRE_ENDPOINT)), &empty(&fields.aid.id), &[]),
+            ),
+            (
+                ADOPT_ENDPOINT,
+                true,
+                self.cookie_for(self.config().cookie_roles.adopt),
+                encodings.adopt.attach(self.client.post(self.api_url(ADOPT_ENDPOINT)), &empty(&fields.adopt.did), &[]),
+            ),
+        ];
+
+        let mut results = Vec::new();
+        for (endpoint, write, cookie, request) in probes {
+            let outcome = if write && dry_run {
+                ProbeOutcome::Skipped
+            } else {
+                let request = request
+                    .header("Accept", "application/json, text/plain, */*")
+                    .header("Accept-Language", "zh-CN,zh;q=0.9")
+                    .header("Cookie", cookie)
+                    .header("Origin", "https://v.115.com")
+                    .header("Referer", "https://v.115.com/")
+                    .header("User-Agent", self.user_agent());
+                // 网络环境异常、已达请求预算也只记为该接口无响应，继续探测其余接口
+                match self.send_request(request).await {
+                    Ok(Ok(response)) => {
+                        let status = response.status().as_u16();
+                        let body = response.text().await.unwrap_or_default();
+                        ProbeOutcome::from_response(status, &body)
+                    }
+                    Ok(Err(e)) => ProbeOutcome::Failed(e.to_string()),
+                    Err(e) => ProbeOutcome::Failed(e.to_string()),
+                }
+            };
+            info!("探测接口 {}: {}", endpoint, outcome);
+            results.push(ProbeResult { endpoint, write, outcome });
+        }
+        results
+    }
+
+    /// 只读地汇总本账号所有愿望的当前状态
+    async fn collect_status(&self) -> Result<Vec<status::WishStatus>> {
+        let Some(list) = self.list_my_desires(STATUS_WISH_LIMIT).await? else {
+            anyhow::bail!("获取愿望列表失败");
+        };
+
+        let mut rows = Vec::new();
+        for item in list {
+            let adopted = self.ctx.idempotency.get(Operation::Adopt, &item.code).is_some();
+            let row = match self.fetch_desire_info(&item.code, self.account().cookie.expose_secret()).await? {
+                Some(desire) => status::WishStatus {
+                    code: item.code,
+                    content: desire.content,
+                    audit_status: Some(desire.audit_status),
+                    status: Some(desire.status),
+                    aid_num: desire.aid_num,
+                    aid: desire.aid,
+                    reward: desire.reward,
+                    actual_reward: desire.sj_reward,
+                    edit_time: Some(desire.edit_time),
+                    adopted,
+                },
+                None => status::WishStatus {
+                    code: item.code,
+                    aid_num: item.aid_num,
+                    reward: item.reward,
+                    adopted,
+                    ..Default::default()
+                },
+            };
+            rows.push(row);
+        }
+        Ok(rows)
+    }
+
+    /// 请求愿望的助力列表，请求或解析失败时返回错误原因
+    async fn list_desire_aids(&self, wish_id: &str) -> Result<std::result::Result<Vec<aid_view::Aider>, String>> {
+        let request = self.client.get(self.api_url(DESIRE_AID_LIST_ENDPOINT))
+            .query(&[
+                ("id", wish_id),
+                ("start", "0"),
+                ("page", "1"),
+                ("limit", AID_LIST_LIMIT),
+            ])
+            .header("Accept", "application/json, text/plain, */*")
+            .header("Accept-Language", "zh-CN,zh;q=0.9")
+            .header("Cache-Control", "no-cache")
+            .header("Cookie", self.account().cookie.expose_secret())
+            .header("Origin", "https://v.115.com")
+            .header("Referer", "https://v.115.com/")
+            .header("User-Agent", self.user_agent());
+
+        let response = match self.send_request(request).await? {
+            Ok(resp) => resp,
+            Err(e) => return Ok(Err(format!("请求失败: {}", e))),
+        };
+        if !response.status().is_success() {
+            return Ok(Err(format!("状态码: {}", response.status())));
+        }
+        let aids_response = match response.json::<AidListResponse>().await {
+            Ok(resp) => resp,
+            Err(e) => return Ok(Err(format!("解析响应失败: {}", e))),
+        };
+
+        if aids_response.state == 1 && aids_response.code == 0 {
+            Ok(Ok(aids_response.data.list.into_iter()
+                .map(|item| aid_view::Aider { aid_id: item.id, name: item.user_info.user_name })
+                .collect()))
+        } else {
+            let msg = format!("{} (状态: {}, 代码: {}){}",
+                              describe_message(&aids_response.message, aids_response.state, aids_response.code), aids_response.state, aids_response.code, aids_response.error_fields);
+            self.note_failure(&msg)?;
+            Ok(Err(msg))
+        }
+    }
+
+    /// 按愿望整理本账号各愿望的助力者和采纳情况，没有助力的愿望不查询助力列表
+    async fn collect_aid_view(&self) -> Result<Vec<aid_view::WishAids>> {
+        let Some(list) = self.list_my_desires(STATUS_WISH_LIMIT).await? else {
+            anyhow::bail!("获取愿望列表失败");
+        };
+
+        let mut wishes = Vec::new();
+        for item in list {
+            let (aiders, error) = if item.aid_num == 0 {
+                (Vec::new(), None)
+            } else {
+                match self.list_desire_aids(&item.code).await? {
+                    Ok(aiders) => (aiders, None),
+                    Err(e) => (Vec::new(), Some(e)),
+                }
+            };
+            wishes.push(aid_view::WishAids {
+                adopted: self.ctx.idempotency.get(Operation::Adopt, &item.code),
+                code: item.code,
+                aiders,
+                error,
+            });
+        }
+        Ok(wishes)
+    }
+
+    #[instrument(name = "aid_desire", skip(self))]
+    pub async fn aid_desire(&self, wish_id: &str) -> Result<Option<String>> {
+        info!("开始为愿望 {} 提供助力...", wish_id);
+
+        let Some(aid_account) = self.ctx.aid_accounts.pick() else {
+            if self.ctx.aid_accounts.len() == 0 {
+                anyhow::bail!("未配置助愿账号");
+            }
+            warn!("所有助愿账号今日助力额度已用完，跳过为愿望 {} 助力", wish_id);
+            return Ok(None);
+        };
+        let aid_index = aid_account.index;
+        if self.ctx.aid_accounts.len() > 1 {
+            info!("愿望 {} 使用第 {} 个助愿账号助力", wish_id, aid_account.index + 1);
+        }
+        let aid_cookie = aid_account.cookie;
+
+        // 列表中的 code 与愿望 id 相同，默认先查详情拿到可助力的 code
+        let wish_code = if self.config().fetch_code_before_aid {
+            let Some(desire) = self.get_desire_info(wish_id, aid_cookie).await? else {
+                let msg = format!("获取愿望 {} 的详情失败", wish_id);
+                error!("{}", msg);
+                return Ok(None);
+            };
+
+            if desire.code.is_empty() {
+                let msg = format!("愿望 {} 的详情中没有可助力的 code", wish_id);
+                error!("{}", msg);
+                return Ok(None);
+            }
+
+            // 愿望详情是用助愿账号查询的，is_my_desire 为真说明助愿账号就是许愿账号
+            if desire.is_my_desire != 0 {
+                let msg = format!("愿望 {} 属于助愿账号自己，不能给自己助力，跳过", wish_id);
+                warn!("{}", msg);
+                return Ok(None);
+            }
+
+            info!(
+                "愿望 {} 的 button: {}（{}，状态: {}，审核状态: {}，编辑时间: {}{}）",
+                wish_id,
+                desire.button,
+                desire.button(),
+                desire.status,
+                desire.audit_status,
+                timestamp::format_local(desire.edit_time),
+                if timestamp::is_today(desire.edit_time) { "，今天" } else { "" }
+            );
+            if !desire.can_aid() {
+                info!("愿望 {} 当前不可助力（{}），跳过助力", wish_id, desire.button());
+                return Ok(None);
+            }
+            if self.config().skip_aid_buttons.contains(&desire.button) {
+                info!("愿望 {} 的 button 为 {}，在 skip_aid_buttons 中，跳过助力", wish_id, desire.button);
+                return Ok(None);
+            }
+
+            // 待处理愿望属于当前许愿账号，顺带记录账号昵称和愿望奖励
+            let reward = WishReward {
+                promised: desire.reward,
+                actual: desire.sj_reward,
+            };
+            if reward.is_shrunk() {
+                warn!("愿望 {} 的实际奖励少于承诺奖励（承诺: {}，实际: {}）", wish_id, reward.promised, reward.actual);
+            } else {
+                info!("愿望 {} 的奖励（承诺: {}，实际: {}）", wish_id, reward.promised, reward.actual);
+            }
+            self.update_report(|r| {
+                if r.nickname.is_empty() {
+                    r.nickname = desire.user_info.user_name.clone();
+                }
+                r.wish_rewards.insert(wish_id.to_string(), reward);
+            });
+            desire.code
+        } else {
+            wish_id.to_string()
+        };
+
+        let wait = self.ctx.aid_accounts.reserve(aid_index, Duration::from_secs(self.config().aid_cooldown));
+        if !wait.is_zero() {
+            info!("第 {} 个助愿账号助力冷却中，等待 {} 秒后为愿望 {} 助力", aid_index + 1, wait.as_secs(), wish_id);
+            pace::sleep(wait).await;
+        }
+        self.ctx.aid_accounts.record_use(aid_index);
+
+        let fields = &self.config().form_fields.aid;
+        let payload = [
+            (fields.id.clone(), wish_code),
+            (fields.content.clone(), content::fill_placeholders(self.ctx.aid_contents.pick().trim(), self.account_index)),
+            (fields.images.clone(), String::new()),
+            (fields.file_ids.clone(), String::new()),
+        ];
+
+        let request = self.aid_request(aid_cookie, &payload);
+
+        let response = match self.send_request(request).await? {
+            Ok(resp) => resp,
+            Err(e) => {
+                let msg = format!("为愿望 {} 提供助力请求失败: {}", wish_id, e);
+                error!("{}", msg);
+                return Ok(None);
+            }
+        };
+
+        let status = response.status();
+        let response_text = response.text().await?;
+        info!("服务器响应状态: {}", status);
+        info!("服务器响应内容: {}", response_text);
+
+        let aid_response: AidResponse = match serde_json::from_str(&response_text) {
+            Ok(resp) => resp,
+            Err(e) => {
+                let msg = format!("解析愿望 {} 助力响应失败: {} (响应内容: {})",
+                                  wish_id, e, response_text);
+                error!("{}", msg);
+                // 维护页面常以 HTML 返回，需要连同响应内容一起判断是否为维护或风控
+                self.note_failure(&format!("{}，HTTP 状态: {}", msg, status))?;
+                return Ok(None);
+            }
+        };
+
+        if aid_response.state == 1 && aid_response.code == 0 {
+            if let Some(aid_id) = extract_aid_id(&aid_response.data) {
+                info!("助力成功，等待{}s时间防止频繁请求", AID_SUCCESS_DELAY);
+                pace::sleep(Duration::from_secs(AID_SUCCESS_DELAY)).await;
+                let msg = format!("为愿望 {} 助力成功！aid_id: {}", wish_id, aid_id);
+                info!("{}", msg);
+                return Ok(Some(aid_id));
+            }
+            let msg = format!("为愿望 {} 助力成功但未返回 aid_id (返回数据: {})", wish_id, aid_response.data);
+            warn!("{}", msg);
+            Ok(None)
+        } else {
+            let msg = format!("为愿望 {} 助力失败: {} (状态: {}, 代码: {}){}",
+                              wish_id, describe_message(&aid_response.message, aid_response.state, aid_response.code), aid_response.state, aid_response.code, aid_response.error_fields);
+            warn!("{}", msg);
+            self.note_failure(&msg)?;
+            if risk::is_quota_message(&aid_response.message) && self.ctx.aid_accounts.mark_exhausted(aid_index) {
+                warn!("第 {} 个助愿账号今日助力额度已用完，本轮不再使用", aid_index + 1);
+            }
+            Ok(None)
+        }
+    }
+    // 添加采纳助力的方法
+    #[instrument(name = "adopt", skip(self))]
+    pub async fn adopt_aid(&self, wish_id: &str, aid_id: &str) -> Result<bool> {
+        info!("开始采纳愿望 {} 的助力 {}...", wish_id, aid_id);
+
+        let fields = &self.config().form_fields.adopt;
+        let request = self.client.post(self.api_url(ADOPT_ENDPOINT))
+            .header("Accept", "application/json, text/plain, */*")
+            .header("Accept-Language", "zh-CN,zh;q=0.9")
+            .header("Cookie", self.cookie_for(self.config().cookie_roles.adopt))  // 默认使用许愿的 cookie
+            .header("Origin", "https://v.115.com")
+            .header("Referer", "https://v.115.com/")
+            .header("User-Agent", self.user_agent());
+        let payload = [
+            (fields.did.clone(), wish_id.to_string()),
+            (fields.aid.clone(), aid_id.to_string()),
+            (fields.to_cid.clone(), self.adopt_to_cid.clone()),
+        ];
+        let request = self.config().body_encoding.adopt.attach(request, &payload, &[]);
+
+        let response = match self.send_request(request).await? {
+            Ok(resp) => resp,
+            Err(e) => {
+                let msg = format!("采纳助力请求失败: {}", e);
+                error!("{}", msg);
+                return Ok(false);
+            }
+        };
+
+        if !response.status().is_success() {
+            let msg = format!("采纳助力失败，状态码: {}", response.status());
+            error!("{}", msg);
+            self.note_failure(&msg)?;
+            return Ok(false);
+        }
+
+        let adopt_response = match response.json::<AdoptResponse>().await {
+            Ok(resp) => resp,
+            Err(e) => {
+                let msg = format!("解析采纳助力响应失败: {}", e);
+                error!("{}", msg);
+                return Ok(false);
+            }
+        };
+
+        if adopt_response.state == 1 && adopt_response.code == 0 {
+            let msg = format!("成功采纳愿望 {} 的助力 {}", wish_id, aid_id);
+            info!("{}", msg);
+            if let Some(target_cid) = self.config().move_rewards_to.as_deref() {
+                self.move_reward_files(&adopt_response.data, target_cid).await;
+            }
+            Ok(true)
+        } else {
+            let msg = format!("采纳助力失败: {} (状态: {}, 代码: {}){}",
+                              describe_message(&adopt_response.message, adopt_response.state, adopt_response.code), adopt_response.state, adopt_response.code, adopt_response.error_fields);
+            warn!("{}", msg);
+            self.note_failure(&msg)?;
+            Ok(false)
+        }
+    }
+
+    /// 构造助力请求
+    ///
+    /// Host、Content-Length、Connection、Accept-Encoding 由 reqwest 按实际请求生成，不手动设置
+    fn aid_request(&self, aid_cookie: &str, payload: &[(String, String)]) -> RequestBuilder {
+        let request = self.client
+            .post(self.api_url(AID_DESIRE_ENDPOINT))
+            .header("Accept", "application/json, text/plain, */*")
+            .header("Sec-Fetch-Site", "same-site")
+            .header("Accept-Language", "zh-CN,zh-Hans;q=0.9")
+            .header("Sec-Fetch-Mode", "cors")
+            .header("Origin", "https://v.115.com")
+            .header("User-Agent", AID_USER_AGENT)
+            .header("Referer", "https://v.115.com/")
+            .header("Sec-Fetch-Dest", "empty")
+            .header("Cookie", aid_cookie);
+        self.config().body_encoding.aid.attach(request, payload, &[])
+    }
+
+    /// 把采纳得到的奖励文件移动到指定目录，失败只告警，不影响采纳结果
+    async fn move_reward_files(&self, adopt_data: &serde_json::Value, target_cid: &str) {
+        if !account::is_valid_cid(target_cid) {
+            warn!("奖励文件目标目录 cid \"{}\" 格式无效（应为纯数字），不移动奖励文件", target_cid);
+            return;
+        }
+
+        let file_ids = extract_file_ids(adopt_data);
+        if file_ids.is_empty() {
+            warn!("采纳响应中没有奖励文件 id，无法移动奖励文件 (返回数据: {})", adopt_data);
+            return;
+        }
+
+        match self.move_files(&file_ids, target_cid).await {
+            Ok(()) => info!("已把奖励文件 {} 移动到目录 {}", file_ids.join(","), target_cid),
+            Err(e) => warn!("移动奖励文件 {} 到目录 {} 失败: {}", file_ids.join(","), target_cid, e),
+        }
+    }
+
+    /// 调用网盘接口把文件移动到目标目录
+    async fn move_files(&self, file_ids: &[String], target_cid: &str) -> Result<()> {
+        let mut form = vec![("pid".to_string(), target_cid.to_string())];
+        form.extend(file_ids.iter().enumerate().map(|(i, id)| (format!("fid[{}]", i), id.clone())));
+
+        let request = self.client.post(FILE_MOVE_URL)
+            .header("Accept", "application/json, text/plain, */*")
+            .header("Accept-Language", "zh-CN,zh;q=0.9")
+            .header("Content-Type", "application/x-www-form-urlencoded")
+            .header("Cookie", self.cookie_for(self.config().cookie_roles.adopt))
+            .header("Origin", "https://115.com")
+            .header("Referer", "https://115.com/")
+            .header("User-Agent", self.user_agent())
+            .form(&form);
+
+        let response = self.send_request(request).await??;
+        if !response.status().is_success() {
+            anyhow::bail!("状态码: {}", response.status());
+        }
+
+        let body: serde_json::Value = response.json().await?;
+        // 网盘接口的 state 为布尔值
+        if body.get("state").and_then(serde_json::Value::as_bool) == Some(true) {
+            Ok(())
+        } else {
+            anyhow::bail!("接口返回失败: {}", body)
+        }
+    }
+
+    // 获取愿望详情，多这一步的原因是愿望列表中的code，虽然看似一样，但是不知道什么原因，无法助力成功，而通过这个接口获取到的code可以成功助力
+    pub async fn get_desire_info(&self, id: &str, aid_cookie: &str) -> Result<Option<DesireInfo>> {
+        let key = (id.to_string(), cookie::fingerprint(aid_cookie));
+        if let Some(desire) = self.ctx.desire_infos.get(&key) {
+            info!("愿望 {} 的详情命中缓存，code: {}", id, desire.code);
+            return Ok(Some(desire));
+        }
+
+        info!("开始获取待助力愿望 {} 的详情...", id);
+
+        let desire = self.fetch_desire_info(id, aid_cookie).await?;
+        if let Some(desire) = desire.as_ref().filter(|desire| !desire.code.is_empty()) {
+            self.ctx.desire_infos.insert(key, desire.clone());
+        }
+        Ok(desire)
+    }
+
+    /// 请求愿望详情，不经过 code 缓存；去抖间隔内同一账号对同一愿望的重复请求复用上次结果
+    async fn fetch_desire_info(&self, id: &str, cookie: &str) -> Result<Option<DesireInfo>> {
+        let key = (id.to_string(), cookie::fingerprint(cookie));
+        if let Some(desire) = self.ctx.desire_info_recent.get(&key) {
+            debug!("愿望 {} 的详情在 {} 秒内已请求过，复用上次结果", id, self.config().desire_info_debounce);
+            return Ok(desire);
+        }
+
+        let desire = self.request_desire_info(id, cookie).await?;
+        self.ctx.desire_info_recent.insert(key, desire.clone());
+        Ok(desire)
+    }
+
+    /// 请求愿望详情
+    async fn request_desire_info(&self, id: &str, cookie: &str) -> Result<Option<DesireInfo>> {
+        let request = self.client.get(self.api_url(GET_DESIRE_INFO_ENDPOINT))
+            .query(&[("id", id)])
+            .header("Accept", "application/json, text/plain, */*")
+            .header("Accept-Language", "zh-CN,zh;q=0.9")
+            .header("Cache-Control", "no-cache")
+            .header("Cookie", cookie)
+            .header("Origin", "https://v.115.com")
+            .header("Referer", "https://v.115.com/")
+            .header("User-Agent", self.user_agent());
+
+        let response = match self.send_request(request).await? {
+            Ok(resp) => resp,
+            Err(e) => {
+                let msg = format!("获取愿望详情请求失败: {}", e);
+                error!("{}", msg);
+                return Ok(None);
+            }
+        };
+
+        if !response.status().is_success() {
+            let msg = format!("获取愿望详情失败，状态码: {}", response.status());
+            error!("{}", msg);
+            self.note_failure(&msg)?;
+            return Ok(None);
+        }
+        // 先获取原始响应文本进行调试
+        let response_text = match response.text().await {
+            Ok(text) => {
+                info!("收到的响应内容: {}", text);
+                text
+            }
+            Err(e) => {
+                let msg = format!("读取响应内容失败: {}", e);
+                error!("{}", msg);
+                return Ok(None);
+            }
+        };
+
+        // 尝试解析JSON
+        let desire_response: DesireInfoResponse = match serde_json::from_str(&response_text) {
+            Ok(resp) => resp,
+            Err(e) => {
+                let msg = format!("解析愿望详情响应失败: {} \n响应内容: {}", e, response_text);
+                error!("{}", msg);
+                return Ok(None);
+            }
+        };
+
+
+        if desire_response.state == 1 && desire_response.code == 0 {
+            let msg = format!("成功获取到 {} 愿望详情", desire_response.data.code);
+            info!("{}", msg);
+            Ok(Some(desire_response.data))
+        } else {
+            let msg = format!("获取愿望详情: {} (状态: {}, 代码: {}){}",
+                              describe_message(&desire_response.message, desire_response.state, desire_response.code), desire_response.state, desire_response.code, desire_response.error_fields);
+            warn!("{}", msg);
+            self.note_failure(&msg)?;
+            Ok(None)
+        }
+    }
+}
+
+/// 多账号客户端
+#[derive(Clone)]
+struct Api115Client {
+    client: Client,
+    ctx: Arc<RunContext>,
+}
+
+impl Api115Client {
+    /// 创建新的多账号客户端实例
+    pub fn new(config: AppConfig) -> Self {
+        Self::with_api_base(config, API_BASE_URL)
+    }
+
+    /// 创建请求发往指定接口地址的客户端实例，用于对接本地的模拟服务
+    fn with_api_base(config: AppConfig, api_base: &str) -> Self {
+        let client = client_builder().build().unwrap_or_else(|_| Client::new());
+        let proxies = ProxyPool::new(&config.proxy, client_builder);
+
+        // overrides 在启动时已校验过，这里合并失败时按全局配置处理；每个账号只合并一次，
+        // 各处按序号创建单账号客户端时直接复用
+        let account_configs: Vec<_> = config
+            .wish_cookies
+            .iter()
+            .map(|account| {
+                let overrides = account.overrides.as_ref()?;
+                overrides::apply(&config, overrides).ok().map(Arc::new)
+            })
+            .collect();
+        // 覆盖了代理配置的账号使用自己的代理池
+        let account_proxies = account_configs
+            .iter()
+            .map(|merged| {
+                merged
+                    .as_ref()
+                    .filter(|merged| merged.proxy.urls != config.proxy.urls)
+                    .map(|merged| Arc::new(ProxyPool::new(&merged.proxy, client_builder)))
+            })
+            .collect();
+
+        let wish_candidates = config
+            .wish_content_file
+            .as_deref()
+            .and_then(content::load_file)
+            .unwrap_or_else(|| config.wish_contents.clone());
+        let wish_contents = ContentPool::new(wish_candidates, config.distinct_wish_contents);
+        wish_contents.check_diversity(config.wish_cookies.len());
+
+        let aid_candidates = config
+            .aid_content_file
+            .as_deref()
+            .and_then(content::load_file)
+            .unwrap_or_else(|| config.aid_contents.clone());
+        let aid_candidates = if aid_candidates.is_empty() { default_aid_contents() } else { aid_candidates };
+        let aid_contents = ContentPool::new(aid_candidates, false);
+
+        let ctx = RunContext {
+            api_base: api_base.trim_end_matches('/').to_string(),
+            budget: RequestBudget::new(config.max_total_requests),
+            throttle: Throttle::load(&config.state_dir, &config.throttle),
+            wish_contents,
+            aid_contents,
+            desire_infos: TtlCache::new(
+                config.desire_code_cache_size,
+                Duration::from_secs(config.desire_code_cache_ttl),
+            ),
+            desire_info_recent: TtlCache::new(
+                if config.desire_info_debounce > 0 { DEFAULT_DESIRE_CODE_CACHE_SIZE } else { 0 },
+                Duration::from_secs(config.desire_info_debounce),
+            ),
+            reports: Mutex::new(Vec::new()),
+            idempotency: IdempotencyStore::load(&config.state_dir, config.idempotency),
+            aid_accounts: AidBalancer::new(config.all_aid_cookies(), &config.aid_balance),
+            vcr: Cassette::load(&config.vcr),
+            signer: sign::default_signer(),
+            proxies,
+            account_configs,
+            account_proxies,
+            content_filter: ContentFilter::new(&config.content_filter).unwrap_or_else(|e| {
+                warn!("许愿内容检测规则无效，已停用: {}", e);
+                ContentFilter::disabled()
+            }),
+            user_agents: config.user_agent.assign(config.wish_cookies.len(), USER_AGENT),
+            events: EventSink::bind(config.event_socket.as_deref()),
+            config,
+        };
+
+        Self {
+            client,
+            ctx: Arc::new(ctx),
+        }
+    }
+
+    /// 对所有账号一个个处理，以防并发风控，返回本轮的整体结果
+    pub async fn process_all_accounts(&self) -> Result<RunStatus> {
+        if self.ctx.config.health_check.enabled && !self.health_check().await? {
+            return Ok(RunStatus::Failure);
+        }
+        let expired = if self.ctx.config.health_check.check_cookies {
+            self.expired_accounts().await?
+        } else {
+            HashSet::new()
+        };
+        if !expired.is_empty() && expired.len() == self.ctx.config.wish_cookies.len() {
+            let message = format!("所有 {} 个许愿账号的 cookie 已失效，请更新 cookie 后再运行", expired.len());
+            error!("{}", message);
+            notify::send_all(&self.client, &self.ctx.config.notify, "115 许愿助手 cookie 已全部失效", &message).await;
+            return Ok(RunStatus::Failure);
+        }
+
+        let mut failures = FailureTracker::load(
+            &self.ctx.config.state_dir,
+            self.ctx.config.disable_after_failures,
+        );
+
+        // 各账号的处理结果，被禁用而跳过的账号计为失败
+        let mut succeeded = 0;
+        let mut failed = 0;
+        let mut fatal = false;
+        let mut stopped = false;
+
+        let wish_cookies = &self.ctx.config.wish_cookies;
+        let fingerprints: Vec<String> = wish_cookies.iter().map(|account| cookie::fingerprint(account.cookie.expose_secret())).collect();
+        let mut checkpoint = Checkpoint::load(&self.ctx.config.state_dir, self.ctx.config.checkpoint, fingerprints.join(","));
+        self.ctx.events.emit(&Event::RunStarted { run_id: run_id::current().to_string(), accounts: wish_cookies.len() });
+
+        for (index, account_key) in fingerprints.iter().enumerate().skip(checkpoint.next()) {
+            if failures.is_disabled(account_key) {
+                warn!(
+                    "[账号-{}] 已因连续处理失败被自动禁用，跳过。请更新 cookie 或运行 --reset-disabled 重新启用",
+                    index + 1
+                );
+                failed += 1;
+                checkpoint.finish(index);
+                continue;
+            }
+            if expired.contains(&index) {
+                warn!("[账号-{}] cookie 已失效，跳过。请更新该账号的 cookie", index + 1);
+                failed += 1;
+                checkpoint.finish(index);
+                continue;
+            }
+
+            info!("开始处理第 {} 个账号，共 {} 个账号", index + 1, wish_cookies.len());
+            checkpoint.begin(index);
+
+            let single_client = Api115ClientSingle::new(self.client.clone(), index, self.ctx.clone());
+            self.ctx.events.emit(&Event::AccountStarted {
+                account: index + 1,
+                name: single_client.report().display_name(),
+            });
+
+            let result = single_client.process_single_account().await;
+            if let Err(e) = &result {
+                if is_fatal(e) {
+                    warn!("[账号-{}] {}，结束本轮处理（已处理 {} 个账号）", index + 1, e, index + 1);
+                    if e.is::<ActivityPaused>() {
+                        notify::send_all(&self.client, &self.ctx.config.notify, "115 活动维护中，已停止本轮处理", &e.to_string()).await;
+                    }
+                    fatal = true;
+                    break;
+                }
+                error!("[账号-{}] 处理账号时出错: {}", index + 1, e);
+            }
+            checkpoint.finish(index);
+
+            let report = single_client.report();
+            let failure = match &result {
+                Err(e) => Some(e.to_string()),
+                Ok(()) if report.is_failed_round() => Some(format!("本轮 {} 个操作全部失败", report.failed)),
+                Ok(()) => None,
+            };
+            self.ctx.events.emit(&Event::AccountFinished {
+                account: index + 1,
+                aided: report.aided,
+                adopted: report.adopted,
+                failed: report.failed,
+                error: failure.clone(),
+            });
+            let stop_reason = match self.ctx.config.on_account_failure {
+                OnAccountFailure::Continue => None,
+                OnAccountFailure::Stop => failure.clone(),
+                OnAccountFailure::StopOnRisk => report.risk.as_ref().map(|risk| format!("触发风控: {}", risk)),
+            };
+            match failure {
+                Some(error) => {
+                    failed += 1;
+                    single_client.write_diag(&error);
+                    if failures.record_failure(account_key, error) {
+                        error!(
+                            "[账号-{}] 连续 {} 轮处理失败，已自动禁用，后续轮次将跳过。需要人工处理：检查 cookie 是否失效或账号是否被限制",
+                            index + 1,
+                            self.ctx.config.disable_after_failures
+                        );
+                    }
+                }
+                None => {
+                    succeeded += 1;
+                    failures.record_success(account_key);
+                }
+            }
+
+            if let Some(reason) = stop_reason {
+                error!(
+                    "[账号-{}] {}，按 on_account_failure: {} 停止处理后续账号（已处理 {} 个账号，剩余 {} 个未处理）",
+                    index + 1,
+                    reason,
+                    self.ctx.config.on_account_failure,
+                    index + 1,
+                    wish_cookies.len() - index - 1
+                );
+                stopped = true;
+                break;
+            }
+
+            if self.ctx.budget.is_exhausted() {
+                warn!("已达请求预算，结束本轮处理（已处理 {} 个账号）", index + 1);
+                stopped = true;
+                break;
+            }
+
+            // Add a delay between processing different accounts to avoid rate limiting
+            if index < wish_cookies.len() - 1 {
+                info!("等待{}秒后处理下一个账号...", ACCOUNT_INTERVAL);
+                pace::sleep(Duration::from_secs(ACCOUNT_INTERVAL)).await;
+            }
+        }
+
+        if let Err(e) = failures.save() {
+            warn!("保存账号失败状态失败: {}", e);
+        }
+        self.ctx.throttle.save();
+        // 中途停止时保留断点，下次运行从剩余的账号继续
+        if !fatal && !stopped {
+            checkpoint.clear();
+        }
+
+        if self.ctx.aid_accounts.len() > 1 {
+            let usage = self.ctx.aid_accounts.usage();
+            let usage: Vec<String> = usage.iter().enumerate().map(|(i, n)| format!("#{}: {}", i + 1, n)).collect();
+            info!("各助愿账号本轮助力次数（策略: {}）: {}", self.ctx.config.aid_balance.strategy, usage.join(", "));
+        }
+        let exhausted = self.ctx.aid_accounts.exhausted();
+        if !exhausted.is_empty() {
+            let exhausted: Vec<String> = exhausted.iter().map(|i| format!("#{}", i + 1)).collect();
+            warn!("今日助力额度已用完的助愿账号: {}", exhausted.join(", "));
+        }
+        let proxy_usage = self.ctx.proxies.usage();
+        if !proxy_usage.is_empty() {
+            let usage: Vec<String> = proxy_usage.iter().map(|(url, n)| format!("{}: {}", url, n)).collect();
+            info!("各代理本轮请求数: {}", usage.join(", "));
+        }
+        info!("本轮共发出 {} 个请求", self.ctx.budget.used());
+        self.ctx.events.emit(&Event::RunFinished { succeeded, failed, requests: self.ctx.budget.used() });
+        self.check_memory().await;
+        self.log_error_summary();
+        self.log_aid_view().await;
+        self.write_reports();
+        self.send_summary().await;
+        self.report_diff().await;
+        self.check_success_rates().await;
+
+        if fatal {
+            return Ok(RunStatus::Failure);
+        }
+        Ok(RunStatus::from_counts(succeeded, failed))
+    }
+
+    /// 只读地汇总所有账号的愿望状态，返回渲染好的表格
+    pub async fn status(&self) -> String {
+        let mut accounts = Vec::new();
+        for index in 0..self.ctx.config.wish_cookies.len() {
+            let single_client = Api115ClientSingle::new(self.client.clone(), index, self.ctx.clone());
+            let result = single_client.collect_status().await.map_err(|e| e.to_string());
+            accounts.push((single_client.report().display_name(), result));
+        }
+        status::render(&accounts)
+    }
+
+    /// 用第一个许愿账号探测各活动接口的可用性，返回渲染好的表格
+    pub async fn probe(&self, dry_run: bool) -> String {
+        let single_client = Api115ClientSingle::new(self.client.clone(), 0, self.ctx.clone());
+        probe::render(&single_client.probe_endpoints(dry_run).await)
+    }
+
+    /// 打印各账号即将提交的许愿请求，不发送任何请求
+    pub fn preview_wishes(&self) {
+        for index in 0..self.ctx.config.wish_cookies.len() {
+            let single_client = Api115ClientSingle::new(self.client.clone(), index, self.ctx.clone());
+            println!("===== 账号-{} =====", index + 1);
+            // 预览不发送请求，dynamic 策略无法查询剩余空间，按下界显示
+            println!("{}", single_client.prepare_wish(single_client.pick_reward_space(None)));
+        }
+    }
+
+    /// 运行前健康检查，返回是否继续处理
+    async fn health_check(&self) -> Result<bool> {
+        info!("开始运行前健康检查...");
+
+        if self.ctx.config.wish_cookies.is_empty() {
+            return Ok(true);
+        }
+        let checker = Api115ClientSingle::new(self.client.clone(), 0, self.ctx.clone());
+
+        let status = checker.check_activity_health().await?;
+        if status.is_healthy() {
+            info!("健康检查通过: {}", status);
+            return Ok(true);
+        }
+
+        if self.ctx.config.health_check.continue_on_failure {
+            warn!("健康检查未通过: {}，按配置继续处理", status);
+            Ok(true)
+        } else {
+            error!("健康检查未通过: {}，本轮不再处理（可设置 health_check.continue_on_failure: true 强制继续）", status);
+            Ok(false)
+        }
+    }
+
+    /// 逐个检查许愿账号的 cookie，返回已失效账号的序号（从 0 开始）
+    async fn expired_accounts(&self) -> Result<HashSet<usize>> {
+        info!("开始检查各许愿账号的 cookie...");
+        let mut expired = HashSet::new();
+        for index in 0..self.ctx.config.wish_cookies.len() {
+            let checker = Api115ClientSingle::new(self.client.clone(), index, self.ctx.clone());
+            if checker.is_cookie_expired().await? {
+                warn!("[{}] cookie 已失效", checker.report().display_name());
+                expired.insert(index);
+            }
+        }
+        if !expired.is_empty() {
+            warn!("{} 个许愿账号中有 {} 个 cookie 已失效", self.ctx.config.wish_cookies.len(), expired.len());
+        }
+        Ok(expired)
+    }
+
+    /// 记录本轮结束时的内存占用，超过 memory_warn_mb 时告警并推送
+    async fn check_memory(&self) {
+        let Some(usage) = memory::usage() else {
+            return;
+        };
+        info!("本轮结束时{}", usage);
+
+        let limit = self.ctx.config.memory_warn_mb;
+        if limit == 0 || usage.rss_mb() <= limit {
+            return;
+        }
+        let message = format!("进程{}，超过告警阈值 {} MB，可能存在内存泄漏，建议重启进程", usage, limit);
+        warn!("{}", message);
+        notify::send_all(&self.client, &self.ctx.config.notify, "115 许愿助手内存占用过高", &message).await;
+    }
+
+    /// 按配置输出各账号以愿望为中心的助力关联视图
+    async fn log_aid_view(&self) {
+        if !self.ctx.config.aid_view {
+            return;
+        }
+        for index in 0..self.ctx.config.wish_cookies.len() {
+            let single_client = Api115ClientSingle::new(self.client.clone(), index, self.ctx.clone());
+            let name = single_client.report().display_name();
+            match single_client.collect_aid_view().await {
+                Ok(wishes) => info!("{}", aid_view::render(&name, &wishes)),
+                Err(e) => {
+                    warn!("[{}] 获取愿望助力情况失败: {}", name, e);
+                    if self.ctx.budget.is_exhausted() {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    /// 并发助力时各账号的错误日志相互交错，结束后按账号分组再输出一遍
+    fn log_error_summary(&self) {
+        if self.ctx.config.aid_concurrency <= 1 {
+            return;
+        }
+        let reports = self.ctx.reports.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(errors) = report::render_errors(&reports) {
+            warn!("本轮错误汇总（按账号分组）:\n{}", errors);
+        }
+    }
+
+    /// 把本轮汇总发送到配置的通知渠道
+    async fn send_summary(&self) {
+        if self.ctx.config.notify.is_empty() {
+            return;
+        }
+
+        let summary = {
+            let reports = self.ctx.reports.lock().unwrap_or_else(|e| e.into_inner());
+            report::render_summary(&reports)
+        };
+        let title = format!("115 许愿助手运行汇总 {}", Local::now().format("%Y-%m-%d"));
+        notify::send_all(&self.client, &self.ctx.config.notify, &title, &summary).await;
+    }
+
+    /// 计算各类操作的成功率，低于阈值时告警并推送
+    async fn check_success_rates(&self) {
+        let rates = {
+            let reports = self.ctx.reports.lock().unwrap_or_else(|e| e.into_inner());
+            report::operation_rates(&reports)
+        };
+        let summary: Vec<String> = rates.iter().map(|rate| rate.to_string()).collect();
+        info!("本轮{}", summary.join("，"));
+
+        let alerts = report::rate_alerts(&rates, &self.ctx.config.success_rate_alert);
+        if alerts.is_empty() {
+            return;
+        }
+        let message = format!(
+            "{}。可能是 cookie 批量失效或接口有变动，请检查日志",
+            alerts.join("；")
+        );
+        error!("成功率告警: {}", message);
+        if !self.ctx.config.notify.is_empty() {
+            notify::send_all(&self.client, &self.ctx.config.notify, "115 许愿助手成功率告警", &message).await;
+        }
+    }
+
+    /// 保存本次运行的快照，并与上次运行对比，首次运行时只打印本次结果
+    async fn report_diff(&self) {
+        let config = &self.ctx.config;
+        if !config.diff_report.enabled {
+            return;
+        }
+
+        let current = {
+            let reports = self.ctx.reports.lock().unwrap_or_else(|e| e.into_inner());
+            RunSnapshot::from_reports(&reports)
+        };
+        let last = RunSnapshot::load_last(&config.state_dir);
+        if let Err(e) = current.save(&config.state_dir) {
+            warn!("保存运行快照失败: {}", e);
+        }
+
+        let Some(last) = last else {
+            info!("{}（没有上次运行的记录，跳过差异对比）", history::render_current(&current));
+            return;
+        };
+
+        let diff = history::render_diff(&last, &current);
+        info!("{}", diff);
+        if config.diff_report.notify && !config.notify.is_empty() {
+            notify::send_all(&self.client, &config.notify, "115 许愿助手运行趋势", &diff).await;
+        }
+    }
+
+    /// 按配置输出本轮的处理结果报告
+    fn write_reports(&self) {
+        let reports = self.ctx.reports.lock().unwrap_or_else(|e| e.into_inner());
+
+        if let Some(template) = &self.ctx.config.markdown_report {
+            let path = report::resolve_report_path(template);
+            match report::write_markdown(Path::new(&path), &reports) {
+                Ok(()) => info!("Markdown 报告已写入 {}", path),
+                Err(e) => warn!("写入 Markdown 报告失败: {}", e),
+            }
+        }
+
+        if let Some(template) = &self.ctx.config.csv_report {
+            let path = report::resolve_report_path(template);
+            match report::append_csv(Path::new(&path), &reports) {
+                Ok(()) => info!("运行结果已追加到 CSV 文件 {}", path),
+                Err(e) => warn!("追加 CSV 结果失败: {}", e),
+            }
+        }
+    }
+}
+
+/// 把 status、probe 等命令的结果写入 output 指定的文件，不指定则输出到控制台
+fn write_output(output: Option<&str>, text: &str, what: &str) -> RunStatus {
+    match output {
+        Some(path) => match fs::write(path, text) {
+            Ok(()) => {
+                info!("{}已写入 {}", what, path);
+                RunStatus::Success
+            }
+            Err(e) => {
+                error!("写入 {} 失败: {}", path, e);
+                RunStatus::Failure
+            }
+        },
+        None => {
+            print!("{}", text);
+            RunStatus::Success
+        }
+    }
+}
+
+/// 代码中写死、不能通过配置修改的值，供 --show-defaults 列出
+fn builtin_values() -> Vec<defaults::Builtin> {
+    let builtin = |name, value: String, description| defaults::Builtin { name, value, description };
+    vec![
+        builtin("DEFAULT_WAIT_TIME", format!("{}s", DEFAULT_WAIT_TIME), "每个愿望助力（及立即采纳）后的等待时间"),
+        builtin("AID_SUCCESS_DELAY", format!("{}s", AID_SUCCESS_DELAY), "助力成功后的等待时间"),
+        builtin("ADOPT_DELAY", format!("{}s", ADOPT_DELAY), "助力后到采纳、批量采纳之间的等待时间"),
+        builtin("ACCOUNT_INTERVAL", format!("{}s", ACCOUNT_INTERVAL), "相邻两个账号之间的等待时间"),
+        builtin("PENDING_WISH_LIMIT", PENDING_WISH_LIMIT.to_string(), "每次获取的待处理愿望数"),
+        builtin("USER_AGENT", USER_AGENT.to_string(), "许愿、查询等请求默认使用的 User-Agent，启用 user_agent.random 时改为从池中分配"),
+        builtin("AID_USER_AGENT", AID_USER_AGENT.to_string(), "助力请求使用的 User-Agent（模拟 115 iOS 客户端）"),
+        builtin("API_BASE_URL", API_BASE_URL.to_string(), "活动接口地址前缀，后接 activity_path 和各端点"),
+    ]
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = match CliArgs::parse() {
+        Ok(cli) => cli,
+        Err(e) => {
+            eprintln!("{}", e);
+            return RunStatus::ConfigError.into();
+        }
+    };
+    if let Some(path) = &cli.config {
+        set_config_file_path(path);
+    }
+
+    // 加密明文配置文件，完成后直接退出
+    if cli.encrypt_config {
+        if let Err(e) = crypto::run_encrypt_config() {
+            eprintln!("加密配置文件失败: {}", e);
+            return RunStatus::Failure.into();
+        }
+        return RunStatus::Success.into();
+    }
+
+    // 交互式配置向导，完成后直接退出
+    if cli.setup {
+        if let Err(e) = setup::run() {
+            eprintln!("配置向导执行失败: {}", e);
+            return RunStatus::Failure.into();
+        }
+        return RunStatus::Success.into();
+    }
+
+    // 收紧配置文件权限，完成后直接退出
+    if cli.fix_perms {
+        let mut status = RunStatus::Success;
+        for path in [config_file_path(), encrypted_config_file_path()] {
+            if !Path::new(path).exists() {
+                continue;
+            }
+            match perms::restrict(Path::new(path)) {
+                Ok(()) => println!("已把 {} 的权限设置为 600", path),
+                Err(e) => {
+                    eprintln!("设置 {} 的权限失败: {}", path, e);
+                    status = RunStatus::Failure;
+                }
+            }
+        }
+        return status.into();
+    }
+
+    // 从 curl 命令或 header 中提取 cookie，完成后直接退出
+    if let Some(target) = cli.parse_curl {
+        if let Err(e) = curl::run(target) {
+            eprintln!("解析 cookie 失败: {}", e);
+            return RunStatus::Failure.into();
+        }
+        return RunStatus::Success.into();
+    }
+
+    // 初始化日志系统
+    let logger = match setup_logger() {
+        Ok(handle) => handle,
+        Err(e) => {
+            eprintln!("初始化日志系统失败: {}", e);
+            return RunStatus::Failure.into();
+        }
+    };
+
+    info!("程序开始执行 - {}", Local::now().format("%Y-%m-%d %H:%M:%S"));
+
+    // 加载配置，--once 时不读取配置文件
+    let mut config = if cli.once {
+        info!("--once 模式，不读取配置文件，使用命令行传入的 cookie 和默认配置");
+        AppConfig::default()
+    } else {
+        match AppConfig::load() {
+            Ok(cfg) => cfg,
+            Err(e) => {
+                error!("加载配置文件失败: {}", e);
+                return RunStatus::ConfigError.into();
+            }
+        }
+    };
+    config.apply_cli_cookies(&cli);
+    if cli.diag_on_account_failure {
+        config.diag_on_account_failure = true;
+    }
+
+    // 按配置重新设置日志输出（日志格式、syslog）
+    match logger_config(&config.logging) {
+        Ok(new_config) => {
+            logger.set_config(new_config);
+            if config.logging.syslog {
+                info!("日志将同时写入 syslog（facility: {}）", config.logging.facility);
+            }
+        }
+        Err(e) => warn!("按配置设置日志输出失败，继续使用默认的日志输出: {}", e),
+    }
+
+    config.migrate_retry_options();
+
+    // 列出默认值和生效值，完成后直接退出
+    if cli.show_defaults {
+        print!("{}", defaults::render(&AppConfig::default(), &config, &builtin_values()));
+        return RunStatus::Success.into();
+    }
+
+    // 验证配置
+    if let Err(e) = config.normalize_cookies() {
+        error!("{}", e);
+        return RunStatus::ConfigError.into();
+    }
+    if config.wish_cookies.is_empty() {
+        error!("未配置任何 wish cookie");
+        return RunStatus::ConfigError.into();
+    }
+    if let Err(e) = config.check_account_overrides() {
+        error!("{}", e);
+        return RunStatus::ConfigError.into();
+    }
+    if !cli.tags.is_empty() {
+        if let Err(e) = config.retain_tagged_accounts(&cli.tags) {
+            error!("{}", e);
+            return RunStatus::ConfigError.into();
+        }
+    }
+    for (wish_index, aid_index, user_id) in config.uid_conflicts() {
+        warn!(
+            "第 {} 个许愿账号与第 {} 个助愿账号是同一个 115 用户（UID {}），给自己的愿望助力必然失败，请为其中之一换用其他账号",
+            wish_index, aid_index, user_id
+        );
+    }
+    if config.aid_cookie.is_empty() {
+        error!("未配置 aid cookie");
+        return RunStatus::ConfigError.into();
+    }
+    if let Some(index) = config.wish_contents.iter().position(|content| content.trim().is_empty()) {
+        error!("wish_contents 中第 {} 条许愿内容为空，请删除或填写内容", index + 1);
+        return RunStatus::ConfigError.into();
+    }
+    if let Some(index) = config.aid_contents.iter().position(|content| content.trim().is_empty()) {
+        error!("aid_contents 中第 {} 条助力内容为空，请删除或填写内容", index + 1);
+        return RunStatus::ConfigError.into();
+    }
+    if let Err(e) = ContentFilter::new(&config.content_filter) {
+        error!("content_filter 配置无效: {}", e);
+        return RunStatus::ConfigError.into();
+    }
+    if config.cookie_roles.adopt != CookieRole::Wish {
+        info!("采纳助力使用 {} 账号的 cookie（cookie_roles.adopt）", config.cookie_roles.adopt);
+    }
+    if let Err(e) = config.validate() {
+        error!("{:#}", e);
+        return RunStatus::ConfigError.into();
+    }
+    if config.activity_path.trim_matches('/').is_empty() {
+        error!("activity_path 不能为空");
+        return RunStatus::ConfigError.into();
+    }
+
+    if let Some(scale) = cli.time_scale {
+        config.time_scale = scale;
+    }
+    if !(0.0..=pace::MAX_TIME_SCALE).contains(&config.time_scale) {
+        error!("time_scale 需在 0 到 {} 之间，当前为 {}", pace::MAX_TIME_SCALE, config.time_scale);
+        return RunStatus::ConfigError.into();
+    }
+    pace::set_time_scale(config.time_scale);
+    if config.time_scale != DEFAULT_TIME_SCALE {
+        info!("所有等待时间按 {} 倍执行", config.time_scale);
+    }
+
+    if let Some(hint) = config.account_count_hint() {
+        warn!("{}", hint);
+    }
+    for warning in config.timing_warnings() {
+        warn!("{}", warning);
+    }
+
+    // 清除账号失败状态，重新启用被自动禁用的账号
+    if cli.reset_disabled {
+        return match FailureTracker::reset(&config.state_dir) {
+            Ok(()) => {
+                info!("已清除账号失败状态，所有被自动禁用的账号已重新启用");
+                RunStatus::Success.into()
+            }
+            Err(e) => {
+                error!("清除账号失败状态失败: {}", e);
+                RunStatus::Failure.into()
+            }
+        };
+    }
+
+    // 创建客户端并处理所有账号
+    let client = Api115Client::new(config);
+
+    // 只读地导出各账号愿望状态，不做任何写操作
+    if cli.status {
+        let table = client.status().await;
+        return write_output(cli.output.as_deref(), &table, "愿望状态").into();
+    }
+
+    // 探测各接口的可用性，写接口只发送会被拒绝的请求
+    if cli.probe {
+        let table = client.probe(cli.dry_run).await;
+        return write_output(cli.output.as_deref(), &table, "接口探测结果").into();
+    }
+
+    // 只预览许愿请求，不真正提交
+    if cli.preview_wish {
+        client.preview_wishes();
+        return RunStatus::Success.into();
+    }
+    let status = client.process_all_accounts().await.unwrap_or_else(|e| {
+        error!("处理账号时发生错误: {}", e);
+        RunStatus::Failure
+    });
+
+    info!(
+        "所有愿望处理完成 - {}，退出码: {}",
+        Local::now().format("%Y-%m-%d %H:%M:%S"),
+        status.code()
+    );
+
+    status.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn migrate_retry_options_moves_deprecated_values() {
+        let mut config = AppConfig {
+            rate_limit_retries: Some(7),
+            network_retries: Some(0),
+            ..Default::default()
+        };
+        config.migrate_retry_options();
+
+        assert_eq!(config.retry.rate_limit.times, 7);
+        assert_eq!(config.retry.network.times, 0);
+        assert_eq!(config.retry.rate_limit.backoff, RetryConfig::default().rate_limit.backoff);
+        assert!(config.rate_limit_retries.is_none());
+        assert!(config.network_retries.is_none());
+    }
+
+    #[test]
+    fn migrate_retry_options_keeps_retry_without_deprecated_values() {
+        let mut config = AppConfig::default();
+        config.retry.server_error.times = 4;
+        config.migrate_retry_options();
+
+        assert_eq!(config.retry.server_error.times, 4);
+        assert_eq!(config.retry.network, RetryConfig::default().network);
+    }
+
+    /// 测试用的独立状态目录，避免读写真实的 state 目录
+    fn test_state_dir(name: &str) -> String {
+        let dir = std::env::temp_dir().join(format!("wish_115_test_{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir.to_string_lossy().into_owned()
+    }
+
+    fn test_client(name: &str, accounts: Vec<AccountConfig>) -> Api115Client {
+        Api115Client::new(AppConfig {
+            wish_cookies: accounts,
+            state_dir: test_state_dir(name),
+            ..Default::default()
+        })
+    }
+
+    fn account_with_overrides(overrides: &str) -> AccountConfig {
+        AccountConfig {
+            overrides: Some(serde_yaml::from_str(overrides).unwrap()),
+            ..AccountConfig::new("UID=1_A1_1; CID=c; SEID=s".to_string())
+        }
+    }
+
+    #[test]
+    fn account_level_options_take_priority_over_overrides() {
+        let overrides = "reward_space: { value: 9 }\nadopt_to_cid: \"200\"";
+        let pinned = AccountConfig {
+            content: Some("账号内容".to_string()),
+            reward_space: Some(3),
+            adopt_to_cid: Some("100".to_string()),
+            ..account_with_overrides(overrides)
+        };
+        let client = test_client("account_priority", vec![pinned, account_with_overrides(overrides)]);
+
+        let single = Api115ClientSingle::new(client.client.clone(), 0, client.ctx.clone());
+        assert_eq!(single.pick_reward_space(None), 3);
+        assert_eq!(single.adopt_to_cid, "100");
+        assert!(single.prepare_wish(3).form.iter().any(|(_, value)| value == "账号内容"));
+
+        let single = Api115ClientSingle::new(client.client.clone(), 1, client.ctx.clone());
+        assert_eq!(single.pick_reward_space(None), 9);
+        assert_eq!(single.adopt_to_cid, "200");
+    }
+
+    #[test]
+    fn overrides_are_merged_once_per_account() {
+        let client = test_client(
+            "merged_once",
+            vec![account_with_overrides("retry: { network: { times: 7 } }"), AccountConfig::new("UID=2_A1_1".to_string())],
+        );
+        assert_eq!(client.ctx.account_configs.len(), 2);
+        assert!(client.ctx.account_configs[1].is_none());
+
+        let first = Api115ClientSingle::new(client.client.clone(), 0, client.ctx.clone());
+        let second = Api115ClientSingle::new(client.client.clone(), 0, client.ctx.clone());
+        assert_eq!(first.config().retry.network.times, 7);
+        assert!(Arc::ptr_eq(first.config.as_ref().unwrap(), second.config.as_ref().unwrap()));
+        assert!(first.proxies.is_none());
+    }
+
+    /// 各宽松字段都为普通数字时的愿望详情
+    fn desire_info_json() -> serde_json::Value {
+        serde_json::json!({
+            "id": 1, "content": "c", "images": "", "edit_time": 1700000000, "audit_status": 1,
+            "status": 0, "aid": 2, "reward": 3, "sj_reward": 4, "code": "abc", "aid_num": 5,
+            "images_data": [], "user_info": { "user_name": "u", "face_l": "" },
+            "is_my_desire": 1, "button": 1,
+        })
+    }
+
+    #[test]
+    fn desire_info_int_fields_are_lenient() {
+        let i32_fields = ["audit_status", "status", "aid_num", "is_my_desire", "button"];
+        let i64_fields = ["edit_time", "aid", "reward", "sj_reward"];
+        for field in i32_fields.iter().chain(&i64_fields) {
+            let parse = |value: serde_json::Value| {
+                let mut json = desire_info_json();
+                json[*field] = value;
+                serde_json::from_value::<DesireInfo>(json)
+            };
+            assert!(parse(serde_json::json!(7)).is_ok(), "{} 数字", field);
+            assert!(parse(serde_json::json!("7")).is_ok(), "{} 字符串", field);
+            assert!(parse(serde_json::json!("")).is_ok(), "{} 空字符串", field);
+            assert!(parse(serde_json::Value::Null).is_ok(), "{} null", field);
+            let out_of_range = if i32_fields.contains(field) { serde_json::json!(1u64 << 40) } else { serde_json::json!(1e30) };
+            assert!(parse(out_of_range).is_err(), "{} 超出范围", field);
+        }
+
+        let mut json = desire_info_json();
+        json["aid"] = serde_json::json!("42");
+        json["status"] = serde_json::json!("");
+        let info: DesireInfo = serde_json::from_value(json).unwrap();
+        assert_eq!((info.aid, info.status), (42, 0));
+    }
+
+    #[test]
+    fn desire_info_string_fields_are_lenient() {
+        for field in ["id", "code"] {
+            let parse = |value: serde_json::Value| {
+                let mut json = desire_info_json();
+                json[field] = value;
+                serde_json::from_value::<DesireInfo>(json)
+            };
+            assert!(parse(serde_json::json!(7)).is_ok(), "{} 数字", field);
+            assert!(parse(serde_json::json!("7")).is_ok(), "{} 字符串", field);
+            assert!(parse(serde_json::json!("")).is_ok(), "{} 空字符串", field);
+            assert!(parse(serde_json::Value::Null).is_ok(), "{} null", field);
+            assert!(parse(serde_json::json!({})).is_err(), "{} 对象", field);
+        }
+        let mut json = desire_info_json();
+        json["id"] = serde_json::json!(123);
+        json["code"] = serde_json::Value::Null;
+        let info: DesireInfo = serde_json::from_value(json).unwrap();
+        assert_eq!((info.id.as_str(), info.code.as_str()), ("123", ""));
+    }
+
+    #[test]
+    fn extract_aid_id_reads_each_key() {
+        for key in AID_ID_KEYS {
+            assert_eq!(extract_aid_id(&serde_json::json!({ key: "123" })), Some("123".to_string()), "{}", key);
+            assert_eq!(extract_aid_id(&serde_json::json!({ key: 456 })), Some("456".to_string()), "{}", key);
+        }
+    }
+
+    #[test]
+    fn extract_aid_id_prefers_keys_in_order() {
+        let data = serde_json::json!({ "aid": 3, "id": 2, "aid_id": 1 });
+        assert_eq!(extract_aid_id(&data), Some("1".to_string()));
+        let data = serde_json::json!({ "aid_id": "0", "id": 0, "aid": "9" });
+        assert_eq!(extract_aid_id(&data), Some("9".to_string()));
+    }
+
+    #[test]
+    fn extract_aid_id_reads_nested_info() {
+        let data = serde_json::json!({ "info": { "aid_id": 789 } });
+        assert_eq!(extract_aid_id(&data), Some("789".to_string()));
+        let data = serde_json::json!({ "id": "1", "info": { "aid_id": 789 } });
+        assert_eq!(extract_aid_id(&data), Some("1".to_string()));
+    }
+
+    #[test]
+    fn extract_aid_id_returns_none_without_id() {
+        assert_eq!(extract_aid_id(&serde_json::json!({})), None);
+        assert_eq!(extract_aid_id(&serde_json::json!({ "other": 1, "info": {} })), None);
+        assert_eq!(extract_aid_id(&serde_json::json!({ "aid_id": "" })), None);
+        assert_eq!(extract_aid_id(&serde_json::json!({ "aid_id": 0 })), None);
+        assert_eq!(extract_aid_id(&serde_json::json!({ "aid_id": null })), None);
+        assert_eq!(extract_aid_id(&serde_json::json!([])), None);
+        assert_eq!(extract_aid_id(&serde_json::Value::Null), None);
+    }
+
+    #[test]
+    fn my_desires_data_accepts_null_list() {
+        let data: MyDesiresData = serde_json::from_str(r#"{"list": null, "count": 0}"#).unwrap();
+        assert!(data.list.is_empty());
+        assert_eq!(data.count, 0);
+    }
+
+    #[test]
+    fn my_desires_data_accepts_empty_list() {
+        let data: MyDesiresData = serde_json::from_str(r#"{"list": [], "count": "0"}"#).unwrap();
+        assert!(data.list.is_empty());
+        assert_eq!(data.count, 0);
+    }
+
+    #[test]
+    fn my_desires_data_accepts_missing_list() {
+        let data: MyDesiresData = serde_json::from_str("{}").unwrap();
+        assert!(data.list.is_empty());
+        assert_eq!(data.count, 0);
+
+        let data: MyDesiresData = serde_json::from_str(r#"{"list": [{"code": 1, "aid_num": "2"}], "count": 1}"#).unwrap();
+        assert_eq!((data.list[0].code.as_str(), data.list[0].aid_num), ("1", 2));
+    }
+
+    #[test]
+    fn wish_form_body_percent_encodes_utf8_content() {
+        let cases = [
+            ("中文：（全角）", "%E4%B8%AD%E6%96%87%EF%BC%9A%EF%BC%88%E5%85%A8%E8%A7%92%EF%BC%89"),
+            ("emoji 👍🏻❤️", "emoji+%F0%9F%91%8D%F0%9F%8F%BB%E2%9D%A4%EF%B8%8F"),
+            ("许愿 🎉 a+b&c=d%/?", "%E8%AE%B8%E6%84%BF+%F0%9F%8E%89+a%2Bb%26c%3Dd%25%2F%3F"),
+            ("<script>\"x\"</script>#!", "%3Cscript%3E%22x%22%3C%2Fscript%3E%23%21"),
+        ];
+        for (content, encoded) in cases {
+            let account = AccountConfig {
+                content: Some(content.to_string()),
+                ..AccountConfig::new("UID=1_A1_1".to_string())
+            };
+            let client = test_client("wish_encoding", vec![account]);
+            let single = Api115ClientSingle::new(client.client.clone(), 0, client.ctx.clone());
+            let wish = single.prepare_wish(5);
+            let expected = format!("content={}&images=&rewardSpace=5", encoded);
+
+            assert_eq!(wish.encoded_body(), expected, "{}", content);
+            let request = wish.build(&client.client).build().unwrap();
+            let body = request.body().and_then(|body| body.as_bytes()).unwrap();
+            assert_eq!(body, expected.as_bytes(), "{}", content);
+            assert_eq!(request.headers()["Content-Type"], "application/x-www-form-urlencoded");
+        }
+    }
+
+    #[test]
+    fn login_state_detects_expired_cookie() {
+        let expired = |body: &str| serde_json::from_str::<LoginState>(body).unwrap().is_expired();
+        assert!(expired(r#"{"state": 0, "code": 990001, "message": ""}"#));
+        assert!(expired(r#"{"state": 0, "code": "990001", "message": ""}"#));
+        assert!(expired(r#"{"state": 0, "code": 0, "message": "请重新登录"}"#));
+        assert!(!expired(r#"{"state": 1, "code": 0, "message": "登录奖励已发放"}"#));
+        assert!(!expired(r#"{"state": 1, "code": "", "data": {}}"#));
+    }
+
+    #[test]
+    fn aid_request_leaves_transport_headers_to_reqwest() {
+        let client = test_client("aid_headers", vec![AccountConfig::new("UID=1_A1_1".to_string())]);
+        let single = Api115ClientSingle::new(client.client.clone(), 0, client.ctx.clone());
+        let payload = [("id".to_string(), "abc".to_string()), ("content".to_string(), "加油".to_string())];
+        let request = single.aid_request("UID=2_A1_1", &payload).build().unwrap();
+
+        for name in ["Content-Length", "Host", "Connection", "Accept-Encoding"] {
+            assert!(!request.headers().contains_key(name), "不应手动设置 {}", name);
+        }
+        assert_eq!(request.headers()["User-Agent"], AID_USER_AGENT);
+        assert_eq!(request.headers()["Cookie"], "UID=2_A1_1");
+        assert_eq!(request.url().as_str(), single.api_url(AID_DESIRE_ENDPOINT));
+    }
+
+    /// 回放 tests/fixtures/one_wish.json：许愿、审核通过、列出一个待助力愿望、助力并采纳
+    #[tokio::test]
+    async fn process_single_account_replays_cassette() {
+        let _scale = time_scale(0.0).await;
+        let config = AppConfig {
+            aid_cookie: Secret::new("UID=2_A1_1; CID=a; SEID=a".to_string()),
+            wish_cookies: vec![AccountConfig::new("UID=1_A1_1; CID=w; SEID=w".to_string())],
+            state_dir: test_state_dir("replay"),
+            vcr: VcrConfig {
+                mode: VcrMode::Replay,
+                cassette: concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/one_wish.json").to_string(),
+            },
+            ..Default::default()
+        };
+        let client = Api115Client::new(config);
+        let single = Api115ClientSingle::new(client.client.clone(), 0, client.ctx.clone());
+
+        single.process_single_account().await.unwrap();
+        let report = single.report();
+        assert!(matches!(&report.wish, WishOutcome::Success(id) if id == "W1"), "{:?}", report);
+        assert_eq!((report.aided, report.adopted, report.failed), (1, 1, 0), "{:?}", report);
+        assert_eq!(report.reward, 5);
+        assert_eq!(report.nickname, "测试用户");
+        let actions: Vec<_> = report.actions.iter().map(|a| (a.action, a.wish_code.as_str(), a.success)).collect();
+        assert_eq!(actions, vec![("wish", "W1", true), ("aid", "D1", true), ("adopt", "D1", true)]);
+        assert_eq!(client.ctx.reports.lock().unwrap().len(), 1);
+    }
+
+    /// 等待时间倍率是全局设置，依赖倍率的测试持有此锁，避免并行的测试互相改动
+    static TIME_SCALE: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+    async fn time_scale(scale: f64) -> tokio::sync::MutexGuard<'static, ()> {
+        let guard = TIME_SCALE.lock().await;
+        pace::set_time_scale(scale);
+        guard
+    }
+
+    const WISH_COOKIE: &str = "UID=1_A1_1; CID=w; SEID=w";
+    const AID_COOKIE: &str = "UID=2_A1_1; CID=a; SEID=a";
+
+    /// 模拟服务收到的一个请求
+    #[derive(Debug, Clone)]
+    struct Hit {
+        /// 开始处理和响应完成的时间
+        started: Instant,
+        finished: Instant,
+        /// 请求路径，如 /aid_desire
+        endpoint: String,
+        target: String,
+        cookie: String,
+        body: String,
+    }
+
+    /// 模拟 115 接口的本地服务，记录每个请求的时间、内容和同时处理中的最大请求数
+    struct FakeServer {
+        base: String,
+        /// 按开始处理的顺序记录
+        hits: Arc<Mutex<Vec<Hit>>>,
+        max_in_flight: Arc<AtomicUsize>,
+    }
+
+    impl FakeServer {
+        /// 每个请求都停留 hold 后再响应，以便重叠的请求能被观察到
+        fn start(hold: Duration) -> Self {
+            let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            let base = format!("http://{}", listener.local_addr().unwrap());
+            let hits = Arc::new(Mutex::new(Vec::new()));
+            let max_in_flight = Arc::new(AtomicUsize::new(0));
+            let in_flight = Arc::new(AtomicUsize::new(0));
+
+            let (hits_, max_) = (hits.clone(), max_in_flight.clone());
+            std::thread::spawn(move || {
+                for stream in listener.incoming().flatten() {
+                    let (hits, max_in_flight, in_flight) = (hits_.clone(), max_.clone(), in_flight.clone());
+                    std::thread::spawn(move || Self::serve(stream, hold, &hits, &max_in_flight, &in_flight));
+                }
+            });
+            Self { base, hits, max_in_flight }
+        }
+
+        fn serve(
+            mut stream: std::net::TcpStream,
+            hold: Duration,
+            hits: &Mutex<Vec<Hit>>,
+            max_in_flight: &AtomicUsize,
+            in_flight: &AtomicUsize,
+        ) {
+            use std::io::{Read, Write};
+            loop {
+                // 读取 header 和按 Content-Length 的请求体
+                let mut request = Vec::new();
+                let mut buf = [0u8; 4096];
+                let header_end = loop {
+                    if let Some(end) = request.windows(4).position(|w| w == b"\r\n\r\n") {
+                        break end + 4;
+                    }
+                    match stream.read(&mut buf) {
+                        Ok(0) | Err(_) => return,
+                        Ok(n) => request.extend_from_slice(&buf[..n]),
+                    }
+                };
+                let head = String::from_utf8_lossy(&request[..header_end]).into_owned();
+                let header = |name: &str| {
+                    head.lines()
+                        .find_map(|line| {
+                            let (key, value) = line.split_once(':')?;
+                            key.eq_ignore_ascii_case(name).then(|| value.trim().to_string())
+                        })
+                        .unwrap_or_default()
+                };
+                let length = header("content-length").parse().unwrap_or(0);
+                while request.len() < header_end + length {
+                    match stream.read(&mut buf) {
+                        Ok(0) | Err(_) => return,
+                        Ok(n) => request.extend_from_slice(&buf[..n]),
+                    }
+                }
+                let target = head.split_whitespace().nth(1).unwrap_or_default().to_string();
+                let path = target.split('?').next().unwrap_or_default();
+                let mut hit = Hit {
+                    started: Instant::now(),
+                    finished: Instant::now(),
+                    endpoint: format!("/{}", path.rsplit('/').next().unwrap_or_default()),
+                    cookie: header("cookie"),
+                    body: String::from_utf8_lossy(&request[header_end..]).into_owned(),
+                    target,
+                };
+
+                let current = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                max_in_flight.fetch_max(current, Ordering::SeqCst);
+                let index = {
+                    let mut hits = hits.lock().unwrap();
+                    hits.push(hit.clone());
+                    hits.len() - 1
+                };
+                std::thread::sleep(hold);
+                let response = Self::respond(&hit);
+                hit.finished = Instant::now();
+                hits.lock().unwrap()[index].finished = hit.finished;
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+
+                let reply = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                    response.len(),
+                    response
+                );
+                if stream.write_all(reply.as_bytes()).is_err() {
+                    return;
+                }
+            }
+        }
+
+        /// 按接口返回成功的响应
+        ///
+        /// 每个许愿账号的列表中有 4 个待助力愿望，code 为 D<UID>-<序号>，UID 为 1 时为 D1 到 D4；
+        /// 除 AID_COOKIE 外的账号查询详情时都视为愿望的主人，is_my_desire 为 1、button 为可采纳；
+        /// id 以 N 开头的愿望对任何账号都没有可执行的操作
+        fn respond(hit: &Hit) -> String {
+            let owner = hit.cookie != AID_COOKIE;
+            let user_id = cookie::user_id(&hit.cookie).unwrap_or_default();
+            let wish = |id: &str| {
+                let button = match id {
+                    _ if id.starts_with('N') => 0,
+                    _ if owner => 2,
+                    _ => 1,
+                };
+                serde_json::json!({
+                    "id": id, "content": "c", "images": "", "edit_time": 0, "audit_status": 1, "status": 0,
+                    "aid": 0, "reward": 1, "sj_reward": 1, "code": format!("C{}", id), "aid_num": 0, "images_data": [],
+                    "user_info": { "user_name": "u", "face_l": "" },
+                    "is_my_desire": i32::from(owner), "button": button,
+                })
+            };
+            let form_value = |name: &str| {
+                hit.body
+                    .split('&')
+                    .find_map(|pair| pair.strip_prefix(name)?.strip_prefix('='))
+                    .unwrap_or_default()
+                    .to_string()
+            };
+            let data = match hit.endpoint.as_str() {
+                WISH_ENDPOINT => serde_json::json!({ "xys_id": format!("W{}", user_id) }),
+                MY_DESIRE_ENDPOINT => {
+                    let code = |i: usize| if user_id == "1" { format!("D{}", i) } else { format!("D{}-{}", user_id, i) };
+                    let list: Vec<_> = (1..=4).map(|i| serde_json::json!({ "code": code(i), "aid_num": 0 })).collect();
+                    serde_json::json!({ "list": list, "count": 4 })
+                }
+                GET_DESIRE_INFO_ENDPOINT => wish(hit.target.split("id=").nth(1).unwrap_or_default()),
+                AID_DESIRE_ENDPOINT => serde_json::json!({ "aid_id": format!("A{}", form_value("id")) }),
+                _ => serde_json::json!({}),
+            };
+            serde_json::json!({ "state": 1, "code": 0, "message": "", "data": data }).to_string()
+        }
+
+        /// 按开始处理的顺序返回收到的请求
+        fn hits(&self) -> Vec<Hit> {
+            self.hits.lock().unwrap().clone()
+        }
+
+        /// 按开始处理的顺序返回各请求的端点
+        fn endpoints(&self) -> Vec<String> {
+            self.hits().into_iter().map(|hit| hit.endpoint).collect()
+        }
+    }
+
+    /// 指向模拟服务的客户端，第一个许愿账号为 WISH_COOKIE
+    fn fake_server_client(name: &str, server: &FakeServer, accounts: usize, concurrency: usize) -> Api115Client {
+        let wish_cookies = (0..accounts)
+            .map(|i| match i {
+                0 => WISH_COOKIE.to_string(),
+                _ => format!("UID={}_A1_1; CID=w; SEID=w", i + 2),
+            })
+            .map(AccountConfig::new)
+            .collect();
+        let config = AppConfig {
+            aid_cookie: Secret::new(AID_COOKIE.to_string()),
+            wish_cookies,
+            state_dir: test_state_dir(name),
+            aid_concurrency: concurrency,
+            ..Default::default()
+        };
+        Api115Client::with_api_base(config, &server.base)
+    }
+
+    /// 助力用详情中的 code 而不是列表中的愿望 id，采纳用助力返回的 aid_id
+    #[tokio::test]
+    async fn process_single_account_passes_ids_along() {
+        let _scale = time_scale(0.0).await;
+        let server = FakeServer::start(Duration::ZERO);
+        let client = fake_server_client("ids_along", &server, 1, 1);
+        let single = Api115ClientSingle::new(client.client.clone(), 0, client.ctx.clone());
+
+        single.process_single_account().await.unwrap();
+        let report = single.report();
+        assert_eq!((report.aided, report.adopted, report.failed), (4, 4, 0), "{:?}", report);
+
+        let bodies = |endpoint: &str| -> Vec<String> {
+            server.hits().into_iter().filter(|hit| hit.endpoint == endpoint).map(|hit| hit.body).collect()
+        };
+        let aids = bodies(AID_DESIRE_ENDPOINT);
+        let adopts = bodies(ADOPT_ENDPOINT);
+        assert_eq!((aids.len(), adopts.len()), (4, 4));
+        for (i, (aid, adopt)) in aids.iter().zip(&adopts).enumerate() {
+            let wish_id = format!("D{}", i + 1);
+            assert!(aid.split('&').any(|pair| pair == format!("id=C{}", wish_id)), "{}", aid);
+            assert!(adopt.split('&').any(|pair| pair == format!("did={}", wish_id)), "{}", adopt);
+            assert!(adopt.split('&').any(|pair| pair == format!("aid=AC{}", wish_id)), "{}", adopt);
+        }
+    }
+
+    /// 按比例缩短等待的倍率，账号间隔 30 秒缩短为 60 毫秒
+    const SHORT_WAITS: f64 = 0.002;
+
+    /// 一个许愿账号从许愿开始依次发出的请求
+    fn account_endpoints() -> Vec<&'static str> {
+        let mut endpoints = vec![WISH_ENDPOINT, GET_DESIRE_INFO_ENDPOINT, MY_DESIRE_ENDPOINT];
+        for _ in 0..4 {
+            endpoints.extend([GET_DESIRE_INFO_ENDPOINT, AID_DESIRE_ENDPOINT, GET_DESIRE_INFO_ENDPOINT, ADOPT_ENDPOINT]);
+        }
+        endpoints
+    }
+
+    /// 从第一个许愿请求开始的请求，以及第二个账号的许愿请求在其中的位置
+    fn run_hits(server: &FakeServer) -> (Vec<Hit>, usize) {
+        let hits = server.hits();
+        let first = hits.iter().position(|hit| hit.endpoint == WISH_ENDPOINT).unwrap();
+        let hits = hits[first..].to_vec();
+        let second = hits.iter().rposition(|hit| hit.endpoint == WISH_ENDPOINT).unwrap();
+        (hits, second)
+    }
+
+    #[tokio::test]
+    async fn serial_run_keeps_order_and_waits() {
+        let _scale = time_scale(SHORT_WAITS).await;
+        let server = FakeServer::start(Duration::from_millis(5));
+        let client = fake_server_client("serial_run", &server, 2, 1);
+
+        assert_eq!(client.process_all_accounts().await.unwrap(), RunStatus::Success);
+        assert_eq!(server.max_in_flight.load(Ordering::SeqCst), 1);
+
+        // 每个请求结束后才开始下一个，账号、愿望都按顺序处理
+        let (hits, second) = run_hits(&server);
+        assert!(hits.windows(2).all(|pair| pair[1].started >= pair[0].finished));
+        let endpoints: Vec<_> = hits.iter().map(|hit| hit.endpoint.as_str()).collect();
+        assert_eq!(endpoints, account_endpoints().repeat(2));
+
+        // 账号之间、相邻两个愿望之间的等待都按倍率生效
+        let gap = hits[second].started - hits[second - 1].finished;
+        assert!(gap >= pace::scale(Duration::from_secs(ACCOUNT_INTERVAL)), "账号间隔 {:?}", gap);
+        let adopts: Vec<_> = (0..second).filter(|&i| hits[i].endpoint == ADOPT_ENDPOINT).collect();
+        for &adopt in &adopts[..adopts.len() - 1] {
+            let gap = hits[adopt + 1].started - hits[adopt].finished;
+            assert!(gap >= pace::scale(Duration::from_secs(DEFAULT_WAIT_TIME)), "愿望间隔 {:?}", gap);
+        }
+    }
+
+    #[tokio::test]
+    async fn concurrent_run_stays_within_limit() {
+        let _scale = time_scale(SHORT_WAITS).await;
+        let server = FakeServer::start(Duration::from_millis(20));
+        let client = fake_server_client("concurrent_run", &server, 2, 2);
+
+        assert_eq!(client.process_all_accounts().await.unwrap(), RunStatus::Success);
+        let max_in_flight = server.max_in_flight.load(Ordering::SeqCst);
+        assert!(max_in_flight <= 2, "同时处理中的请求数 {}", max_in_flight);
+        assert!(max_in_flight > 1, "并发助力没有生效");
+
+        // 并发只发生在一个账号内部，账号之间仍然串行并等待
+        let (hits, second) = run_hits(&server);
+        let last_finished = hits[..second].iter().map(|hit| hit.finished).max().unwrap();
+        assert!(hits[second].started - last_finished >= pace::scale(Duration::from_secs(ACCOUNT_INTERVAL)));
+        let reports = client.ctx.reports.lock().unwrap();
+        assert!(reports.iter().all(|r| (r.aided, r.adopted, r.failed) == (4, 4, 0)), "{:?}", reports);
+    }
+
+    /// 账号很多时单账号客户端只引用共享的运行上下文，不各自复制配置和缓存
+    ///
+    /// 只检查共享关系，不测量耗时和内存占用
+    #[test]
+    fn many_accounts_share_run_context() {
+        const ACCOUNTS: usize = 600;
+        let accounts = (0..ACCOUNTS)
+            .map(|i| AccountConfig {
+                overrides: (i % 2 == 0).then(|| serde_yaml::from_str("retry: { network: { times: 5 } }").unwrap()),
+                ..AccountConfig::new(format!("UID={}_A1_1; CID=c{}; SEID=s{}", i + 1, i, i))
+            })
+            .collect();
+
+        let client = test_client("many_accounts", accounts);
+        let singles: Vec<_> = (0..ACCOUNTS)
+            .map(|index| Api115ClientSingle::new(client.client.clone(), index, client.ctx.clone()))
+            .collect();
+
+        assert_eq!(Arc::strong_count(&client.ctx), ACCOUNTS + 1);
+        assert!(singles.iter().all(|single| Arc::ptr_eq(&single.ctx, &client.ctx)));
+        // 只有配置了 overrides 的账号持有合并后的配置，其余直接使用全局配置
+        for (index, single) in singles.iter().enumerate() {
+            let merged = single.config.as_ref().map(|config| Arc::ptr_eq(config, client.ctx.account_configs[index].as_ref().unwrap()));
+            assert_eq!(merged, (index % 2 == 0).then_some(true));
+        }
+        assert!(std::ptr::eq(singles[1].config(), &client.ctx.config));
+        assert_eq!(singles[0].config().retry.network.times, 5);
+    }
+
+    #[tokio::test]
+    async fn aid_after_audit_polling_reads_details_as_aid_account() {
+        let _scale = time_scale(0.0).await;
+        let server = FakeServer::start(Duration::ZERO);
+        let client = fake_server_client("audit_then_aid", &server, 1, 1);
+        let single = Api115ClientSingle::new(client.client.clone(), 0, client.ctx.clone());
+
+        // 许愿账号轮询审核时看到的是自己的愿望，助力时须以助愿账号重新查询，不能复用
+        assert!(single.wait_for_audit("D1").await.unwrap());
+        assert_eq!(single.aid_desire("D1").await.unwrap(), Some("ACD1".to_string()));
+        let details = server.endpoints().iter().filter(|e| *e == GET_DESIRE_INFO_ENDPOINT).count();
+        assert_eq!(details, 2);
+    }
+
+    #[tokio::test]
+    async fn desire_details_are_cached_per_aid_account() {
+        let _scale = time_scale(0.0).await;
+        let server = FakeServer::start(Duration::ZERO);
+        let config = AppConfig {
+            aid_cookie: Secret::new(AID_COOKIE.to_string()),
+            // 第二个助愿账号就是许愿账号本身
+            aid_cookies: vec![Secret::new(WISH_COOKIE.to_string())],
+            wish_cookies: vec![AccountConfig::new(WISH_COOKIE.to_string())],
+            state_dir: test_state_dir("cache_per_aid_account"),
+            ..Default::default()
+        };
+        let client = Api115Client::with_api_base(config, &server.base);
+        let single = Api115ClientSingle::new(client.client.clone(), 0, client.ctx.clone());
+
+        assert_eq!(single.aid_desire("D1").await.unwrap(), Some("ACD1".to_string()));
+        // 换用第二个助愿账号时重新查询详情，发现是自己的愿望而跳过，跳过不计入使用次数
+        assert_eq!(single.aid_desire("D1").await.unwrap(), None);
+        assert_eq!(single.aid_desire("D1").await.unwrap(), None);
+        assert_eq!(client.ctx.aid_accounts.usage(), vec![1, 0]);
+        let aids = server.endpoints().iter().filter(|e| *e == AID_DESIRE_ENDPOINT).count();
+        assert_eq!(aids, 1);
+    }
+
+    #[test]
+    fn invalid_account_overrides_are_rejected() {
+        let config = |overrides: &str| AppConfig {
+            aid_cookie: Secret::new(AID_COOKIE.to_string()),
+            wish_cookies: vec![AccountConfig::new(WISH_COOKIE.to_string()), account_with_overrides(overrides)],
+            ..Default::default()
+        };
+
+        let error = config("reward_space: { strategy: random, min: 9, max: 1 }").check_account_overrides().unwrap_err();
+        assert!(error.to_string().starts_with("第 2 个许愿账号的 overrides 无效"), "{:#}", error);
+        assert!(config("reward_space: { value: 0 }").check_account_overrides().is_err());
+        assert!(config("only_wishes: [a]\nignore_wishes: [b]").check_account_overrides().is_err());
+        assert!(config("reward_space: { strategy: random, min: 1, max: 9 }").check_account_overrides().is_ok());
+    }
+
+    #[tokio::test]
+    async fn button_without_actions_skips_aid_and_adopt() {
+        let _scale = time_scale(0.0).await;
+        let server = FakeServer::start(Duration::ZERO);
+        let client = fake_server_client("button_skip", &server, 1, 1);
+        let single = Api115ClientSingle::new(client.client.clone(), 0, client.ctx.clone());
+
+        assert_eq!(single.aid_desire("N1").await.unwrap(), None);
+        single.handle_adopt("N1", "AN1").await.unwrap();
+        assert_eq!(server.endpoints(), vec![GET_DESIRE_INFO_ENDPOINT, GET_DESIRE_INFO_ENDPOINT]);
+        assert_eq!(single.report().adopted, 0);
+        assert_eq!(single.report().failed, 0);
+    }
+}
@@ -0,0 +1,41 @@
+//! 进程内存占用
+//!
+//! 从 /proc/self/status 读取常驻内存（VmRSS）和峰值（VmHWM），仅 Linux 可用，
+//! 其他平台返回 None
+
+use std::fmt;
+
+/// 进程内存占用（KB）
+#[derive(Debug, Clone, Copy)]
+pub struct MemoryUsage {
+    pub rss_kb: u64,
+    pub peak_kb: u64,
+}
+
+impl MemoryUsage {
+    pub fn rss_mb(&self) -> u64 {
+        self.rss_kb / 1024
+    }
+}
+
+impl fmt::Display for MemoryUsage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "常驻内存 {} MB，峰值 {} MB", self.rss_mb(), self.peak_kb / 1024)
+    }
+}
+
+/// 读取当前进程的内存占用，不支持的平台或读取失败时返回 None
+pub fn usage() -> Option<MemoryUsage> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let field = |name: &str| {
+        status
+            .lines()
+            .find_map(|line| line.strip_prefix(name))
+            .and_then(|rest| rest.trim().trim_end_matches("kB").trim().parse::<u64>().ok())
+    };
+    let rss_kb = field("VmRSS:")?;
+    Some(MemoryUsage {
+        rss_kb,
+        peak_kb: field("VmHWM:").unwrap_or(rss_kb),
+    })
+}
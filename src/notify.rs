@@ -0,0 +1,136 @@
+//! 运行结果通知
+//!
+//! 把运行汇总发送到企业微信、钉钉、飞书的群机器人。各平台的消息格式和加签方式不同：
+//! - 企业微信：markdown 消息，无加签
+//! - 钉钉：markdown 消息，加签时在 webhook 地址上附加 timestamp 和 sign 参数
+//! - 飞书：文本消息，加签时在请求体中附加 timestamp 和 sign 字段
+//!
+//! 发送失败只告警，不影响主流程
+
+use crate::run_id;
+use anyhow::{anyhow, bail, Result};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use hmac::{Hmac, Mac};
+use tracing::{info, warn};
+use reqwest::{Client, Url};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::Sha256;
+use std::fmt;
+
+/// 机器人所属平台
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChannelKind {
+    /// 企业微信
+    Wecom,
+    /// 钉钉
+    Dingtalk,
+    /// 飞书
+    Feishu,
+}
+
+impl fmt::Display for ChannelKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChannelKind::Wecom => write!(f, "企业微信"),
+            ChannelKind::Dingtalk => write!(f, "钉钉"),
+            ChannelKind::Feishu => write!(f, "飞书"),
+        }
+    }
+}
+
+/// 一个通知渠道
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotifyChannel {
+    #[serde(rename = "type")]
+    pub kind: ChannelKind,
+    /// 机器人的 webhook 地址
+    pub webhook: String,
+    /// 钉钉、飞书机器人的加签密钥，未开启加签时不填
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+}
+
+impl NotifyChannel {
+    /// 发送一条消息，content 为 markdown 文本，飞书以纯文本发送
+    pub async fn send(&self, client: &Client, title: &str, content: &str) -> Result<()> {
+        let secret = self.secret.as_deref().filter(|secret| !secret.is_empty());
+
+        let (url, body) = match self.kind {
+            ChannelKind::Wecom => (
+                Url::parse(&self.webhook)?,
+                json!({
+                    "msgtype": "markdown",
+                    "markdown": { "content": format!("**{}**\n{}", title, content) },
+                }),
+            ),
+            ChannelKind::Dingtalk => {
+                let mut url = Url::parse(&self.webhook)?;
+                if let Some(secret) = secret {
+                    let timestamp = chrono::Utc::now().timestamp_millis();
+                    let sign = hmac_base64(secret.as_bytes(), format!("{}\n{}", timestamp, secret).as_bytes())?;
+                    url.query_pairs_mut()
+                        .append_pair("timestamp", &timestamp.to_string())
+                        .append_pair("sign", &sign);
+                }
+                (
+                    url,
+                    json!({
+                        "msgtype": "markdown",
+                        "markdown": { "title": title, "text": format!("### {}\n{}", title, content) },
+                    }),
+                )
+            }
+            ChannelKind::Feishu => {
+                let mut body = json!({
+                    "msg_type": "text",
+                    "content": { "text": format!("{}\n{}", title, content) },
+                });
+                if let Some(secret) = secret {
+                    // 飞书以 "timestamp\nsecret" 为密钥，对空消息签名
+                    let timestamp = chrono::Utc::now().timestamp();
+                    let sign = hmac_base64(format!("{}\n{}", timestamp, secret).as_bytes(), b"")?;
+                    body["timestamp"] = json!(timestamp.to_string());
+                    body["sign"] = json!(sign);
+                }
+                (Url::parse(&self.webhook)?, body)
+            }
+        };
+
+        let response = client.post(url).json(&body).send().await?;
+        let status = response.status();
+        let result: Value = response.json().await.unwrap_or(Value::Null);
+        if !status.is_success() {
+            bail!("状态码: {}，返回: {}", status, result);
+        }
+
+        // 企业微信、钉钉返回 errcode，飞书返回 code（旧版为 StatusCode）
+        let code = ["errcode", "code", "StatusCode"]
+            .iter()
+            .find_map(|key| result.get(*key).and_then(Value::as_i64))
+            .unwrap_or(0);
+        if code != 0 {
+            bail!("机器人返回错误: {}", result);
+        }
+        Ok(())
+    }
+}
+
+/// 计算 HMAC-SHA256 并以 base64 编码
+fn hmac_base64(key: &[u8], message: &[u8]) -> Result<String> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).map_err(|e| anyhow!("无效的加签密钥: {}", e))?;
+    mac.update(message);
+    Ok(BASE64.encode(mac.finalize().into_bytes()))
+}
+
+/// 向所有渠道发送消息，失败只告警
+pub async fn send_all(client: &Client, channels: &[NotifyChannel], title: &str, content: &str) {
+    let content = format!("{}\n\n运行 ID：{}", content.trim_end(), run_id::current());
+    for channel in channels {
+        match channel.send(client, title, &content).await {
+            Ok(()) => info!("已发送{}通知", channel.kind),
+            Err(e) => warn!("发送{}通知失败: {}", channel.kind, e),
+        }
+    }
+}
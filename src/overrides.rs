@@ -0,0 +1,155 @@
+//! 账号级的配置覆盖
+//!
+//! 账号配置中的 `overrides` 段与全局配置的结构相同，只需写出要覆盖的字段。
+//! 处理该账号时把 overrides 逐层合并到全局配置上：映射按字段递归合并，
+//! 其余值（数字、字符串、列表）整体替换。只影响单个账号流程的配置才能覆盖，
+//! 整轮运行共用的配置（账号列表、状态目录、日志等）写在 overrides 中不生效
+
+use crate::secret;
+use anyhow::{Context, Result};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_yaml::Value;
+
+/// 整轮运行共用、不能按账号覆盖的配置项
+const RUN_LEVEL_KEYS: &[&str] = &[
+    "aid_cookie",
+    "aid_cookies",
+    "aid_balance",
+    "wish_cookies",
+    "accounts_csv",
+    "activity_path",
+    "state_dir",
+    "diag_dir",
+    "logging",
+    "idempotency",
+    "checkpoint",
+    "throttle",
+    "max_total_requests",
+    "user_agent",
+    "wish_contents",
+    "wish_content_file",
+    "aid_contents",
+    "aid_content_file",
+    "vcr",
+    "event_socket",
+    "markdown_report",
+    "csv_report",
+    "notify",
+    "time_scale",
+    "content_filter",
+    "distinct_wish_contents",
+    "desire_code_cache_size",
+    "desire_code_cache_ttl",
+    "desire_info_debounce",
+    "diag_on_account_failure",
+    "on_account_failure",
+    "disable_after_failures",
+    "health_check",
+    "aid_view",
+    "memory_warn_mb",
+    "success_rate_alert",
+    "diff_report",
+    "account_soft_limit",
+    "rate_limit_retries",
+    "network_retries",
+];
+
+/// overrides 中写了但不会生效的整轮配置项
+pub fn run_level_keys(overrides: &Value) -> Vec<String> {
+    let Some(mapping) = overrides.as_mapping() else {
+        return Vec::new();
+    };
+    mapping
+        .keys()
+        .filter_map(Value::as_str)
+        .filter(|key| RUN_LEVEL_KEYS.contains(key))
+        .map(str::to_string)
+        .collect()
+}
+
+/// 把 overrides 合并到 base 上，得到该账号生效的配置
+pub fn apply<T: Serialize + DeserializeOwned>(base: &T, overrides: &Value) -> Result<T> {
+    if !overrides.is_mapping() {
+        anyhow::bail!("overrides 必须是键值对形式");
+    }
+    let mut merged = secret::exposed(|| serde_yaml::to_value(base)).context("序列化全局配置失败")?;
+    merge(&mut merged, overrides);
+    serde_yaml::from_value(merged).context("overrides 中的配置无效")
+}
+
+fn merge(base: &mut Value, overrides: &Value) {
+    match (base, overrides) {
+        (Value::Mapping(base), Value::Mapping(overrides)) => {
+            for (key, value) in overrides {
+                match base.get_mut(key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        base.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+        }
+        (base, value) => *base = value.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Inner {
+        times: u32,
+        backoff: u64,
+    }
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Config {
+        name: String,
+        list: Vec<String>,
+        inner: Inner,
+    }
+
+    fn base() -> Config {
+        Config {
+            name: "global".to_string(),
+            list: vec!["a".to_string(), "b".to_string()],
+            inner: Inner { times: 3, backoff: 2 },
+        }
+    }
+
+    fn yaml(text: &str) -> Value {
+        serde_yaml::from_str(text).unwrap()
+    }
+
+    #[test]
+    fn nested_mappings_merge_field_by_field() {
+        let merged = apply(&base(), &yaml("inner: { times: 5 }")).unwrap();
+        assert_eq!(merged.inner, Inner { times: 5, backoff: 2 });
+        assert_eq!(merged.name, "global");
+    }
+
+    #[test]
+    fn lists_are_replaced_as_a_whole() {
+        let merged = apply(&base(), &yaml("list: [c]")).unwrap();
+        assert_eq!(merged.list, vec!["c".to_string()]);
+    }
+
+    #[test]
+    fn empty_overrides_keep_base() {
+        assert_eq!(apply(&base(), &yaml("{}")).unwrap(), base());
+    }
+
+    #[test]
+    fn invalid_overrides_are_rejected() {
+        assert!(apply(&base(), &yaml("[1, 2]")).is_err());
+        assert!(apply(&base(), &yaml("inner: { times: many }")).is_err());
+    }
+
+    #[test]
+    fn run_level_keys_are_reported() {
+        let keys = run_level_keys(&yaml("state_dir: /tmp\nhealth_check: { enabled: true }\nretry: {}"));
+        assert_eq!(keys, vec!["state_dir".to_string(), "health_check".to_string()]);
+    }
+}
@@ -0,0 +1,68 @@
+//! 集中调度的等待
+//!
+//! 流程中的等待都经过这里的 `sleep`，按全局的 time_scale 系数统一缩放：
+//! 调试时设为 0.1 或 0 快速跑完整个流程，生产环境设为 1.5 等更保守的节奏。
+//! 系数在启动时设置一次，之后各处直接读取
+
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+/// 允许的最大系数，配置和命令行中超过该值视为错误
+pub const MAX_TIME_SCALE: f64 = 100.0;
+
+/// 缩放后单次等待的上限，避免系数过大时 Duration 溢出
+const MAX_WAIT: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// 以位模式保存的 f64 系数，默认 1.0
+static TIME_SCALE: AtomicU64 = AtomicU64::new(0x3FF0_0000_0000_0000);
+
+/// 设置全局等待系数，需为 0 到 MAX_TIME_SCALE 之间的数
+pub fn set_time_scale(scale: f64) {
+    TIME_SCALE.store(scale.to_bits(), Ordering::Relaxed);
+}
+
+pub fn time_scale() -> f64 {
+    f64::from_bits(TIME_SCALE.load(Ordering::Relaxed))
+}
+
+/// 按系数缩放等待时间
+pub fn scale(duration: Duration) -> Duration {
+    scale_by(duration, time_scale())
+}
+
+/// 按给定系数缩放，结果不超过 MAX_WAIT；系数为负数或 NaN 时视为 0
+fn scale_by(duration: Duration, factor: f64) -> Duration {
+    Duration::try_from_secs_f64(duration.as_secs_f64() * factor)
+        .unwrap_or(if factor > 0.0 { MAX_WAIT } else { Duration::ZERO })
+        .min(MAX_WAIT)
+}
+
+/// 按系数缩放后等待，缩放后为 0 时不等待
+pub async fn sleep(duration: Duration) {
+    let duration = scale(duration);
+    if !duration.is_zero() {
+        tokio::time::sleep(duration).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scales_by_factor() {
+        assert_eq!(scale_by(Duration::from_secs(10), 1.0), Duration::from_secs(10));
+        assert_eq!(scale_by(Duration::from_secs(10), 0.5), Duration::from_secs(5));
+        assert_eq!(scale_by(Duration::from_secs(10), 0.0), Duration::ZERO);
+    }
+
+    #[test]
+    fn saturates_instead_of_overflowing() {
+        assert_eq!(scale_by(Duration::from_secs(10), 1e18), MAX_WAIT);
+        assert_eq!(scale_by(Duration::from_secs(10), f64::INFINITY), MAX_WAIT);
+        assert_eq!(scale_by(Duration::from_secs(10), f64::NAN), Duration::ZERO);
+        assert_eq!(scale_by(Duration::from_secs(10), -1.0), Duration::ZERO);
+    }
+}
@@ -0,0 +1,149 @@
+//! 分页列表的翻页判断
+//!
+//! 115 列表接口返回的 count 偶尔与实际条目不符：count 大于当前页条目数（还有下一页）、
+//! count 虚高导致后面的页为空，或者 count 为 0 但 list 非空。翻页以实际累计获取的条目为准，
+//! count 只用来判断是否可能还有下一页；与累计数对不上时记为分页数据异常，由调用方告警。
+//!
+//! 每页条数固定，按页码翻页，起始偏移由页码和每页条数算出；多取到的条目由调用方截断
+
+/// 单次列表最多请求的页数，避免 count 异常时无限翻页
+const MAX_PAGES: usize = 5;
+
+/// 一次分页获取的进度
+#[derive(Debug)]
+pub struct Pager {
+    wanted: usize,
+    page_size: usize,
+    collected: usize,
+    pages: usize,
+    anomaly: Option<String>,
+}
+
+impl Pager {
+    /// 最多获取 wanted 条，每页请求 page_size 条
+    pub fn new(wanted: usize, page_size: usize) -> Self {
+        Self { wanted, page_size: page_size.max(1), collected: 0, pages: 0, anomaly: None }
+    }
+
+    /// 下一页的页码，从 1 开始
+    pub fn page(&self) -> usize {
+        self.pages + 1
+    }
+
+    /// 下一页的起始偏移
+    pub fn start(&self) -> usize {
+        self.pages * self.page_size
+    }
+
+    /// 每页请求的条目数
+    pub fn page_size(&self) -> usize {
+        self.page_size
+    }
+
+    /// 记录一页的结果，返回是否需要继续请求下一页
+    pub fn record(&mut self, count: i32, received: usize) -> bool {
+        self.pages += 1;
+        self.collected += received;
+        let count = usize::try_from(count).unwrap_or(0);
+
+        if count == 0 && received > 0 {
+            self.note_anomaly(format!("count 为 0，但第 {} 页返回了 {} 条", self.pages, received));
+            return false;
+        }
+        if count < self.collected {
+            self.note_anomaly(format!("count 为 {}，小于实际获取到的 {} 条", count, self.collected));
+            return false;
+        }
+        if received < self.page_size {
+            // 不满一页说明已经到底，count 仍大于累计数时说明 count 虚高
+            if count > self.collected {
+                self.note_anomaly(format!(
+                    "count 为 {}，但第 {} 页只返回了 {} 条，实际只获取到 {} 条",
+                    count, self.pages, received, self.collected
+                ));
+            }
+            return false;
+        }
+        self.collected < self.wanted && count > self.collected && self.pages < MAX_PAGES
+    }
+
+    /// 翻页过程中发现的第一处 count 与实际条目不一致
+    pub fn anomaly(&self) -> Option<&str> {
+        self.anomaly.as_deref()
+    }
+
+    fn note_anomaly(&mut self, anomaly: String) {
+        self.anomaly.get_or_insert(anomaly);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pages_use_constant_size_and_derived_offset() {
+        let mut pager = Pager::new(25, 10);
+        assert_eq!((pager.page(), pager.start(), pager.page_size()), (1, 0, 10));
+        assert!(pager.record(25, 10));
+        assert_eq!((pager.page(), pager.start(), pager.page_size()), (2, 10, 10));
+        assert!(pager.record(25, 10));
+        assert_eq!((pager.page(), pager.start(), pager.page_size()), (3, 20, 10));
+        assert!(!pager.record(25, 5));
+        assert!(pager.anomaly().is_none());
+    }
+
+    #[test]
+    fn stops_once_wanted_entries_are_collected() {
+        let mut pager = Pager::new(10, 10);
+        assert!(!pager.record(50, 10));
+        assert!(pager.anomaly().is_none());
+    }
+
+    #[test]
+    fn inflated_count_with_empty_next_page_is_an_anomaly() {
+        let mut pager = Pager::new(50, 10);
+        assert!(pager.record(30, 10));
+        assert!(!pager.record(30, 0));
+        assert!(pager.anomaly().unwrap().contains("count 为 30"));
+    }
+
+    #[test]
+    fn zero_count_with_entries_is_an_anomaly() {
+        let mut pager = Pager::new(10, 10);
+        assert!(!pager.record(0, 3));
+        assert!(pager.anomaly().unwrap().contains("count 为 0"));
+    }
+
+    #[test]
+    fn count_below_collected_is_an_anomaly() {
+        let mut pager = Pager::new(50, 10);
+        assert!(!pager.record(4, 10));
+        assert!(pager.anomaly().unwrap().contains("小于实际获取到的 10 条"));
+    }
+
+    #[test]
+    fn empty_list_with_zero_count_is_normal() {
+        let mut pager = Pager::new(10, 10);
+        assert!(!pager.record(0, 0));
+        assert!(pager.anomaly().is_none());
+    }
+
+    #[test]
+    fn stops_at_max_pages() {
+        let mut pager = Pager::new(usize::MAX, 10);
+        for _ in 1..MAX_PAGES {
+            assert!(pager.record(i32::MAX, 10));
+        }
+        assert!(!pager.record(i32::MAX, 10));
+        assert_eq!(pager.page(), MAX_PAGES + 1);
+    }
+
+    #[test]
+    fn only_first_anomaly_is_kept() {
+        let mut pager = Pager::new(10, 10);
+        pager.note_anomaly("第一处".to_string());
+        pager.note_anomaly("第二处".to_string());
+        assert_eq!(pager.anomaly(), Some("第一处"));
+    }
+}
@@ -0,0 +1,33 @@
+//! 配置文件权限检查
+//!
+//! 明文配置中保存着 cookie，Unix 下同组或其他用户可读时存在泄露风险。
+//! Windows 的权限模型不同，相关检查直接跳过
+
+use std::{io, path::Path};
+
+/// 返回过宽的权限位（同组或其他用户有任意权限），权限合适或无法判断时返回 None
+#[cfg(unix)]
+pub fn too_open(path: &Path) -> Option<u32> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mode = std::fs::metadata(path).ok()?.permissions().mode() & 0o777;
+    (mode & 0o077 != 0).then_some(mode)
+}
+
+#[cfg(not(unix))]
+pub fn too_open(_path: &Path) -> Option<u32> {
+    None
+}
+
+/// 把文件权限收紧为仅所有者可读写（600）
+#[cfg(unix)]
+pub fn restrict(path: &Path) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+}
+
+#[cfg(not(unix))]
+pub fn restrict(_path: &Path) -> io::Result<()> {
+    Ok(())
+}
@@ -0,0 +1,66 @@
+//! 请求预览
+//!
+//! 把“构造请求”和“发送请求”拆开：先构造出完整的请求内容（URL、header、表单），
+//! 可以打印出来确认，确认无误后再据此发送
+
+use crate::{cookie, form::BodyEncoding};
+use reqwest::{Client, RequestBuilder};
+use std::fmt;
+
+/// 即将提交的 POST 请求
+#[derive(Debug, Clone)]
+pub struct RequestPreview {
+    pub url: String,
+    /// 除 Content-Type 外的 header，Content-Type 由编码方式决定
+    pub headers: Vec<(&'static str, String)>,
+    pub form: Vec<(String, String)>,
+    pub encoding: BodyEncoding,
+    /// JSON 编码时按数字发送的字段
+    pub numeric: Vec<String>,
+}
+
+impl RequestPreview {
+    /// 按预览的内容构造真正要发送的请求
+    pub fn build(&self, client: &Client) -> RequestBuilder {
+        let request = self
+            .headers
+            .iter()
+            .fold(client.post(&self.url), |request, (name, value)| request.header(*name, value));
+        self.encoding.attach(request, &self.form, &self.numeric_fields())
+    }
+
+    /// 实际提交的请求体
+    ///
+    /// 表单编码与 `.form()` 一致：按 UTF-8 逐字节百分号编码，空格编码为 `+`，与浏览器提交表单一致，
+    /// 中文、emoji 等多字节字符不会被截断或替换
+    pub fn encoded_body(&self) -> String {
+        self.encoding.encode(&self.form, &self.numeric_fields())
+    }
+
+    fn numeric_fields(&self) -> Vec<&str> {
+        self.numeric.iter().map(String::as_str).collect()
+    }
+}
+
+impl fmt::Display for RequestPreview {
+    /// 打印时 Cookie 只显示指纹，避免泄露
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "POST {}", self.url)?;
+        writeln!(f, "Header:")?;
+        writeln!(f, "  Content-Type: {}", self.encoding.content_type())?;
+        for (name, value) in &self.headers {
+            if name.eq_ignore_ascii_case("cookie") {
+                writeln!(f, "  {}: <已隐藏，指纹 {}>", name, cookie::fingerprint(value))?;
+            } else {
+                writeln!(f, "  {}: {}", name, value)?;
+            }
+        }
+        writeln!(f, "表单:")?;
+        for (name, value) in &self.form {
+            writeln!(f, "  {} = {:?}", name, value)?;
+        }
+        writeln!(f, "编码后的请求体:")?;
+        writeln!(f, "  {}", self.encoded_body())?;
+        Ok(())
+    }
+}
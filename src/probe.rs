@@ -0,0 +1,104 @@
+//! 接口可用性探测
+//!
+//! `probe` 命令逐个对活动接口发起最小化的请求，报告 HTTP 状态、是否返回合法 JSON 和业务 code，
+//! 用于快速定位是哪个接口下线或改动了。只读接口按正常参数查询；写接口只发送缺少必填参数的请求，
+//! 由服务端拒绝而不产生真实操作，`--dry-run` 时写接口完全不发送
+
+use crate::report::escape_markdown;
+use chrono::Local;
+use std::fmt;
+
+/// 单个接口的探测结果
+#[derive(Debug, Clone)]
+pub enum ProbeOutcome {
+    /// dry-run 下未发送的写接口
+    Skipped,
+    /// 请求未得到响应（网络错误、已达请求预算等）
+    Failed(String),
+    /// 收到了响应
+    Responded {
+        http_status: u16,
+        /// 响应体是否为合法 JSON
+        json: bool,
+        state: Option<i64>,
+        code: Option<i64>,
+        message: String,
+    },
+}
+
+impl ProbeOutcome {
+    /// 按 HTTP 状态和响应体整理探测结果
+    pub fn from_response(http_status: u16, body: &str) -> Self {
+        let Ok(value) = serde_json::from_str::<serde_json::Value>(body) else {
+            return ProbeOutcome::Responded { http_status, json: false, state: None, code: None, message: String::new() };
+        };
+        let field = |name: &str| value.get(name).and_then(|v| v.as_i64().or_else(|| v.as_str()?.parse().ok()));
+        ProbeOutcome::Responded {
+            http_status,
+            json: true,
+            state: field("state"),
+            code: field("code"),
+            message: value.get("message").and_then(|v| v.as_str()).unwrap_or_default().to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ProbeOutcome {
+    /// 一句话结论：接口能否正常响应
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProbeOutcome::Skipped => write!(f, "dry-run，未发送"),
+            ProbeOutcome::Failed(e) => write!(f, "无响应：{}", e),
+            ProbeOutcome::Responded { http_status, .. } if !(200..300).contains(http_status) => write!(f, "HTTP 错误"),
+            ProbeOutcome::Responded { json: false, .. } => write!(f, "返回内容不是 JSON"),
+            ProbeOutcome::Responded { state: None, .. } => write!(f, "JSON 缺少 state 字段，接口可能已改动"),
+            ProbeOutcome::Responded { .. } => write!(f, "可用"),
+        }
+    }
+}
+
+/// 一个接口的探测记录
+#[derive(Debug, Clone)]
+pub struct ProbeResult {
+    pub endpoint: &'static str,
+    /// 是否为写接口
+    pub write: bool,
+    pub outcome: ProbeOutcome,
+}
+
+/// 以 Markdown 表格渲染探测结果
+pub fn render(results: &[ProbeResult]) -> String {
+    let mut md = format!(
+        "# 115 接口探测\n\n生成时间：{}\n\n",
+        Local::now().format("%Y-%m-%d %H:%M:%S")
+    );
+    md.push_str("| 接口 | 类型 | HTTP 状态 | JSON | state | code | message | 结论 |\n");
+    md.push_str("| --- | --- | ---: | --- | ---: | ---: | --- | --- |\n");
+
+    let optional = |value: &Option<i64>| value.map(|v| v.to_string()).unwrap_or_else(|| "-".to_string());
+    for result in results {
+        let kind = if result.write { "写" } else { "只读" };
+        let (http_status, json, state, code, message) = match &result.outcome {
+            ProbeOutcome::Responded { http_status, json, state, code, message } => (
+                http_status.to_string(),
+                if *json { "是" } else { "否" }.to_string(),
+                optional(state),
+                optional(code),
+                escape_markdown(message),
+            ),
+            _ => ("-".to_string(), "-".to_string(), "-".to_string(), "-".to_string(), String::new()),
+        };
+        md.push_str(&format!(
+            "| {} | {} | {} | {} | {} | {} | {} | {} |\n",
+            result.endpoint,
+            kind,
+            http_status,
+            json,
+            state,
+            code,
+            message,
+            escape_markdown(&result.outcome.to_string()),
+        ));
+    }
+    md
+}
@@ -0,0 +1,444 @@
+//! 运行结果统计与报告
+
+use crate::run_id;
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fmt,
+    fs::{self, OpenOptions},
+    io::Write,
+    path::Path,
+};
+
+/// 许愿步骤的结果
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub enum WishOutcome {
+    /// 未执行许愿
+    #[default]
+    NotRun,
+    /// 许愿成功，附带愿望 ID
+    Success(String),
+    /// 今天已许愿，本次跳过，附带愿望 ID
+    AlreadyDone(String),
+    /// 许愿未成功
+    Failed,
+    /// 许愿过程出错
+    Error,
+}
+
+impl fmt::Display for WishOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WishOutcome::NotRun => write!(f, "未执行"),
+            WishOutcome::Success(id) => write!(f, "成功（ID: {}）", id),
+            WishOutcome::AlreadyDone(id) => write!(f, "今日已完成（ID: {}）", id),
+            WishOutcome::Failed => write!(f, "失败"),
+            WishOutcome::Error => write!(f, "出错"),
+        }
+    }
+}
+
+/// 单个账号本轮的处理结果
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AccountReport {
+    pub account_index: usize,
+    /// 账号备注名，来自账号配置
+    pub name: String,
+    /// 账号昵称，从愿望详情中获取
+    pub nickname: String,
+    pub wish: WishOutcome,
+    /// 助力成功数
+    pub aided: u32,
+    /// 采纳成功数
+    pub adopted: u32,
+    /// 失败的操作数
+    pub failed: u32,
+    /// 已采纳愿望的累计奖励（按承诺奖励 reward 计）
+    pub reward: i64,
+    /// 已采纳愿望的累计实际奖励（按 sj_reward 计）
+    pub actual_reward: i64,
+    /// 实际奖励少于承诺奖励的愿望数
+    pub shrunk_rewards: u32,
+    /// 触发风控的失败信息，只保留第一条
+    #[serde(skip)]
+    pub risk: Option<String>,
+    /// 已助力愿望的奖励，采纳成功后计入累计奖励
+    #[serde(skip)]
+    pub wish_rewards: HashMap<String, WishReward>,
+    /// 本轮每次许愿、助力、采纳的结果
+    #[serde(skip)]
+    pub actions: Vec<ActionRecord>,
+    /// 最近一次接口返回的失败信息，记录下一次失败的操作时取出
+    #[serde(skip)]
+    pub last_failure: Option<String>,
+}
+
+/// 单次许愿、助力或采纳的结果
+#[derive(Debug, Clone)]
+pub struct ActionRecord {
+    pub time: DateTime<Local>,
+    /// 操作类型：wish、aid、adopt
+    pub action: &'static str,
+    /// 愿望 ID，许愿失败时为空
+    pub wish_code: String,
+    pub success: bool,
+    /// 失败信息，成功时为空
+    pub message: String,
+    /// 采纳成功时为该愿望的承诺奖励，其余为 0
+    pub reward: i64,
+}
+
+/// 愿望的奖励
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct WishReward {
+    /// 承诺奖励，对应接口的 reward
+    pub promised: i64,
+    /// 实际奖励，对应接口的 sj_reward
+    pub actual: i64,
+}
+
+impl WishReward {
+    /// 实际奖励是否少于承诺奖励
+    pub fn is_shrunk(&self) -> bool {
+        self.actual < self.promised
+    }
+}
+
+impl AccountReport {
+    pub fn new(account_index: usize) -> Self {
+        Self {
+            account_index,
+            ..Default::default()
+        }
+    }
+
+    /// 用于展示的账号名，依次使用备注名、昵称、账号序号
+    pub fn display_name(&self) -> String {
+        if !self.name.is_empty() {
+            self.name.clone()
+        } else if !self.nickname.is_empty() {
+            self.nickname.clone()
+        } else {
+            format!("账号-{}", self.account_index + 1)
+        }
+    }
+
+    /// 本轮是否整体失败：有失败的操作且没有任何成功的操作
+    pub fn is_failed_round(&self) -> bool {
+        self.failed > 0
+            && !matches!(self.wish, WishOutcome::Success(_) | WishOutcome::AlreadyDone(_))
+            && self.aided == 0
+            && self.adopted == 0
+    }
+
+    /// 记录一次成功的操作
+    pub fn record_success(&mut self, action: &'static str, wish_code: &str, reward: i64) {
+        self.last_failure = None;
+        self.push_action(action, wish_code, true, String::new(), reward);
+    }
+
+    /// 记录一次失败的操作，未给出失败信息时使用最近一次接口返回的失败信息
+    pub fn record_failure(&mut self, action: &'static str, wish_code: &str, message: Option<String>) {
+        let message = message.or_else(|| self.last_failure.take()).unwrap_or_default();
+        self.last_failure = None;
+        self.push_action(action, wish_code, false, message, 0);
+    }
+
+    fn push_action(&mut self, action: &'static str, wish_code: &str, success: bool, message: String, reward: i64) {
+        self.actions.push(ActionRecord {
+            time: Local::now(),
+            action,
+            wish_code: wish_code.to_string(),
+            success,
+            message,
+            reward,
+        });
+    }
+
+    /// 记录一次采纳成功，并把该愿望的奖励计入累计奖励
+    pub fn record_adopted(&mut self, wish_id: &str) {
+        self.adopted += 1;
+        let reward = self.wish_rewards.get(wish_id).copied().unwrap_or_default();
+        self.reward += reward.promised;
+        self.actual_reward += reward.actual;
+        if reward.is_shrunk() {
+            self.shrunk_rewards += 1;
+        }
+    }
+}
+
+/// 把报告路径中的 `{date}` 替换为当天日期，`{run_id}` 替换为本次运行的 run_id
+pub fn resolve_report_path(template: &str) -> String {
+    template
+        .replace("{date}", &Local::now().format("%Y-%m-%d").to_string())
+        .replace("{run_id}", run_id::current())
+}
+
+/// 以 Markdown 表格写出各账号的处理结果
+pub fn write_markdown(path: &Path, reports: &[AccountReport]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, render_markdown(reports))?;
+    Ok(())
+}
+
+fn render_markdown(reports: &[AccountReport]) -> String {
+    let mut md = String::new();
+    md.push_str(&format!(
+        "# 115 许愿助手运行报告\n\n生成时间：{}\n\n运行 ID：{}\n\n",
+        Local::now().format("%Y-%m-%d %H:%M:%S"),
+        run_id::current()
+    ));
+    md.push_str("| 账号 | 昵称 | 许愿结果 | 助力数 | 采纳数 | 失败数 | 累计奖励 | 实际奖励 | 奖励缩水 |\n");
+    md.push_str("| --- | --- | --- | ---: | ---: | ---: | ---: | ---: | ---: |\n");
+
+    for report in reports {
+        md.push_str(&format!(
+            "| {} | {} | {} | {} | {} | {} | {} | {} | {} |\n",
+            report.account_index + 1,
+            escape_markdown(&report.display_name()),
+            escape_markdown(&report.wish.to_string()),
+            report.aided,
+            report.adopted,
+            report.failed,
+            report.reward,
+            report.actual_reward,
+            report.shrunk_rewards,
+        ));
+    }
+
+    md.push_str(&format!(
+        "| **合计** | | | {} | {} | {} | {} | {} | {} |\n",
+        reports.iter().map(|r| r.aided).sum::<u32>(),
+        reports.iter().map(|r| r.adopted).sum::<u32>(),
+        reports.iter().map(|r| r.failed).sum::<u32>(),
+        reports.iter().map(|r| r.reward).sum::<i64>(),
+        reports.iter().map(|r| r.actual_reward).sum::<i64>(),
+        reports.iter().map(|r| r.shrunk_rewards).sum::<u32>(),
+    ));
+
+    md
+}
+
+/// 成功率告警的配置，各项为百分比阈值，0 表示不检查该项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SuccessRateAlertConfig {
+    pub enabled: bool,
+    pub wish: f64,
+    pub aid: f64,
+    pub adopt: f64,
+}
+
+impl Default for SuccessRateAlertConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            wish: 50.0,
+            aid: 50.0,
+            adopt: 50.0,
+        }
+    }
+}
+
+/// 某类操作本轮的成功率
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OperationRate {
+    /// 操作类型：wish、aid、adopt
+    pub action: &'static str,
+    pub succeeded: usize,
+    pub attempts: usize,
+}
+
+impl OperationRate {
+    /// 成功百分比，没有尝试时返回 None
+    pub fn percent(&self) -> Option<f64> {
+        (self.attempts > 0).then(|| self.succeeded as f64 * 100.0 / self.attempts as f64)
+    }
+
+    /// 该类操作的阈值，0 表示不检查
+    fn threshold(&self, config: &SuccessRateAlertConfig) -> f64 {
+        match self.action {
+            "wish" => config.wish,
+            "aid" => config.aid,
+            _ => config.adopt,
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        action_label(self.action)
+    }
+}
+
+fn action_label(action: &str) -> &'static str {
+    match action {
+        "wish" => "许愿",
+        "aid" => "助力",
+        _ => "采纳",
+    }
+}
+
+impl fmt::Display for OperationRate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.percent() {
+            Some(percent) => write!(f, "{}成功率 {:.1}%（{}/{}）", self.label(), percent, self.succeeded, self.attempts),
+            None => write!(f, "{}无尝试", self.label()),
+        }
+    }
+}
+
+/// 按各账号记录的操作结果计算许愿、助力、采纳的成功率
+pub fn operation_rates(reports: &[AccountReport]) -> [OperationRate; 3] {
+    ["wish", "aid", "adopt"].map(|action| {
+        let records = reports.iter().flat_map(|r| &r.actions).filter(|a| a.action == action);
+        OperationRate {
+            action,
+            succeeded: records.clone().filter(|a| a.success).count(),
+            attempts: records.count(),
+        }
+    })
+}
+
+/// 低于阈值的成功率告警，没有低于阈值的项时返回空列表
+pub fn rate_alerts(rates: &[OperationRate], config: &SuccessRateAlertConfig) -> Vec<String> {
+    if !config.enabled {
+        return Vec::new();
+    }
+    rates
+        .iter()
+        .filter_map(|rate| {
+            let threshold = rate.threshold(config);
+            let percent = rate.percent()?;
+            (threshold > 0.0 && percent < threshold).then(|| format!("{}，低于阈值 {}%", rate, threshold))
+        })
+        .collect()
+}
+
+/// CSV 的表头
+const CSV_HEADER: [&str; 7] = ["date", "account", "wish_code", "action", "result", "message", "reward"];
+
+/// 把各账号每次操作的结果追加到 CSV 文件，文件不存在或为空时先写入表头
+///
+/// 新文件开头写入 UTF-8 BOM，Excel 直接打开时中文不会乱码
+pub fn append_csv(path: &Path, reports: &[AccountReport]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let is_new = fs::metadata(path).map(|meta| meta.len() == 0).unwrap_or(true);
+
+    let mut csv = String::new();
+    if is_new {
+        csv.push('\u{feff}');
+        push_csv_row(&mut csv, CSV_HEADER.map(String::from));
+    }
+    for report in reports {
+        for action in &report.actions {
+            push_csv_row(
+                &mut csv,
+                [
+                    action.time.format("%Y-%m-%d %H:%M:%S").to_string(),
+                    report.display_name(),
+                    action.wish_code.clone(),
+                    action.action.to_string(),
+                    if action.success { "success" } else { "failed" }.to_string(),
+                    action.message.clone(),
+                    action.reward.to_string(),
+                ],
+            );
+        }
+    }
+
+    OpenOptions::new().create(true).append(true).open(path)?.write_all(csv.as_bytes())?;
+    Ok(())
+}
+
+fn push_csv_row<const N: usize>(csv: &mut String, fields: [String; N]) {
+    let fields: Vec<String> = fields.iter().map(|field| escape_csv(field)).collect();
+    csv.push_str(&fields.join(","));
+    csv.push_str("\r\n");
+}
+
+/// 按 RFC 4180 转义 CSV 字段：含逗号、引号或换行时用引号包裹，引号写成两个引号
+fn escape_csv(field: &str) -> String {
+    if field.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// 生成各账号处理结果的简要汇总，用于推送通知
+pub fn render_summary(reports: &[AccountReport]) -> String {
+    let mut text = String::new();
+    for report in reports {
+        text.push_str(&format!(
+            "- {}：许愿{}，助力 {}，采纳 {}，失败 {}，奖励 {}\n",
+            report.display_name(),
+            report.wish,
+            report.aided,
+            report.adopted,
+            report.failed,
+            report.reward,
+        ));
+    }
+    text.push_str(&format!(
+        "合计：助力 {}，采纳 {}，失败 {}，奖励 {}（实际 {}）",
+        reports.iter().map(|r| r.aided).sum::<u32>(),
+        reports.iter().map(|r| r.adopted).sum::<u32>(),
+        reports.iter().map(|r| r.failed).sum::<u32>(),
+        reports.iter().map(|r| r.reward).sum::<i64>(),
+        reports.iter().map(|r| r.actual_reward).sum::<i64>(),
+    ));
+    text
+}
+
+/// 按账号分组整理本轮失败的操作和风控信息，没有任何失败时返回 None。
+/// 并发助力时各愿望的日志相互交错，运行结束后用它给出一份便于复盘的清单
+pub fn render_errors(reports: &[AccountReport]) -> Option<String> {
+    let mut text = String::new();
+    for report in reports {
+        let failures: Vec<&ActionRecord> = report.actions.iter().filter(|a| !a.success).collect();
+        if failures.is_empty() && report.risk.is_none() {
+            continue;
+        }
+        text.push_str(&format!("{}：\n", report.display_name()));
+        if let Some(risk) = &report.risk {
+            text.push_str(&format!("  触发风控: {}\n", risk));
+        }
+        for failure in failures {
+            let target = if failure.wish_code.is_empty() {
+                String::new()
+            } else {
+                format!(" {}", failure.wish_code)
+            };
+            let message = if failure.message.is_empty() { "未记录失败信息" } else { &failure.message };
+            text.push_str(&format!(
+                "  [{}] {}{}: {}\n",
+                failure.time.format("%H:%M:%S"),
+                action_label(failure.action),
+                target,
+                message
+            ));
+        }
+    }
+    (!text.is_empty()).then(|| text.trim_end().to_string())
+}
+
+/// 转义 Markdown 表格单元格中的特殊字符，换行替换为空格
+pub fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' | '|' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '#' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\r' | '\n' => escaped.push(' '),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,133 @@
+//! 按错误类别的重试策略
+//!
+//! 网络错误多为临时问题，可以较快重试；限流应当退避后再试；服务端 5xx 错误少量重试即可。
+//! 业务层面的失败（如内容违规、cookie 失效）重试也不会成功，不在这里重试
+
+use crate::http;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::time::Duration;
+
+/// 网络错误的默认策略
+const NETWORK: RetryPolicy = RetryPolicy { times: 3, backoff: 2 };
+/// 限流的默认策略
+const RATE_LIMIT: RetryPolicy = RetryPolicy { times: 3, backoff: 5 };
+/// 服务端错误的默认策略
+const SERVER_ERROR: RetryPolicy = RetryPolicy { times: 1, backoff: 5 };
+
+/// 单类错误的重试策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetryPolicy {
+    /// 最大重试次数，0 表示不重试
+    pub times: u32,
+    /// 指数退避的基础等待时间（秒），第 n 次重试等待 backoff * 2^(n-1) 秒
+    pub backoff: u64,
+}
+
+impl RetryPolicy {
+    /// 第 attempt 次重试（从 0 开始计）前的等待时间
+    pub fn delay(&self, attempt: u32) -> Duration {
+        http::backoff_delay(Duration::from_secs(self.backoff), attempt)
+    }
+}
+
+/// 配置中写出的策略，未写的字段取该类错误的默认值
+#[derive(Deserialize)]
+struct PartialPolicy {
+    #[serde(default)]
+    times: Option<u32>,
+    #[serde(default)]
+    backoff: Option<u64>,
+}
+
+impl PartialPolicy {
+    fn or(self, default: RetryPolicy) -> RetryPolicy {
+        RetryPolicy {
+            times: self.times.unwrap_or(default.times),
+            backoff: self.backoff.unwrap_or(default.backoff),
+        }
+    }
+}
+
+fn network<'de, D: Deserializer<'de>>(deserializer: D) -> Result<RetryPolicy, D::Error> {
+    PartialPolicy::deserialize(deserializer).map(|policy| policy.or(NETWORK))
+}
+
+fn rate_limit<'de, D: Deserializer<'de>>(deserializer: D) -> Result<RetryPolicy, D::Error> {
+    PartialPolicy::deserialize(deserializer).map(|policy| policy.or(RATE_LIMIT))
+}
+
+fn server_error<'de, D: Deserializer<'de>>(deserializer: D) -> Result<RetryPolicy, D::Error> {
+    PartialPolicy::deserialize(deserializer).map(|policy| policy.or(SERVER_ERROR))
+}
+
+/// 各类错误的重试策略，每类只需写出要修改的字段
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryConfig {
+    /// 超时、连接中断等临时网络错误；DNS、TLS 错误属于环境问题，始终不重试
+    #[serde(deserialize_with = "network")]
+    pub network: RetryPolicy,
+    /// HTTP 429 限流，响应带 Retry-After 时优先按它等待
+    #[serde(deserialize_with = "rate_limit")]
+    pub rate_limit: RetryPolicy,
+    /// HTTP 5xx 服务端错误
+    #[serde(deserialize_with = "server_error")]
+    pub server_error: RetryPolicy,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            network: NETWORK,
+            rate_limit: RATE_LIMIT,
+            server_error: SERVER_ERROR,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(yaml: &str) -> RetryConfig {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn missing_classes_use_defaults() {
+        let config = parse("{}");
+        assert_eq!(config.network, NETWORK);
+        assert_eq!(config.rate_limit, RATE_LIMIT);
+        assert_eq!(config.server_error, SERVER_ERROR);
+    }
+
+    #[test]
+    fn missing_fields_use_the_class_default() {
+        let config = parse("network: { times: 5 }\nrate_limit: { backoff: 9 }\nserver_error: {}");
+        assert_eq!(config.network, RetryPolicy { times: 5, backoff: NETWORK.backoff });
+        assert_eq!(config.rate_limit, RetryPolicy { times: RATE_LIMIT.times, backoff: 9 });
+        assert_eq!(config.server_error, SERVER_ERROR);
+    }
+
+    #[test]
+    fn zero_times_disables_retry() {
+        let config = parse("server_error: { times: 0 }");
+        assert_eq!(config.server_error.times, 0);
+    }
+
+    #[test]
+    fn delay_backs_off_exponentially() {
+        let policy = RetryPolicy { times: 3, backoff: 2 };
+        assert_eq!(policy.delay(0), Duration::from_secs(2));
+        assert_eq!(policy.delay(1), Duration::from_secs(4));
+        assert_eq!(policy.delay(2), Duration::from_secs(8));
+    }
+
+    #[test]
+    fn delay_is_capped() {
+        let policy = RetryPolicy { times: 3, backoff: 5 };
+        assert_eq!(policy.delay(30), Duration::from_secs(600));
+        assert_eq!(policy.delay(u32::MAX), Duration::from_secs(600));
+        assert_eq!(RetryPolicy { times: 1, backoff: 0 }.delay(3), Duration::ZERO);
+    }
+}
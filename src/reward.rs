@@ -0,0 +1,91 @@
+//! 许愿奖励空间（rewardSpace）的取值策略
+//!
+//! - fixed：固定值
+//! - random：在 [min, max] 区间内随机，多账号的奖励值不雷同
+//! - dynamic：按账号网盘剩余空间（GB）取值，不超过 max，避免承诺超过账号实际可提供的空间
+
+use anyhow::{bail, Result};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// 默认的奖励空间
+const DEFAULT_REWARD_SPACE: u32 = 5;
+
+/// 奖励空间的取值策略
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RewardSpaceStrategy {
+    #[default]
+    Fixed,
+    Random,
+    Dynamic,
+}
+
+impl fmt::Display for RewardSpaceStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RewardSpaceStrategy::Fixed => write!(f, "fixed"),
+            RewardSpaceStrategy::Random => write!(f, "random"),
+            RewardSpaceStrategy::Dynamic => write!(f, "dynamic"),
+        }
+    }
+}
+
+/// 奖励空间的配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RewardSpaceConfig {
+    pub strategy: RewardSpaceStrategy,
+    /// fixed 策略使用的值
+    pub value: u32,
+    /// random、dynamic 策略的下界
+    pub min: u32,
+    /// random、dynamic 策略的上界
+    pub max: u32,
+}
+
+impl Default for RewardSpaceConfig {
+    fn default() -> Self {
+        Self {
+            strategy: RewardSpaceStrategy::Fixed,
+            value: DEFAULT_REWARD_SPACE,
+            min: 1,
+            max: DEFAULT_REWARD_SPACE,
+        }
+    }
+}
+
+impl RewardSpaceConfig {
+    /// 校验当前策略用到的取值是否合法
+    pub fn validate(&self) -> Result<()> {
+        match self.strategy {
+            RewardSpaceStrategy::Fixed if self.value == 0 => bail!("reward_space.value 必须大于 0"),
+            RewardSpaceStrategy::Fixed => Ok(()),
+            RewardSpaceStrategy::Random | RewardSpaceStrategy::Dynamic => {
+                if self.min == 0 {
+                    bail!("reward_space.min 必须大于 0");
+                }
+                if self.min > self.max {
+                    bail!("reward_space.min（{}）不能大于 max（{}）", self.min, self.max);
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// 按策略取本次许愿的奖励空间
+    ///
+    /// available 为账号剩余空间（GB），仅 dynamic 策略使用；未能获取时取下界。
+    /// 剩余空间低于下界时按剩余空间取值，至少为 1
+    pub fn pick(&self, available: Option<u64>) -> u32 {
+        match self.strategy {
+            RewardSpaceStrategy::Fixed => self.value,
+            RewardSpaceStrategy::Random => rand::thread_rng().gen_range(self.min..=self.max),
+            RewardSpaceStrategy::Dynamic => match available {
+                Some(available) => u32::try_from(available).unwrap_or(u32::MAX).min(self.max).max(1),
+                None => self.min,
+            },
+        }
+    }
+}
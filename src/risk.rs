@@ -0,0 +1,39 @@
+//! 风控类、额度类、活动维护类失败的识别
+//!
+//! 115 触发风控时通常返回 HTTP 403/429，或在业务字段的 message 中提示操作频繁、需要验证等。
+//! 115 没有公开查询今日剩余助力次数的接口，助力额度用完只能从助力失败的 message 中识别
+
+/// 失败信息中表示触发风控的关键字
+const RISK_KEYWORDS: [&str; 10] = [
+    "风控",
+    "频繁",
+    "过快",
+    "验证码",
+    "安全验证",
+    "异常操作",
+    "账号异常",
+    "封禁",
+    "403 Forbidden",
+    "429 Too Many Requests",
+];
+
+/// 失败信息是否表明触发了风控
+pub fn is_risk_message(message: &str) -> bool {
+    RISK_KEYWORDS.iter().any(|keyword| message.contains(keyword))
+}
+
+/// 失败信息中表示今日助力额度已用完的关键字
+const QUOTA_KEYWORDS: [&str; 5] = ["次数已用完", "次数用完", "已达上限", "达到上限", "今日助力已满"];
+
+/// 失败信息是否表明今日助力额度已用完
+pub fn is_quota_message(message: &str) -> bool {
+    QUOTA_KEYWORDS.iter().any(|keyword| message.contains(keyword))
+}
+
+/// 失败信息中表示活动维护或暂停的关键字
+const MAINTENANCE_KEYWORDS: [&str; 6] = ["维护中", "系统维护", "活动维护", "已暂停", "暂停服务", "暂停活动"];
+
+/// 失败信息是否表明活动正在维护或已暂停，此时继续请求只会反复失败
+pub fn is_maintenance_message(message: &str) -> bool {
+    MAINTENANCE_KEYWORDS.iter().any(|keyword| message.contains(keyword))
+}
@@ -0,0 +1,15 @@
+//! 本次运行的唯一标识
+//!
+//! 启动时生成一次，由时间戳和随机后缀组成，如 `20250101-080000-3f2a`。
+//! 日志的每一行、报告文件名、诊断包和通知都带上它，排查时按 run_id 即可过滤出同一次运行的全部记录，
+//! 多个实例或多轮运行的产出也能据此区分来源
+
+use chrono::Local;
+use std::sync::OnceLock;
+
+static RUN_ID: OnceLock<String> = OnceLock::new();
+
+/// 本次运行的 run_id，首次调用时生成
+pub fn current() -> &'static str {
+    RUN_ID.get_or_init(|| format!("{}-{:04x}", Local::now().format("%Y%m%d-%H%M%S"), rand::random::<u16>()))
+}
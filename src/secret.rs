@@ -0,0 +1,79 @@
+//! 配置中的敏感字段
+//!
+//! cookie 等字段包在 `Secret` 中，Debug 和序列化都只输出占位符，报告、诊断包、`--show-defaults`
+//! 等任何打印或导出配置的地方都不会带出明文；只有显式调用 `expose_secret()` 才能拿到明文，
+//! 仅在构造请求、校验 cookie 时使用。
+//!
+//! 写回配置文件、合并账号 overrides 确实需要序列化出明文，这类序列化放在 `exposed` 中进行
+
+use secrecy::{ExposeSecret, SecretString};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{cell::Cell, fmt};
+
+/// 序列化时代替明文输出的占位符
+const REDACTED: &str = "<已隐藏>";
+
+thread_local! {
+    static EXPOSED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// 在 f 执行期间序列化 `Secret` 时输出明文，用于写回配置文件等必须保留明文的场景
+pub fn exposed<T>(f: impl FnOnce() -> T) -> T {
+    let previous = EXPOSED.replace(true);
+    let result = f();
+    EXPOSED.set(previous);
+    result
+}
+
+/// 打印和序列化时自动隐藏的字符串
+#[derive(Clone, Default)]
+pub struct Secret(SecretString);
+
+impl Secret {
+    pub fn new(value: String) -> Self {
+        Self(SecretString::from(value))
+    }
+
+    /// 取出明文，只在构造请求等确实需要的地方调用
+    pub fn expose_secret(&self) -> &str {
+        self.0.expose_secret()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.expose_secret().is_empty()
+    }
+}
+
+impl From<String> for Secret {
+    fn from(value: String) -> Self {
+        Self::new(value)
+    }
+}
+
+impl PartialEq for Secret {
+    fn eq(&self, other: &Self) -> bool {
+        self.expose_secret() == other.expose_secret()
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+impl Serialize for Secret {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if EXPOSED.get() {
+            serializer.serialize_str(self.expose_secret())
+        } else {
+            serializer.serialize_str(REDACTED)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Secret {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Self::new)
+    }
+}
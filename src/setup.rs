@@ -0,0 +1,117 @@
+//! 交互式配置向导
+//!
+//! 在终端里逐步提示用户粘贴助愿账号和许愿账号的 cookie，校验后写入配置文件
+
+use crate::{account::AccountConfig, config_file_path, cookie, secret::Secret, AppConfig};
+use anyhow::Result;
+use std::{
+    io::{self, BufRead, Write},
+    path::Path,
+};
+
+/// 运行配置向导
+pub fn run() -> Result<()> {
+    println!("===== 115 许愿助手配置向导 =====");
+    println!("cookie 可以从浏览器开发者工具中复制，支持多行粘贴，粘贴完成后输入一个空行结束。");
+    println!();
+
+    let stdin = io::stdin();
+    let mut input = stdin.lock();
+
+    let mut config = if Path::new(config_file_path()).exists() {
+        println!("检测到已有配置文件 {}，将只更新其中的 cookie，其他配置保持不变。", config_file_path());
+        AppConfig::read_from_file(config_file_path())?
+    } else {
+        AppConfig::default()
+    };
+
+    config.aid_cookie = loop {
+        match prompt_cookie(&mut input, "请粘贴助愿账号的 cookie:")? {
+            Some(cookie) => break Secret::new(cookie),
+            None => println!("助愿账号的 cookie 不能为空，请重新输入。"),
+        }
+    };
+
+    let mut wish_cookies = Vec::new();
+    loop {
+        let prompt = format!(
+            "请粘贴第 {} 个许愿账号的 cookie（直接输入空行结束）:",
+            wish_cookies.len() + 1
+        );
+        match prompt_cookie(&mut input, &prompt)? {
+            Some(cookie) => wish_cookies.push(AccountConfig::new(cookie)),
+            None if wish_cookies.is_empty() => println!("至少需要一个许愿账号。"),
+            None => break,
+        }
+    }
+    config.wish_cookies = wish_cookies;
+
+    config.save_to_file(config_file_path())?;
+    println!();
+    println!(
+        "配置已写入 {}，共 {} 个许愿账号。现在可以直接运行程序了。",
+        config_file_path(),
+        config.wish_cookies.len()
+    );
+
+    Ok(())
+}
+
+/// 提示并读取一个 cookie，输入不合格时重新提示；直接输入空行返回 None
+fn prompt_cookie(input: &mut impl BufRead, prompt: &str) -> Result<Option<String>> {
+    loop {
+        println!("{}", prompt);
+        io::stdout().flush()?;
+
+        let cookie = read_multiline(input)?;
+        if cookie.is_empty() {
+            return Ok(None);
+        }
+
+        let missing = cookie::missing_fields(&cookie);
+        if missing.is_empty() {
+            return Ok(Some(cookie));
+        }
+
+        println!("cookie 中缺少必需字段: {}，请确认复制完整后重新粘贴。", missing.join(", "));
+    }
+}
+
+/// 读取多行输入直到空行，把各行拼接成一个 cookie 字符串
+///
+/// 粘贴时被折成多行的 cookie 会被重新拼接：上一行以分号结尾或下一行是新的
+/// `name=value` 片段时用 `; ` 连接，否则视为同一个值被折行而直接拼接
+fn read_multiline(input: &mut impl BufRead) -> Result<String> {
+    let mut cookie = String::new();
+
+    loop {
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            break;
+        }
+
+        let line = line.trim();
+        if line.is_empty() {
+            break;
+        }
+
+        if !cookie.is_empty() {
+            if cookie.ends_with(';') {
+                cookie.push(' ');
+            } else if starts_new_pair(line) {
+                cookie.push_str("; ");
+            }
+        }
+        cookie.push_str(line);
+    }
+
+    Ok(cookie.trim().trim_end_matches(';').to_string())
+}
+
+/// 判断一行是否以新的 `name=value` 片段开头
+fn starts_new_pair(line: &str) -> bool {
+    line.split(';')
+        .next()
+        .and_then(|first| first.split_once('='))
+        .is_some_and(|(name, _)| !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'))
+}
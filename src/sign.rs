@@ -0,0 +1,31 @@
+//! 请求签名
+//!
+//! 115 的活动接口目前不需要签名参数。为应对接口日后要求加签，所有请求在发送前都会经过
+//! [`RequestSigner::sign`]，加签时只需实现新的签名器并在 [`default_signer`] 中替换，
+//! 无需改动各接口的请求构造代码
+
+use anyhow::Result;
+use reqwest::Request;
+
+/// 为请求附加签名参数
+pub trait RequestSigner: Send + Sync {
+    /// 就地修改请求（追加 query 参数、header 或表单字段），签名失败时返回错误，请求不会发出
+    ///
+    /// cookie 为本次请求使用的账号 cookie，供依赖账号信息的签名算法使用
+    fn sign(&self, request: &mut Request, cookie: &str) -> Result<()>;
+}
+
+/// 不加签名，当前所有接口使用
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoSign;
+
+impl RequestSigner for NoSign {
+    fn sign(&self, _request: &mut Request, _cookie: &str) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// 当前使用的签名器
+pub fn default_signer() -> Box<dyn RequestSigner> {
+    Box::new(NoSign)
+}
@@ -0,0 +1,76 @@
+//! 愿望状态巡检
+//!
+//! `status` 命令只调用只读接口，汇总各账号现有愿望的审核状态、助力情况和奖励，
+//! 便于在正式运行前先看清全局状态
+
+use crate::{audit::AuditState, report::escape_markdown, timestamp};
+use chrono::Local;
+
+/// 单个愿望的当前状态
+#[derive(Debug, Clone, Default)]
+pub struct WishStatus {
+    pub code: String,
+    /// 愿望内容，详情获取失败时为空
+    pub content: String,
+    /// 审核状态，详情获取失败时为 None
+    pub audit_status: Option<i32>,
+    /// 愿望状态的原始值，详情获取失败时为 None
+    pub status: Option<i32>,
+    /// 助力数
+    pub aid_num: i32,
+    /// 助力记录 ID，没有助力时为 0
+    pub aid: i64,
+    pub reward: i64,
+    pub actual_reward: i64,
+    /// 最后编辑时间的时间戳，详情获取失败时为 None
+    pub edit_time: Option<i64>,
+    /// 今天是否已由本程序采纳
+    pub adopted: bool,
+}
+
+/// 以 Markdown 表格渲染各账号的愿望状态，获取失败的账号单独列出原因
+pub fn render(accounts: &[(String, Result<Vec<WishStatus>, String>)]) -> String {
+    let mut md = format!(
+        "# 115 愿望状态\n\n生成时间：{}\n\n",
+        Local::now().format("%Y-%m-%d %H:%M:%S")
+    );
+    md.push_str("| 账号 | 愿望 | 内容 | 审核 | 状态 | 助力数 | 助力 ID | 奖励 | 实际奖励 | 编辑时间 | 今日已采纳 |\n");
+    md.push_str("| --- | --- | --- | --- | ---: | ---: | --- | ---: | ---: | --- | --- |\n");
+
+    let mut failures = Vec::new();
+    for (name, result) in accounts {
+        let wishes = match result {
+            Ok(wishes) => wishes,
+            Err(e) => {
+                failures.push(format!("- {}：{}", name, e));
+                continue;
+            }
+        };
+        if wishes.is_empty() {
+            md.push_str(&format!("| {} | - | 没有愿望 | | | | | | | | |\n", escape_markdown(name)));
+        }
+        for wish in wishes {
+            md.push_str(&format!(
+                "| {} | {} | {} | {} | {} | {} | {} | {} | {} | {} | {} |\n",
+                escape_markdown(name),
+                escape_markdown(&wish.code),
+                escape_markdown(&wish.content),
+                wish.audit_status.map(|s| AuditState::from_status(s).to_string()).unwrap_or_else(|| "未知".to_string()),
+                wish.status.map(|s| s.to_string()).unwrap_or_default(),
+                wish.aid_num,
+                if wish.aid == 0 { "-".to_string() } else { wish.aid.to_string() },
+                wish.reward,
+                wish.actual_reward,
+                wish.edit_time.map(timestamp::format_local).unwrap_or_else(|| "-".to_string()),
+                if wish.adopted { "是" } else { "否" },
+            ));
+        }
+    }
+
+    if !failures.is_empty() {
+        md.push_str("\n获取失败的账号：\n\n");
+        md.push_str(&failures.join("\n"));
+        md.push('\n');
+    }
+    md
+}
@@ -0,0 +1,55 @@
+//! 把日志同时写入本机 syslog 的 log4rs appender
+
+use anyhow::{anyhow, Result};
+use log::{Level, Record};
+use log4rs::append::Append;
+use std::{fmt, str::FromStr, sync::Mutex};
+use syslog::{Facility, Formatter3164, Logger, LoggerBackend};
+
+/// syslog 中显示的程序名
+const PROCESS_NAME: &str = "wish_115";
+
+/// 通过本机 syslog 套接字写日志，日志级别映射为对应的 syslog severity
+pub struct SyslogAppender {
+    logger: Mutex<Logger<LoggerBackend, Formatter3164>>,
+}
+
+impl SyslogAppender {
+    /// 连接本机 syslog，facility 使用 syslog 的名称，如 user、daemon、local0
+    pub fn new(facility: &str) -> Result<Self> {
+        let facility = Facility::from_str(facility).map_err(|()| anyhow!("无效的 syslog facility: {}", facility))?;
+        let formatter = Formatter3164 {
+            facility,
+            hostname: None,
+            process: PROCESS_NAME.to_string(),
+            pid: std::process::id(),
+        };
+        let logger = syslog::unix(formatter).map_err(|e| anyhow!("连接 syslog 失败: {}", e))?;
+
+        Ok(Self {
+            logger: Mutex::new(logger),
+        })
+    }
+}
+
+impl fmt::Debug for SyslogAppender {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SyslogAppender").finish_non_exhaustive()
+    }
+}
+
+impl Append for SyslogAppender {
+    fn append(&self, record: &Record) -> Result<()> {
+        let message = record.args().to_string();
+        let mut logger = self.logger.lock().unwrap_or_else(|e| e.into_inner());
+        let result = match record.level() {
+            Level::Error => logger.err(message),
+            Level::Warn => logger.warning(message),
+            Level::Info => logger.info(message),
+            Level::Debug | Level::Trace => logger.debug(message),
+        };
+        result.map_err(|e| anyhow!("写入 syslog 失败: {}", e))
+    }
+
+    fn flush(&self) {}
+}
@@ -0,0 +1,122 @@
+//! 全局请求速率平滑
+//!
+//! 所有账号的请求共用一个令牌桶：桶满时允许短时间内连续发出 capacity 个请求，
+//! 之后按 refill_per_minute 的速率补充。桶的状态在运行结束时保存到状态目录，
+//! 下次运行时按间隔时间补充后继续使用，多次运行叠加的请求速率也不会超过设定值
+
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::PathBuf,
+    sync::Mutex,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tracing::warn;
+
+/// 令牌桶状态文件名
+const STATE_FILE: &str = "throttle.json";
+
+/// 全局速率平滑的配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThrottleConfig {
+    pub enabled: bool,
+    /// 桶的容量，即允许连续发出的最多请求数
+    pub capacity: u32,
+    /// 每分钟补充的令牌数，即长期平均的每分钟请求数
+    pub refill_per_minute: u32,
+}
+
+impl Default for ThrottleConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            capacity: 20,
+            refill_per_minute: 30,
+        }
+    }
+}
+
+/// 持久化的令牌桶状态
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct BucketState {
+    /// 剩余令牌数，为负表示已预约的请求
+    tokens: f64,
+    /// 上次更新时间（Unix 秒）
+    updated_at: f64,
+}
+
+/// 跨账号、跨运行共用的令牌桶
+pub struct Throttle {
+    /// 未启用时为 None
+    config: Option<ThrottleConfig>,
+    path: PathBuf,
+    state: Mutex<BucketState>,
+}
+
+impl Throttle {
+    /// 创建令牌桶，启用时读取上次保存的状态，没有保存的状态时从满桶开始
+    pub fn load(state_dir: &str, config: &ThrottleConfig) -> Self {
+        let path = PathBuf::from(state_dir).join(STATE_FILE);
+        let enabled = config.enabled && config.capacity > 0 && config.refill_per_minute > 0;
+        let full = BucketState {
+            tokens: f64::from(config.capacity),
+            updated_at: now(),
+        };
+        let state = if enabled {
+            fs::read_to_string(&path)
+                .ok()
+                .and_then(|text| serde_json::from_str(&text).ok())
+                .unwrap_or(full)
+        } else {
+            full
+        };
+
+        Self {
+            config: enabled.then(|| config.clone()),
+            path,
+            state: Mutex::new(state),
+        }
+    }
+
+    /// 取出一个令牌，返回发送请求前需要等待的时间
+    pub fn acquire(&self) -> Duration {
+        let Some(config) = &self.config else {
+            return Duration::ZERO;
+        };
+
+        let rate = f64::from(config.refill_per_minute) / 60.0;
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let now = now();
+        let elapsed = (now - state.updated_at).max(0.0);
+        state.tokens = (state.tokens + elapsed * rate).min(f64::from(config.capacity)) - 1.0;
+        state.updated_at = now;
+
+        if state.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-state.tokens / rate)
+        }
+    }
+
+    /// 保存令牌桶状态，未启用时不保存
+    pub fn save(&self) {
+        if self.config.is_none() {
+            return;
+        }
+
+        let state = *self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let result = self
+            .path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|()| fs::write(&self.path, serde_json::to_string(&state).unwrap_or_default()));
+        if let Err(e) = result {
+            warn!("保存令牌桶状态到 {} 失败: {}", self.path.display(), e);
+        }
+    }
+}
+
+fn now() -> f64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs_f64()).unwrap_or_default()
+}
@@ -0,0 +1,40 @@
+//! 115 接口返回的时间戳
+//!
+//! 115 的时间戳通常是 Unix 秒，个别接口返回毫秒，按数值大小区分。
+//! "今天"按活动所在的北京时间（UTC+8）判断，与运行环境的时区无关
+
+use chrono::{DateTime, FixedOffset, Local, TimeZone, Utc};
+
+/// 小于该值按秒处理，否则按毫秒处理（1e11 秒约为 5138 年，毫秒约为 1973 年）
+const MILLIS_THRESHOLD: i64 = 100_000_000_000;
+
+/// 活动所在时区（北京时间）
+fn activity_offset() -> FixedOffset {
+    FixedOffset::east_opt(8 * 3600).expect("固定的时区偏移有效")
+}
+
+/// 把时间戳转为 UTC 时间，0、负数或超出范围时返回 None
+pub fn to_datetime(timestamp: i64) -> Option<DateTime<Utc>> {
+    if timestamp <= 0 {
+        return None;
+    }
+    if timestamp < MILLIS_THRESHOLD {
+        Utc.timestamp_opt(timestamp, 0).single()
+    } else {
+        Utc.timestamp_millis_opt(timestamp).single()
+    }
+}
+
+/// 以本地时间展示，无效时间戳显示为 "-"
+pub fn format_local(timestamp: i64) -> String {
+    to_datetime(timestamp)
+        .map(|time| time.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_else(|| "-".to_string())
+}
+
+/// 时间戳是否在活动时区的今天，无效时间戳返回 false
+pub fn is_today(timestamp: i64) -> bool {
+    let offset = activity_offset();
+    to_datetime(timestamp)
+        .is_some_and(|time| time.with_timezone(&offset).date_naive() == Utc::now().with_timezone(&offset).date_naive())
+}
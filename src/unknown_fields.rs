@@ -0,0 +1,69 @@
+//! 配置文件中未知字段的检测
+//!
+//! serde 默认静默忽略未知字段，拼错的字段名不会生效也不会报错。
+//! 这里在加载配置时找出所有未被识别的字段，并按编辑距离给出最接近的正确字段名
+
+use serde::{
+    de::{self, DeserializeOwned, Visitor},
+    forward_to_deserialize_any, Deserialize, Deserializer,
+};
+
+/// 返回配置文本中未被识别的字段路径，如 `health_check.enable`
+///
+/// 配置文本无法解析时返回空列表，解析错误由正式加载时报告
+pub fn find<T: DeserializeOwned>(yaml: &str) -> Vec<String> {
+    let Ok(value) = serde_yaml::from_str::<serde_yaml::Value>(yaml) else {
+        return Vec::new();
+    };
+
+    let mut unknown = Vec::new();
+    let _: Result<T, _> = serde_ignored::deserialize(value, |path| unknown.push(path.to_string()));
+    unknown
+}
+
+/// 从候选字段名中找出与 name 最接近的一个，差距过大时返回 None
+pub fn suggest<'a>(name: &str, candidates: &[&'a str]) -> Option<&'a str> {
+    let max_distance = (name.chars().count() / 3).max(2);
+    candidates
+        .iter()
+        .map(|candidate| (strsim::levenshtein(name, candidate), *candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// 读取结构体的全部字段名
+pub fn struct_fields<'de, T: Deserialize<'de>>() -> &'static [&'static str] {
+    let mut recorder = FieldRecorder { fields: &[] };
+    let _ = T::deserialize(&mut recorder);
+    recorder.fields
+}
+
+/// 只用来截获 `deserialize_struct` 收到的字段列表，不产出任何值
+struct FieldRecorder {
+    fields: &'static [&'static str],
+}
+
+impl<'de> Deserializer<'de> for &mut FieldRecorder {
+    type Error = de::value::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(de::Error::custom("仅用于读取字段名"))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.fields = fields;
+        Err(de::Error::custom("仅用于读取字段名"))
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map enum identifier ignored_any
+    }
+}
@@ -0,0 +1,60 @@
+//! 许愿账号的 User-Agent 池
+//!
+//! 所有账号使用同一个 User-Agent 不像真实的多台设备。启用后每轮开始时为每个许愿账号
+//! 从池中分配一个，同一账号在本轮内始终使用同一个，账号数不超过池大小时各账号互不相同
+
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+
+/// 内置的常见桌面浏览器 User-Agent
+const BUILTIN_POOL: &[&str] = &[
+    "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/133.0.0.0 Safari/537.36",
+    "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/132.0.0.0 Safari/537.36",
+    "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/133.0.0.0 Safari/537.36 Edg/133.0.0.0",
+    "Mozilla/5.0 (Windows NT 10.0; Win64; x64; rv:135.0) Gecko/20100101 Firefox/135.0",
+    "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/133.0.0.0 Safari/537.36",
+    "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/18.3 Safari/605.1.15",
+    "Mozilla/5.0 (Macintosh; Intel Mac OS X 10.15; rv:135.0) Gecko/20100101 Firefox/135.0",
+    "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/133.0.0.0 Safari/537.36",
+];
+
+/// User-Agent 池的配置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UserAgentConfig {
+    /// 是否为各许愿账号随机分配 User-Agent，关闭时所有账号使用同一个
+    pub random: bool,
+    /// 候选 User-Agent，不填则使用内置的常见浏览器列表
+    pub pool: Vec<String>,
+}
+
+impl UserAgentConfig {
+    /// 为 `count` 个账号分配 User-Agent，关闭时都使用 `default`。
+    /// 池打乱后依次分配，账号数超过池大小时循环使用
+    pub fn assign(&self, count: usize, default: &str) -> Vec<String> {
+        if !self.random {
+            return vec![default.to_string(); count];
+        }
+
+        let mut pool: Vec<String> = self
+            .pool
+            .iter()
+            .map(|ua| ua.trim())
+            .filter(|ua| !ua.is_empty())
+            .map(str::to_string)
+            .collect();
+        if pool.is_empty() {
+            pool = BUILTIN_POOL.iter().map(|ua| ua.to_string()).collect();
+        }
+        if count > pool.len() {
+            tracing::warn!(
+                "许愿账号数（{}）多于 User-Agent 池大小（{}），部分账号会使用相同的 User-Agent",
+                count,
+                pool.len()
+            );
+        }
+
+        pool.shuffle(&mut rand::thread_rng());
+        pool.iter().cycle().take(count).cloned().collect()
+    }
+}
@@ -0,0 +1,299 @@
+//! 接口响应的录制与回放（VCR 风格）
+//!
+//! 录制模式下正常发送请求，并把每次请求和对应的响应追加写入 cassette 文件；
+//! 回放模式下不发送真实请求，按方法、URL 和请求体从 cassette 中找出录制的响应返回。
+//! 这样无需真实账号和会过期的 cookie 也能复现一次完整的处理流程。
+//!
+//! 录制的只有响应内容，请求中的 Cookie 不会写入文件；但响应里可能含有账号信息，
+//! cassette 文件不要提交到公开仓库
+
+use tracing::{info, warn};
+use reqwest::{Request, Response};
+use serde::{Deserialize, Serialize};
+use std::{fs, path::PathBuf, sync::Mutex};
+
+/// 录制时不保存的响应 header：body 已被解压，原有的编码和长度不再适用
+const SKIPPED_HEADERS: &[&str] = &["set-cookie", "content-encoding", "content-length", "transfer-encoding"];
+
+/// 录制回放的模式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VcrMode {
+    /// 正常发送请求
+    #[default]
+    Off,
+    /// 发送真实请求并录制
+    Record,
+    /// 只从 cassette 回放，不发送真实请求
+    Replay,
+}
+
+/// 录制回放的配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VcrConfig {
+    pub mode: VcrMode,
+    /// cassette 文件路径
+    pub cassette: String,
+}
+
+impl Default for VcrConfig {
+    fn default() -> Self {
+        Self {
+            mode: VcrMode::Off,
+            cassette: "cassettes/115.json".to_string(),
+        }
+    }
+}
+
+/// 用来匹配录制的请求特征
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestKey {
+    pub method: String,
+    pub url: String,
+    pub body: String,
+}
+
+impl RequestKey {
+    /// 从请求中提取特征，需在压缩请求体之前调用
+    pub fn from_request(request: &Request) -> Self {
+        let body = request
+            .body()
+            .and_then(|body| body.as_bytes())
+            .map(|bytes| String::from_utf8_lossy(bytes).into_owned())
+            .unwrap_or_default();
+
+        Self {
+            method: request.method().to_string(),
+            url: request.url().to_string(),
+            body,
+        }
+    }
+}
+
+impl std::fmt::Display for RequestKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.method, self.url)
+    }
+}
+
+/// 一次录制的请求和响应
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Interaction {
+    method: String,
+    url: String,
+    #[serde(default)]
+    body: String,
+    status: u16,
+    #[serde(default)]
+    headers: Vec<(String, String)>,
+    response: String,
+}
+
+impl Interaction {
+    fn same_endpoint(&self, key: &RequestKey) -> bool {
+        self.method == key.method && self.url == key.url
+    }
+
+    /// 重建响应，录制中的状态码或 header 不合法时只保留响应体
+    fn to_response(&self) -> Response {
+        let mut builder = http::Response::builder().status(self.status);
+        for (name, value) in &self.headers {
+            builder = builder.header(name, value);
+        }
+        let response = builder
+            .body(self.response.clone())
+            .unwrap_or_else(|_| http::Response::new(self.response.clone()));
+        Response::from(response)
+    }
+}
+
+/// 录制的全部请求，以及回放时每条录制是否已被用过
+#[derive(Default)]
+struct Tape {
+    interactions: Vec<Interaction>,
+    used: Vec<bool>,
+}
+
+/// 一个 cassette 文件
+pub struct Cassette {
+    mode: VcrMode,
+    path: PathBuf,
+    tape: Mutex<Tape>,
+}
+
+impl Cassette {
+    /// 按配置加载 cassette，录制模式下从空白开始录制
+    pub fn load(config: &VcrConfig) -> Self {
+        let path = PathBuf::from(&config.cassette);
+        let interactions: Vec<Interaction> = match config.mode {
+            VcrMode::Replay => match fs::read_to_string(&path) {
+                Ok(text) => serde_json::from_str(&text).unwrap_or_else(|e| {
+                    warn!("cassette 文件 {} 解析失败，无法回放: {}", path.display(), e);
+                    Vec::new()
+                }),
+                Err(e) => {
+                    warn!("读取 cassette 文件 {} 失败，无法回放: {}", path.display(), e);
+                    Vec::new()
+                }
+            },
+            _ => Vec::new(),
+        };
+
+        match config.mode {
+            VcrMode::Off => {}
+            VcrMode::Record => info!("录制模式：请求和响应将写入 {}", path.display()),
+            VcrMode::Replay => info!(
+                "回放模式：从 {} 回放 {} 条录制，不发送真实请求",
+                path.display(),
+                interactions.len()
+            ),
+        }
+
+        let used = vec![false; interactions.len()];
+        Self {
+            mode: config.mode,
+            path,
+            tape: Mutex::new(Tape { interactions, used }),
+        }
+    }
+
+    pub fn mode(&self) -> VcrMode {
+        self.mode
+    }
+
+    /// 找出与请求匹配的录制响应
+    ///
+    /// 依次尝试：未用过且请求体相同的录制、未用过的同一接口录制、用过的同一接口录制。
+    /// 请求体中含有随机内容（如许愿内容）时，按接口和录制顺序回放
+    pub fn replay(&self, key: &RequestKey) -> Option<Response> {
+        let mut tape = self.tape.lock().unwrap_or_else(|e| e.into_inner());
+        let Tape { interactions, used } = &mut *tape;
+
+        let index = (0..interactions.len())
+            .find(|&i| !used[i] && interactions[i].same_endpoint(key) && interactions[i].body == key.body)
+            .or_else(|| (0..interactions.len()).find(|&i| !used[i] && interactions[i].same_endpoint(key)))
+            .or_else(|| (0..interactions.len()).rev().find(|&i| interactions[i].same_endpoint(key)))?;
+
+        used[index] = true;
+        Some(interactions[index].to_response())
+    }
+
+    /// 读取响应并录制，返回内容相同的新响应供后续处理
+    pub async fn record(&self, key: RequestKey, response: Response) -> reqwest::Result<Response> {
+        let status = response.status().as_u16();
+        let headers: Vec<(String, String)> = response
+            .headers()
+            .iter()
+            .filter(|(name, _)| !SKIPPED_HEADERS.contains(&name.as_str()))
+            .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+            .collect();
+        let text = response.text().await?;
+
+        let interaction = Interaction {
+            method: key.method,
+            url: key.url,
+            body: key.body,
+            status,
+            headers,
+            response: text,
+        };
+        let replayed = interaction.to_response();
+
+        {
+            let mut tape = self.tape.lock().unwrap_or_else(|e| e.into_inner());
+            tape.interactions.push(interaction);
+            tape.used.push(false);
+            self.save(&tape.interactions);
+        }
+
+        Ok(replayed)
+    }
+
+    fn save(&self, interactions: &[Interaction]) {
+        if let Some(dir) = self.path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            if let Err(e) = fs::create_dir_all(dir) {
+                warn!("创建 cassette 目录 {} 失败: {}", dir.display(), e);
+                return;
+            }
+        }
+
+        let result = serde_json::to_string_pretty(interactions)
+            .map_err(anyhow::Error::from)
+            .and_then(|json| fs::write(&self.path, json).map_err(anyhow::Error::from));
+        if let Err(e) = result {
+            warn!("保存 cassette 文件 {} 失败: {}", self.path.display(), e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn interaction(url: &str, body: &str, status: u16) -> Interaction {
+        Interaction {
+            method: "POST".to_string(),
+            url: url.to_string(),
+            body: body.to_string(),
+            status,
+            headers: Vec::new(),
+            response: String::new(),
+        }
+    }
+
+    fn key(url: &str, body: &str) -> RequestKey {
+        RequestKey { method: "POST".to_string(), url: url.to_string(), body: body.to_string() }
+    }
+
+    /// 写入临时 cassette 文件并以回放模式加载
+    fn replay_cassette(name: &str, interactions: &[Interaction]) -> Cassette {
+        let path = std::env::temp_dir().join(format!("wish_115_vcr_{}_{}.json", name, std::process::id()));
+        fs::write(&path, serde_json::to_string(interactions).unwrap()).unwrap();
+        let cassette = Cassette::load(&VcrConfig { mode: VcrMode::Replay, cassette: path.to_string_lossy().into_owned() });
+        let _ = fs::remove_file(&path);
+        cassette
+    }
+
+    fn replayed_status(cassette: &Cassette, key: &RequestKey) -> Option<u16> {
+        cassette.replay(key).map(|response| response.status().as_u16())
+    }
+
+    #[test]
+    fn replay_falls_back_through_body_endpoint_and_used_recordings() {
+        let cassette = replay_cassette(
+            "fallback",
+            &[
+                interaction("https://a/wish", "content=1", 201),
+                interaction("https://a/wish", "content=2", 202),
+                interaction("https://a/other", "content=3", 203),
+            ],
+        );
+
+        // 未用过且请求体相同的录制优先，即使排在后面
+        assert_eq!(replayed_status(&cassette, &key("https://a/wish", "content=2")), Some(202));
+        // 请求体不同时回放未用过的同一接口录制
+        assert_eq!(replayed_status(&cassette, &key("https://a/wish", "content=9")), Some(201));
+        // 同一接口的录制都用过后回放最后一条
+        assert_eq!(replayed_status(&cassette, &key("https://a/wish", "content=1")), Some(202));
+        assert_eq!(replayed_status(&cassette, &key("https://a/wish", "content=2")), Some(202));
+    }
+
+    #[test]
+    fn replay_requires_same_method_and_url() {
+        let cassette = replay_cassette("endpoint", &[interaction("https://a/wish", "", 200)]);
+        assert_eq!(replayed_status(&cassette, &key("https://a/other", "")), None);
+        let get = RequestKey { method: "GET".to_string(), ..key("https://a/wish", "") };
+        assert_eq!(replayed_status(&cassette, &get), None);
+        assert_eq!(replayed_status(&cassette, &key("https://a/wish", "")), Some(200));
+    }
+
+    #[test]
+    fn replayed_response_keeps_recorded_headers() {
+        let mut recorded = interaction("https://a/wish", "", 200);
+        recorded.headers = vec![("content-type".to_string(), "application/json".to_string())];
+        let cassette = replay_cassette("headers", &[recorded]);
+        let response = cassette.replay(&key("https://a/wish", "")).unwrap();
+        assert_eq!(response.headers()["content-type"], "application/json");
+    }
+}